@@ -0,0 +1,315 @@
+use std::fs;
+use std::path::Path;
+use serde_json::Value;
+use fhir_store::error::{Result, Error};
+
+const BASE_URL: &str = "http://hl7.org/fhir/StructureDefinition/";
+const FHIR_TYPE_EXTENSION: &str = "http://hl7.org/fhir/StructureDefinition/structuredefinition-fhir-type";
+
+/// Elements every element may have, handled by [`fhir_store::datatypes::id::get_expects_in`]
+/// instead of the tables of the single types.
+const GENERIC_ELEMENTS: [&str; 3] = ["id", "extension", "modifierExtension"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    Primitive,
+    Datatype,
+    Resource,
+    Backbone
+}
+
+/// Type of an element, a FHIR type like 'HumanName' or the path of the backbone
+/// element defining it, e.g. 'Patient.contact'.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ElementType {
+    Named(String),
+    Backbone(String)
+}
+
+#[derive(Debug, Clone)]
+pub struct Element {
+    /// The json key, without the '[x]' of a choice element.
+    pub name: String,
+    pub types: Vec<ElementType>,
+    pub choice: bool,
+    pub min: u32,
+    /// Either a number or '*'.
+    pub max: String
+}
+
+impl Element {
+    pub fn is_list(&self) -> bool {
+        self.max != "0" && self.max != "1"
+    }
+}
+
+/// A primitive or complex datatype, a resource or a backbone element. Backbone
+/// elements are named by their path, e.g. 'Patient.contact'.
+#[derive(Debug, Clone)]
+pub struct TypeDef {
+    pub name: String,
+    pub kind: Kind,
+    pub elements: Vec<Element>
+}
+
+/// Everything read from a package of StructureDefinitions.
+#[derive(Debug, Default)]
+pub struct Definitions {
+    /// In the order of the package, every type is followed by its backbone elements.
+    pub types: Vec<TypeDef>,
+    /// Elements all resources share, from 'DomainResource'.
+    pub domain_resource: Vec<Element>,
+    /// Paths of elements the store cannot hold, e.g. contained resources.
+    pub skipped: Vec<String>
+}
+
+impl Definitions {
+    pub fn get(&self, name: &str) -> Option<&TypeDef> {
+        self.types.iter().find(|def| def.name == name)
+    }
+}
+
+/// Reads all StructureDefinitions in 'dir', e.g. the 'package' folder of hl7.fhir.r5.core.
+pub fn read_package(dir: &Path) -> Result<Definitions> {
+    from_structure_definitions(&read_documents(dir)?)
+}
+
+/// Reads the StructureDefinitions in 'dir' in the order of their file names.
+pub fn read_documents(dir: &Path) -> Result<Vec<Value>> {
+    let mut paths: Vec<_> = fs::read_dir(dir)
+        .map_err(|err| Error::Io(format!("{}: {err}", dir.display())))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    paths.sort();
+    let mut documents = Vec::with_capacity(paths.len());
+    for path in paths.iter() {
+        let data = fs::read(path).map_err(|err| Error::Io(format!("{}: {err}", path.display())))?;
+        let document: Value = serde_json::from_slice(&data)
+            .map_err(|err| Error::Custom(format!("{}: {err}", path.display())))?;
+        if document["resourceType"] == "StructureDefinition" {
+            documents.push(document);
+        }
+    }
+    Ok(documents)
+}
+
+/// Collects the types of the core StructureDefinitions in 'documents', profiles and
+/// abstract types are left out.
+pub fn from_structure_definitions(documents: &[Value]) -> Result<Definitions> {
+    let mut definitions = Definitions::default();
+    for sd in documents.iter() {
+        let name = text(sd, "name")?;
+        if name == "DomainResource" {
+            let (mut defs, skipped) = read_elements(sd, name, Kind::Resource, true)?;
+            definitions.domain_resource = defs.remove(0).elements;
+            definitions.skipped.extend(skipped);
+            continue;
+        }
+        let kind = match text(sd, "kind")? {
+            "primitive-type" => Kind::Primitive,
+            "complex-type" => Kind::Datatype,
+            "resource" => Kind::Resource,
+            _ => continue
+        };
+        let is_core = sd["url"].as_str() == Some(&format!("{BASE_URL}{name}"));
+        let derivation = sd["derivation"].as_str().unwrap_or("specialization");
+        let is_extension = sd["type"] == "Extension" && name != "Extension";
+        let is_profile = kind == Kind::Resource && derivation != "specialization";
+        if sd["abstract"] == true || !is_core || is_extension || is_profile {
+            continue;
+        }
+        if kind == Kind::Primitive {
+            definitions.types.push(TypeDef { name: name.to_string(), kind, elements: Vec::new() });
+            continue;
+        }
+        let (defs, skipped) = read_elements(sd, name, kind, false)?;
+        definitions.types.extend(defs);
+        definitions.skipped.extend(skipped);
+    }
+    Ok(definitions)
+}
+
+// Returns the type defined by 'sd', followed by its backbone elements, and the
+// paths of the elements left out. Elements inherited from Resource and DomainResource
+// are only kept, if 'with_inherited' is set.
+fn read_elements(sd: &Value, name: &str, kind: Kind, with_inherited: bool) -> Result<(Vec<TypeDef>, Vec<String>)> {
+    let elements = sd["snapshot"]["element"].as_array()
+        .ok_or_else(|| Error::Expected("snapshot.element".to_string(), name.to_string()))?;
+    let mut defs = vec![TypeDef { name: name.to_string(), kind, elements: Vec::new() }];
+    let mut skipped = Vec::new();
+    for element in elements.iter().skip(1) {
+        let path = rebase(text(element, "path")?, name);
+        let Some((parent, segment)) = path.rsplit_once('.') else {
+            continue
+        };
+        let path = path.as_str();
+        // elements a constraint removes, e.g. the comparator of SimpleQuantity
+        if element["max"] == "0" {
+            continue;
+        }
+        // children of skipped elements or of primitives are left out with them
+        let Some(index) = defs.iter().position(|def| def.name == parent) else {
+            continue
+        };
+        let base = element["base"]["path"].as_str().unwrap_or_default();
+        let inherited = base.starts_with("Resource.") || base.starts_with("DomainResource.");
+        if (kind == Kind::Resource && index == 0 && inherited && !with_inherited)
+            || ((index > 0 || kind != Kind::Resource) && GENERIC_ELEMENTS.contains(&segment)) {
+            continue;
+        }
+        let choice = segment.ends_with("[x]");
+        let types = match element["contentReference"].as_str() {
+            Some(reference) => {
+                let target = reference.rsplit_once('#').map(|(_, target)| target).unwrap_or(reference);
+                vec![ElementType::Backbone(rebase(target, name))]
+            },
+            None => element_types(element)?
+        };
+        if types.iter().any(|typ| matches!(typ, ElementType::Named(name) if name == "Resource")) {
+            skipped.push(path.to_string());
+            continue;
+        }
+        let types = match types.first() {
+            Some(ElementType::Named(typ)) if !choice && (typ == "BackboneElement" || typ == "Element") => {
+                defs.push(TypeDef { name: path.to_string(), kind: Kind::Backbone, elements: Vec::new() });
+                vec![ElementType::Backbone(path.to_string())]
+            },
+            _ => types
+        };
+        defs[index].elements.push(Element {
+            name: segment.trim_end_matches("[x]").to_string(),
+            types,
+            choice,
+            min: element["min"].as_u64().unwrap_or(0) as u32,
+            max: element["max"].as_str().unwrap_or("1").to_string()
+        });
+    }
+    Ok((defs, skipped))
+}
+
+// Paths of types constraining another one start with the name of the base type, e.g.
+// 'Quantity.value' for Age, and are named by the constraining type instead.
+fn rebase(path: &str, name: &str) -> String {
+    match path.split_once('.') {
+        Some((_, rest)) => format!("{name}.{rest}"),
+        None => name.to_string()
+    }
+}
+
+fn element_types(element: &Value) -> Result<Vec<ElementType>> {
+    let mut types = Vec::new();
+    for typ in element["type"].as_array().map(|types| types.as_slice()).unwrap_or_default() {
+        let code = text(typ, "code")?;
+        let name = match code.strip_prefix("http://hl7.org/fhirpath/System.") {
+            Some(system) => fhir_type(typ).unwrap_or_else(|| system_type(system)).to_string(),
+            None => match profiled_quantity(typ) {
+                Some(profile) if code == "Quantity" => profile.to_string(),
+                _ => code.to_string()
+            }
+        };
+        types.push(ElementType::Named(name));
+    }
+    Ok(types)
+}
+
+// The FHIR type of an element typed with a FHIRPath system type, e.g. 'id' for Resource.id.
+fn fhir_type(typ: &Value) -> Option<&str> {
+    typ["extension"].as_array()?
+        .iter()
+        .find(|extension| extension["url"] == FHIR_TYPE_EXTENSION)
+        .and_then(|extension| extension["valueUrl"].as_str().or(extension["valueUri"].as_str()))
+}
+
+fn system_type(system: &str) -> &'static str {
+    match system {
+        "Boolean" => "boolean",
+        "Integer" => "integer",
+        "Decimal" => "decimal",
+        "Date" => "date",
+        "DateTime" => "dateTime",
+        "Time" => "time",
+        _ => "string"
+    }
+}
+
+// SimpleQuantity and MoneyQuantity are referred to as Quantity with a profile.
+fn profiled_quantity(typ: &Value) -> Option<&'static str> {
+    let profiles = typ["profile"].as_array()?;
+    ["SimpleQuantity", "MoneyQuantity"].into_iter()
+        .find(|name| profiles.iter().any(|profile| profile.as_str() == Some(&format!("{BASE_URL}{name}"))))
+}
+
+fn text<'v>(value: &'v Value, key: &str) -> Result<&'v str> {
+    value[key].as_str().ok_or_else(|| Error::Expected(key.to_string(), value.to_string()))
+}
+
+
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+
+    pub const VENDORED: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/structuredefinitions");
+    const FIXTURE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../test_data/structuredefinitions");
+
+    fn fixture() -> Definitions {
+        read_package(Path::new(FIXTURE)).unwrap()
+    }
+
+    /// The vendored package with the types 'extra' of the fixture added, e.g. 'BodyStructure',
+    /// which are new to the store.
+    pub fn vendored_with(extra: &[&str]) -> Vec<Value> {
+        let mut documents = read_documents(Path::new(VENDORED)).unwrap();
+        documents.extend(read_documents(Path::new(FIXTURE)).unwrap()
+            .into_iter()
+            .filter(|document| extra.iter().any(|name| document["name"] == *name)));
+        documents
+    }
+
+    #[test]
+    fn definitions_from_package() {
+        let definitions = fixture();
+        assert_eq!(definitions.get("dateTime").unwrap().kind, Kind::Primitive);
+        // profiles and extension definitions are left out
+        assert!(definitions.get("observation-vitalsigns").is_none());
+        assert!(definitions.get("birthTime").is_none());
+
+        let patient = definitions.get("Patient").unwrap();
+        assert_eq!(patient.kind, Kind::Resource);
+        assert!(patient.elements.iter().all(|element| element.name != "id" && element.name != "text"));
+        let deceased = patient.elements.iter().find(|element| element.name == "deceased").unwrap();
+        assert!(deceased.choice && !deceased.is_list());
+        assert_eq!(deceased.types, vec![ElementType::Named("boolean".to_string()), ElementType::Named("dateTime".to_string())]);
+        let contact = patient.elements.iter().find(|element| element.name == "contact").unwrap();
+        assert_eq!(contact.types, vec![ElementType::Backbone("Patient.contact".to_string())]);
+        assert!(contact.is_list());
+        let backbone = definitions.get("Patient.contact").unwrap();
+        assert_eq!(backbone.kind, Kind::Backbone);
+        assert!(backbone.elements.iter().all(|element| element.name != "modifierExtension"));
+
+        let excluded = definitions.get("BodyStructure").unwrap().elements.iter().find(|element| element.name == "excludedStructure").unwrap();
+        assert_eq!(excluded.types, vec![ElementType::Backbone("BodyStructure.includedStructure".to_string())]);
+        let numerator = &definitions.get("RatioRange").unwrap().elements[0];
+        assert_eq!(numerator.types, vec![ElementType::Named("SimpleQuantity".to_string())]);
+
+        let names: Vec<&str> = definitions.domain_resource.iter().map(|element| element.name.as_str()).collect();
+        assert_eq!(names, ["id", "meta", "implicitRules", "language", "text", "extension", "modifierExtension"]);
+        assert_eq!(definitions.domain_resource[0].types, vec![ElementType::Named("id".to_string())]);
+        assert_eq!(definitions.skipped, ["DomainResource.contained"]);
+    }
+
+    #[test]
+    fn definitions_constrained_types() {
+        let definitions = read_package(Path::new(VENDORED)).unwrap();
+        // named by the constraining type, not by the base type in their paths
+        let age = definitions.get("Age").unwrap();
+        assert_eq!(age.kind, Kind::Datatype);
+        assert!(age.elements.iter().any(|element| element.name == "comparator"));
+        assert_eq!(definitions.types.iter().filter(|def| def.name == "Quantity").count(), 1);
+        // elements removed by the constraint are left out
+        let simple = definitions.get("SimpleQuantity").unwrap();
+        assert!(simple.elements.iter().all(|element| element.name != "comparator"));
+        assert_eq!(simple.elements.len(), 4);
+    }
+}
@@ -0,0 +1,298 @@
+use std::fmt::{Result, Write};
+use fhir_store::datatypes::id::ID;
+use crate::numbering::{GENERAL_PURPOSE, GENERAL_PURPOSE_LISTS, PRIMITIVE_LISTS};
+use crate::tables::{ChoiceContext, Table, Tables};
+
+const HEADER: &str = "// Generated by fhir_codegen from the FHIR StructureDefinitions, do not edit.\n\
+    // Regenerate with `cargo run -p fhir_codegen -- codegen/structuredefinitions`.\n";
+
+/// The `ID` enum, its tables and the functions picking them, included by
+/// `src/datatypes/id.rs`.
+pub fn id_tables(tables: &Tables) -> String {
+    let mut out = String::new();
+    write_id_tables(&mut out, tables).expect("writing to a String");
+    out
+}
+
+/// The `ResourceId` enum, included by `src/resourcetypes/mod.rs`.
+pub fn resource_ids(tables: &Tables) -> String {
+    let mut out = String::new();
+    write_resource_ids(&mut out, tables).expect("writing to a String");
+    out
+}
+
+fn write_id_tables(out: &mut String, tables: &Tables) -> Result {
+    let ids = ids(tables);
+    let keys = keys(tables);
+    writeln!(out, "{HEADER}")?;
+
+    writeln!(out, "#[derive(Clone, Copy, Debug, PartialEq, Eq)]")?;
+    writeln!(out, "#[repr(u16)]")?;
+    writeln!(out, "pub enum ID {{")?;
+    for (raw, name) in ids.iter() {
+        writeln!(out, "    {name} = {raw},")?;
+    }
+    for (raw, _, variant) in keys.iter() {
+        writeln!(out, "    {variant} = {raw},")?;
+    }
+    writeln!(out, "}}\n")?;
+
+    let lines = ids.iter().filter(|(raw, _)| *raw != ID::EMPTY as u16)
+        .map(|(raw, name)| (format!("{raw}u16 => ID::{name},"), format!("[{},{}]", raw >> 8, raw & 0xFF)))
+        .chain(keys.iter().map(|(raw, _, variant)| (format!("{raw}u16 => ID::{variant},"), format!("[{},{}]", raw >> 8, raw & 0xFF))));
+    write_static(out, None, "IDS: phf::Map<u16, ID> = phf_map!", lines.collect())?;
+
+    let lines = keys.iter().map(|(_, name, variant)| (format!("\"{}\" => ID::{variant},", name.to_lowercase()), String::new()));
+    write_static(out, None, "KEYS: phf::Map<&'static str, ID> = phf_map!", lines.collect())?;
+
+    let lines = keys.iter().map(|(raw, name, _)| (format!("{raw}u16 => \"{name}\","), String::new()));
+    write_static(out, None, "KEY_NAMES: phf::Map<u16, &'static str> = phf_map!", lines.collect())?;
+
+    let lines = tables.suffixes.iter().map(|(raw, suffix)| (format!("{raw}u16 => \"{suffix}\","), String::new()));
+    write_static(out, Some("///Mapping of [`ID`]s to the suffix of a choice type key, e.g. `deceased` + `Boolean`.\n\
+        ///A SimpleQuantity is written as `Quantity`."), "TYPE_SUFFIXES: phf::Map<u16, &'static str> = phf_map!", lines.collect())?;
+
+    let lines = tables.expects.iter().map(|(raw, expects)| {
+        let name = keys.iter().find(|(key, _, _)| key == raw).map(|(_, name, _)| name.as_str()).unwrap_or_default();
+        (format!("{raw}u16 => ID::{expects},"), name.to_string())
+    });
+    write_static(out, Some("///The [`ID`] a key was first given: the keys of the first Patient table and the elements\n\
+        ///of all resources. Elements of datatypes are only in the table of their type."), "EXPECTS: phf::Map<u16, ID> = phf_map!", lines.collect())?;
+
+    let lines = ids.iter()
+        .filter(|(raw, _)| GENERAL_PURPOSE.contains(raw) || GENERAL_PURPOSE_LISTS.contains(raw))
+        .map(|(raw, name)| (format!("{raw}u16,"), name.clone()));
+    write_static(out, None, "HAS_SUBS: phf::Set<u16> = phf_set!", lines.collect())?;
+
+    for table in tables.types.iter().chain(tables.resources.iter()) {
+        write_table(out, None, table)?;
+    }
+    write_table(out, Some("///Elements every resource has, see [`get_resource_expects`]."), &tables.domain_resource)?;
+
+    let lines = ids.iter()
+        .filter(|(raw, _)| PRIMITIVE_LISTS.contains(raw) || GENERAL_PURPOSE_LISTS.contains(raw))
+        .map(|(raw, name)| (format!("{raw}u16 => ID::{},", &name[1..]), name.clone()));
+    write_static(out, Some("///Mapping of list [`ID`]s to the [`ID`] of their items."), "LIST_ITEMS: phf::Map<u16, ID> = phf_map!", lines.collect())?;
+
+    write_choices(out, tables, &keys)?;
+    write_is_backbone(out, tables, &ids)?;
+    write_get_resource_expects(out, tables)?;
+    write_get_from_sub(out, tables)
+}
+
+// A phf map or set, with a comment after every line if it is not empty.
+fn write_static(out: &mut String, doc: Option<&str>, declaration: &str, lines: Vec<(String, String)>) -> Result {
+    if let Some(doc) = doc {
+        writeln!(out, "{doc}")?;
+    }
+    writeln!(out, "static {declaration} {{")?;
+    let width = lines.iter().map(|(line, _)| line.len()).max().unwrap_or_default();
+    for (line, comment) in lines.iter() {
+        match comment.is_empty() {
+            true => writeln!(out, "    {line}")?,
+            false => writeln!(out, "    {line:<width$} //{comment}")?
+        }
+    }
+    writeln!(out, "}};\n")
+}
+
+fn write_table(out: &mut String, doc: Option<&str>, table: &Table) -> Result {
+    let lines = table.rows.iter()
+        .map(|row| (format!("{}u16 => ID::{},", row.key, row.expects), row.comment.clone()))
+        .collect();
+    write_static(out, doc, &format!("{}_EXPECTS: phf::Map<u16, ID> = phf_map!", table.name), lines)
+}
+
+fn write_choices(out: &mut String, tables: &Tables, keys: &[(u16, String, String)]) -> Result {
+    writeln!(out, "/// The object a choice element is part of, see [`choices`].")?;
+    writeln!(out, "#[derive(Debug, Clone, PartialEq, Eq)]")?;
+    writeln!(out, "enum ChoiceContext {{")?;
+    writeln!(out, "    /// The resource itself.")?;
+    writeln!(out, "    Resource(ResourceId),")?;
+    writeln!(out, "    /// An object of a complex type, items of a list by the type of the items.")?;
+    writeln!(out, "    Type(ID)")?;
+    writeln!(out, "}}\n")?;
+    writeln!(out, "///Types allowed for a choice element (`[x]`) by the object it is part of and its key,")?;
+    writeln!(out, "///see [`get_choice`].")?;
+    writeln!(out, "fn choices(context: &ChoiceContext, key: ID) -> Option<&'static [ID]> {{")?;
+    writeln!(out, "    use ChoiceContext::{{Resource, Type}};")?;
+    writeln!(out, "    let types: &'static [ID] = match (context, key) {{")?;
+    let lines: Vec<(String, String)> = tables.choices.iter().map(|choice| {
+        let context = match &choice.context {
+            ChoiceContext::Resource(name) => format!("Resource(ResourceId::{name})"),
+            ChoiceContext::Type(name) => format!("Type(ID::{name})")
+        };
+        let key = keys.iter().find(|(raw, _, _)| *raw == choice.key).map(|(_, _, variant)| variant.as_str()).unwrap_or_default();
+        let types: Vec<String> = choice.types.iter().map(|typ| format!("ID::{typ}")).collect();
+        (format!("({context}, ID::{key})"), format!("&[{}],", types.join(", ")))
+    }).collect();
+    let width = lines.iter().map(|(pattern, _)| pattern.len()).max().unwrap_or_default();
+    for (pattern, types) in lines.iter() {
+        writeln!(out, "        {pattern:<width$} => {types}")?;
+    }
+    writeln!(out, "        _ => return None")?;
+    writeln!(out, "    }};")?;
+    writeln!(out, "    Some(types)\n}}\n")
+}
+
+fn write_is_backbone(out: &mut String, tables: &Tables, ids: &[(u16, String)]) -> Result {
+    writeln!(out, "/// Returns if 'id' is a backbone element (or a list of them), which may carry `modifierExtension`s.")?;
+    writeln!(out, "pub fn is_backbone(id: ID) -> bool {{")?;
+    writeln!(out, "    matches!(id,")?;
+    let backbones: Vec<String> = ids.iter()
+        .filter(|(_, name)| tables.backbones.contains(name))
+        .map(|(_, name)| match tables.numbering.ids.contains_key(&format!("L{name}")) {
+            true => format!("ID::{name} | ID::L{name}"),
+            false => format!("ID::{name}")
+        })
+        .collect();
+    writeln!(out, "        {}", backbones.join(" |\n        "))?;
+    writeln!(out, "    )\n}}\n")
+}
+
+fn write_get_resource_expects(out: &mut String, tables: &Tables) -> Result {
+    writeln!(out, "/// Returns the expected [`ID`] of 'key' on the level of the resource: the elements of")?;
+    writeln!(out, "/// its own table and the ones common to all resources. A resource of unknown type is")?;
+    writeln!(out, "/// read as Patient.")?;
+    writeln!(out, "pub fn get_resource_expects(resource: &ResourceId, key: ID) -> Option<ID> {{")?;
+    writeln!(out, "    let table = match resource {{")?;
+    for (name, _) in resource_names(tables).iter().filter(|(name, _)| name != "Empty") {
+        let pattern = match name.as_str() {
+            "Patient" => "ResourceId::Empty | ResourceId::Patient".to_string(),
+            _ => format!("ResourceId::{name}")
+        };
+        writeln!(out, "        {pattern} => &{}_EXPECTS,", name.to_uppercase())?;
+    }
+    writeln!(out, "    }};")?;
+    writeln!(out, "    table.get(&(key as u16))")?;
+    writeln!(out, "        .or_else(|| DOMAINRESOURCE_EXPECTS.get(&(key as u16)))")?;
+    writeln!(out, "        .cloned()\n}}\n")
+}
+
+fn write_get_from_sub(out: &mut String, tables: &Tables) -> Result {
+    writeln!(out, "pub fn get_from_sub<I: Into<u16>>(id: I, expects_for: u16) -> Option<ID> {{")?;
+    writeln!(out, "    match id.into() {{")?;
+    for table in tables.types.iter() {
+        let numbers: Vec<String> = table.numbers.iter().map(|raw| format!("{raw}u16")).collect();
+        writeln!(out, "        {} => {{", numbers.join(" | "))?;
+        writeln!(out, "            {}_EXPECTS.get(&expects_for).cloned()", table.name)?;
+        writeln!(out, "        }},")?;
+    }
+    writeln!(out, "        _ => None")?;
+    writeln!(out, "    }}\n}}")
+}
+
+fn write_resource_ids(out: &mut String, tables: &Tables) -> Result {
+    let resources = resource_names(tables);
+    writeln!(out, "{HEADER}")?;
+    writeln!(out, "use crate::error::{{Result, Error}};\n")?;
+    writeln!(out, "#[derive(Debug, Clone, PartialEq, Eq)]")?;
+    writeln!(out, "#[repr(u16)]")?;
+    writeln!(out, "pub enum ResourceId {{")?;
+    let variants: Vec<String> = resources.iter().map(|(name, raw)| format!("    {name} = {raw}")).collect();
+    writeln!(out, "{}", variants.join(",\n"))?;
+    writeln!(out, "}}\n\n")?;
+
+    writeln!(out, "impl TryFrom<&str> for ResourceId {{")?;
+    writeln!(out, "    type Error = Error;")?;
+    writeln!(out, "    fn try_from(value: &str) -> Result<Self> {{")?;
+    writeln!(out, "        match value.to_ascii_lowercase().as_str() {{")?;
+    for (name, _) in resources.iter().filter(|(name, _)| name != "Empty") {
+        writeln!(out, "            \"{}\" => Ok(ResourceId::{name}),", name.to_lowercase())?;
+    }
+    writeln!(out, "            _ => Err(Error::UnknownResourceStr(value.to_string()))")?;
+    writeln!(out, "        }}\n    }}\n}}\n")?;
+
+    writeln!(out, "impl TryFrom<u16> for ResourceId {{")?;
+    writeln!(out, "    type Error = Error;")?;
+    writeln!(out, "    fn try_from(value: u16) -> Result<Self> {{")?;
+    writeln!(out, "        match value {{")?;
+    for (name, raw) in resources.iter() {
+        writeln!(out, "            {raw} => Ok(ResourceId::{name}),")?;
+    }
+    writeln!(out, "            _ => Err(Error::UnknownResourceId(value))")?;
+    writeln!(out, "        }}\n    }}\n}}\n")?;
+
+    writeln!(out, "impl From<ResourceId> for u16 {{")?;
+    writeln!(out, "    fn from(id: ResourceId) -> u16 {{")?;
+    writeln!(out, "        id as u16")?;
+    writeln!(out, "    }}\n}}")
+}
+
+// Numbers and names of the types, in order of their numbers.
+fn ids(tables: &Tables) -> Vec<(u16, String)> {
+    let mut ids: Vec<(u16, String)> = tables.numbering.ids.iter().map(|(name, raw)| (*raw, name.clone())).collect();
+    ids.sort();
+    ids
+}
+
+// Numbers, json names and names of the ID variants of the keys, in order of their numbers.
+// Keys new to the store are named like their json key, e.g. 'BirthDate' for 'birthDate'.
+fn keys(tables: &Tables) -> Vec<(u16, String, String)> {
+    let mut keys: Vec<(u16, String, String)> = tables.numbering.keys.iter()
+        .map(|(name, raw)| {
+            let variant = match ID::try_from(*raw) {
+                Ok(id) => format!("{id:?}"),
+                Err(_) => name[..1].to_uppercase() + &name[1..]
+            };
+            (*raw, name.clone(), variant)
+        })
+        .collect();
+    keys.sort();
+    keys
+}
+
+fn resource_names(tables: &Tables) -> Vec<(String, u16)> {
+    let mut resources: Vec<(String, u16)> = tables.numbering.resources.iter().map(|(name, raw)| (name.clone(), *raw)).collect();
+    resources.sort_by_key(|(_, raw)| *raw);
+    resources
+}
+
+
+
+#[cfg(test)]
+mod test {
+    use crate::definitions::from_structure_definitions;
+    use crate::definitions::test::vendored_with;
+    use crate::numbering::Numbering;
+    use super::*;
+
+    fn fixture() -> Tables {
+        let definitions = from_structure_definitions(&vendored_with(&["BodyStructure", "RatioRange"])).unwrap();
+        Tables::build(&definitions, Numbering::from_store()).unwrap()
+    }
+
+    #[test]
+    fn emit_id_tables() {
+        let tables = fixture();
+        let output = id_tables(&tables);
+        assert!(output.starts_with(HEADER));
+        assert!(output.contains("    HUMANNAME = 513,\n"));
+        assert!(output.contains("    BirthDate = 4123,\n"));
+        assert!(output.contains(&format!("    IncludedStructure = {},\n", tables.numbering.keys["includedStructure"])));
+        assert!(!output.contains("=> ID::EMPTY"));
+        assert!(output.contains("\"birthdate\" => ID::BirthDate,"));
+        assert!(output.contains("(Resource(ResourceId::Patient), ID::Deceased)"));
+        assert!(!output.contains("Type(ID::L"));
+        assert!(output.contains("2048u16 => ID::HUMANNAME,"));
+        assert!(output.contains("static BACKBONECONTACT_EXPECTS: phf::Map<u16, ID> = phf_map! {\n    4150u16 => ID::LCODABLECONCEPT, //relationship [CodeableConcept] 0..*\n"));
+        assert!(output.contains("static BODYSTRUCTURE_EXPECTS"));
+        assert!(output.contains("static PATIENT_EXPECTS"));
+        assert!(output.contains("ResourceId::Empty | ResourceId::Patient => &PATIENT_EXPECTS,"));
+        assert!(output.contains("        522u16 | 2054u16 => {\n            BACKBONECONTACT_EXPECTS.get(&expects_for).cloned()\n"));
+        assert!(output.contains("ResourceId::BodyStructure => &BODYSTRUCTURE_EXPECTS,"));
+        assert!(output.contains("ResourceId::Endpoint => &ENDPOINT_EXPECTS,"));
+        assert!(output.contains("ID::BACKBONEINCLUDEDSTRUCTURE | ID::LBACKBONEINCLUDEDSTRUCTURE"));
+        assert!(output.contains("ID::DOSAGE | ID::LDOSAGE"));
+    }
+
+    #[test]
+    fn emit_resource_ids() {
+        let output = resource_ids(&fixture());
+        assert!(output.contains("    Empty = 0,\n    Patient = 1,\n"));
+        assert!(output.contains("    BodyStructure = 13\n}"));
+        assert!(output.contains("\"bodystructure\" => Ok(ResourceId::BodyStructure),"));
+        assert!(output.contains("13 => Ok(ResourceId::BodyStructure),"));
+        assert!(!output.contains("\"empty\""));
+    }
+}
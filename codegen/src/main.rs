@@ -0,0 +1,58 @@
+mod definitions;
+mod numbering;
+mod tables;
+mod emit;
+
+use std::fs;
+use std::path::PathBuf;
+use fhir_store::error::{Result, Error};
+use numbering::Numbering;
+use tables::Tables;
+
+const USAGE: &str = "usage: fhir_codegen <package dir> [output dir]";
+
+/// Where the store includes the generated files from.
+const GENERATED: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../src/generated");
+
+/// Reads the StructureDefinitions of a FHIR package, e.g. 'codegen/structuredefinitions'
+/// or the 'package' folder of hl7.fhir.r5.core, and writes 'id_tables.rs' and
+/// 'resource_ids.rs' to the output directory, 'src/generated' of the store by default.
+/// Types, keys and resources the store already knows keep their numbers, new ones are
+/// appended.
+fn main() -> Result<()> {
+    let mut args = std::env::args().skip(1);
+    let package = args.next().map(PathBuf::from).ok_or_else(|| Error::Custom(USAGE.to_string()))?;
+    let out = PathBuf::from(args.next().unwrap_or_else(|| GENERATED.to_string()));
+
+    let definitions = definitions::read_package(&package)?;
+    let tables = Tables::build(&definitions, Numbering::from_store())?;
+    fs::create_dir_all(&out).map_err(|err| Error::Io(format!("{}: {err}", out.display())))?;
+    for (file, content) in [("id_tables.rs", emit::id_tables(&tables)), ("resource_ids.rs", emit::resource_ids(&tables))] {
+        let path = out.join(file);
+        fs::write(&path, content).map_err(|err| Error::Io(format!("{}: {err}", path.display())))?;
+    }
+    for path in tables.skipped.iter() {
+        eprintln!("skipped {path}, the store cannot hold it");
+    }
+    println!("{} types, {} resources, {} keys written to {}",
+        tables.types.len(), tables.resources.len(), tables.numbering.keys.len(), out.display());
+    Ok(())
+}
+
+
+
+#[cfg(test)]
+mod test {
+    use std::path::Path;
+    use super::*;
+
+    #[test]
+    fn generated_files_current() {
+        let definitions = definitions::read_package(Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/structuredefinitions"))).unwrap();
+        let tables = Tables::build(&definitions, Numbering::from_store()).unwrap();
+        assert!(emit::id_tables(&tables) == include_str!("../../src/generated/id_tables.rs"),
+            "src/generated/id_tables.rs is out of date, regenerate it");
+        assert!(emit::resource_ids(&tables) == include_str!("../../src/generated/resource_ids.rs"),
+            "src/generated/resource_ids.rs is out of date, regenerate it");
+    }
+}
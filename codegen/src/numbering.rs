@@ -0,0 +1,160 @@
+use std::collections::BTreeMap;
+use std::ops::Range;
+use fhir_store::datatypes::id::{ID, get_expects_in, get_key_id, get_key_name, get_type_suffix};
+use fhir_store::error::{Result, Error};
+use fhir_store::resourcetypes::ResourceId;
+
+/// Ranges of the [`ID`] numbers, see the constants in `datatypes/id.rs`.
+pub const PRIMITIVES: Range<u16> = 1..21;
+pub const PRIMITIVE_LISTS: Range<u16> = 22..256;
+pub const PRIMITIVE_EXT: Range<u16> = 256..512;
+pub const GENERAL_PURPOSE: Range<u16> = 512..2047;
+pub const GENERAL_PURPOSE_LISTS: Range<u16> = 2048..4096;
+pub const KEYS: Range<u16> = 4096..u16::MAX;
+
+/// The numbers of all [`ID`]s and [`ResourceId`]s. Stored resources carry these numbers,
+/// so the ones the store already uses never change, new ones are appended to their range.
+#[derive(Debug, Clone, Default)]
+pub struct Numbering {
+    /// Types by the name of their [`ID`] variant, e.g. 'HUMANNAME'.
+    pub ids: BTreeMap<String, u16>,
+    /// Keys by their json name, e.g. 'birthDate'.
+    pub keys: BTreeMap<String, u16>,
+    /// Resources by their name, e.g. 'Patient'.
+    pub resources: BTreeMap<String, u16>
+}
+
+impl Numbering {
+    /// The numbering of the tables the store is built with.
+    pub fn from_store() -> Numbering {
+        let mut numbering = Numbering::default();
+        numbering.ids.insert(format!("{:?}", ID::EMPTY), ID::EMPTY as u16);
+        for raw in 1..=u16::MAX {
+            if KEYS.contains(&raw) {
+                if let Some(name) = get_key_name(raw) {
+                    numbering.keys.insert(name.to_string(), raw);
+                }
+            } else if let Ok(id) = ID::try_from(raw) {
+                numbering.ids.insert(format!("{id:?}"), raw);
+            }
+        }
+        for raw in 0..=u16::MAX {
+            match ResourceId::try_from(raw) {
+                Ok(resource) => numbering.resources.insert(format!("{resource:?}"), raw),
+                Err(_) => break
+            };
+        }
+        numbering
+    }
+
+    /// Returns the number of the type 'name', a new one in 'range' if it has none yet.
+    pub fn id(&mut self, name: &str, range: Range<u16>) -> Result<u16> {
+        if let Some(raw) = self.ids.get(name) {
+            return Ok(*raw)
+        }
+        let raw = next(self.ids.values(), range)
+            .ok_or_else(|| Error::Custom(format!("no number left for {name}")))?;
+        self.ids.insert(name.to_string(), raw);
+        Ok(raw)
+    }
+
+    /// Returns the number of the list of type 'name', e.g. 'LHUMANNAME' for 'HUMANNAME'.
+    pub fn list(&mut self, name: &str) -> Result<u16> {
+        let raw = *self.ids.get(name)
+            .ok_or_else(|| Error::Custom(format!("list of unknown type {name}")))?;
+        let range = if PRIMITIVES.contains(&raw) || PRIMITIVE_EXT.contains(&raw) {
+            PRIMITIVE_LISTS
+        } else {
+            GENERAL_PURPOSE_LISTS
+        };
+        self.id(&format!("L{name}"), range)
+    }
+
+    /// Returns the number of the json key 'name'. Keys are looked up case insensitive
+    /// by the store, so keys differing in case only share their number.
+    pub fn key(&mut self, name: &str) -> Result<u16> {
+        if let Some((_, raw)) = self.keys.iter().find(|(key, _)| key.eq_ignore_ascii_case(name)) {
+            return Ok(*raw)
+        }
+        let raw = next(self.keys.values(), KEYS)
+            .ok_or_else(|| Error::Custom(format!("no number left for key {name}")))?;
+        self.keys.insert(name.to_string(), raw);
+        Ok(raw)
+    }
+
+    pub fn resource(&mut self, name: &str) -> u16 {
+        if let Some(raw) = self.resources.get(name) {
+            return *raw
+        }
+        let raw = self.resources.values().max().map(|raw| raw + 1).unwrap_or_default();
+        self.resources.insert(name.to_string(), raw);
+        raw
+    }
+
+    /// Returns the name of the [`ID`] of the FHIR type 'typ', the one the store already uses
+    /// if there is one, e.g. 'CODABLECONCEPT' for 'CodeableConcept'.
+    pub fn type_name(&self, typ: &str) -> String {
+        let upper = typ.to_uppercase();
+        if self.ids.contains_key(&upper) {
+            return upper
+        }
+        self.ids.iter()
+            .find(|(_, raw)| get_type_suffix(**raw) == Some(typ))
+            .map(|(name, _)| name.clone())
+            .unwrap_or(upper)
+    }
+
+    /// Returns the name of the [`ID`] the store uses for the backbone element 'key' of
+    /// the type 'context', `None` for the resource 'resource' itself. E.g. 'BACKBONECONTACT'
+    /// for 'contact' of Patient.
+    pub fn backbone_name(&self, resource: &str, context: Option<&str>, key: &str) -> Option<String> {
+        let key = get_key_id(key.as_bytes())?;
+        let (resource, context) = match context {
+            Some(context) => (ResourceId::Empty, Some(ID::try_from(*self.ids.get(context)?).ok()?)),
+            None => (ResourceId::try_from(resource).ok()?, None)
+        };
+        let expects = format!("{:?}", get_expects_in(&resource, context, key)?);
+        let name = expects.strip_prefix('L').unwrap_or(&expects);
+        name.starts_with("BACKBONE").then(|| name.to_string())
+    }
+}
+
+fn next<'a>(used: impl Iterator<Item = &'a u16>, range: Range<u16>) -> Option<u16> {
+    let raw = used.filter(|raw| range.contains(*raw)).max().map(|raw| raw + 1).unwrap_or(range.start);
+    range.contains(&raw).then_some(raw)
+}
+
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn numbering_keeps_store_numbers() {
+        let mut numbering = Numbering::from_store();
+        assert_eq!(numbering.ids["EMPTY"], 0);
+        assert_eq!(numbering.ids["HUMANNAME"], 513);
+        assert_eq!(numbering.ids["LHUMANNAME"], 2048);
+        assert_eq!(numbering.keys["birthDate"], ID::BirthDate as u16);
+        assert_eq!(numbering.resources["Endpoint"], 12);
+        assert_eq!(numbering.type_name("CodeableConcept"), "CODABLECONCEPT");
+        assert_eq!(numbering.type_name("Quantity"), "QUANTITY");
+        assert_eq!(numbering.type_name("BodyStructure"), "BODYSTRUCTURE");
+        assert_eq!(numbering.backbone_name("Patient", None, "contact").as_deref(), Some("BACKBONECONTACT"));
+        assert_eq!(numbering.backbone_name("Timing", Some("TIMING"), "repeat").as_deref(), Some("BACKBONETIMINGREPEAT"));
+        assert_eq!(numbering.backbone_name("Patient", None, "name"), None);
+
+        // new numbers follow the highest one of their range
+        let highest = *numbering.ids.values().filter(|raw| GENERAL_PURPOSE.contains(*raw)).max().unwrap();
+        assert_eq!(numbering.id("HUMANNAME", GENERAL_PURPOSE).unwrap(), 513);
+        assert_eq!(numbering.id("RATIORANGE", GENERAL_PURPOSE).unwrap(), highest + 1);
+        assert_eq!(numbering.list("RATIORANGE").unwrap(), numbering.ids["LBACKBONEPAYLOAD"] + 1);
+        assert_eq!(numbering.list("STRING").unwrap(), 22);
+        assert_eq!(numbering.list("DECIMAL").unwrap(), numbering.ids["LURL"] + 1);
+        assert_eq!(numbering.key("birthDate").unwrap(), ID::BirthDate as u16);
+        assert_eq!(numbering.key("includedStructure").unwrap(), ID::SessionKey as u16 + 1);
+        assert_eq!(numbering.resource("Patient"), 1);
+        assert_eq!(numbering.resource("BodyStructure"), 13);
+    }
+}
@@ -0,0 +1,349 @@
+use std::collections::{BTreeMap, BTreeSet};
+use fhir_store::datatypes::id::{ID, get_expects, is_backbone};
+use fhir_store::error::{Result, Error};
+use crate::definitions::{Definitions, Element, ElementType, Kind, TypeDef};
+use crate::numbering::{Numbering, GENERAL_PURPOSE, PRIMITIVES, PRIMITIVE_EXT};
+
+/// A line of an `_EXPECTS` table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Row {
+    pub key: u16,
+    /// Name of the [`ID`] of the value, e.g. 'LHUMANNAME'.
+    pub expects: String,
+    /// e.g. 'name [HumanName] 0..*'
+    pub comment: String
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Table {
+    /// Name of the static without '_EXPECTS', e.g. 'HUMANNAME'.
+    pub name: String,
+    /// Numbers 'get_from_sub' picks the table by, the type and its list. For resources
+    /// the number of the [`fhir_store::resourcetypes::ResourceId`].
+    pub numbers: Vec<u16>,
+    pub rows: Vec<Row>
+}
+
+/// Types allowed for a choice element, see `choices` in `datatypes/id.rs`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Choice {
+    pub context: ChoiceContext,
+    pub key: u16,
+    pub types: Vec<String>,
+    /// e.g. 'Patient deceased[x]'
+    pub comment: String
+}
+
+/// The object a choice element is part of, lists are looked up by the type of their items.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChoiceContext {
+    /// The name of the resource, e.g. 'Patient'.
+    Resource(String),
+    /// The name of the [`ID`] of the type, e.g. 'BACKBONEDOSEANDRATE'.
+    Type(String)
+}
+
+/// Everything written by [`crate::emit`].
+#[derive(Debug, Default)]
+pub struct Tables {
+    pub numbering: Numbering,
+    /// The [`ID`] of the value of the keys of resources for `EXPECTS`, the elements of
+    /// Patient and DomainResource take precedence.
+    pub expects: BTreeMap<u16, String>,
+    pub suffixes: BTreeMap<u16, String>,
+    /// Names of the [`ID`]s of backbone elements, without their lists.
+    pub backbones: BTreeSet<String>,
+    pub types: Vec<Table>,
+    pub resources: Vec<Table>,
+    pub domain_resource: Table,
+    pub choices: Vec<Choice>,
+    pub skipped: Vec<String>
+}
+
+impl Tables {
+    /// Numbers and names all types of 'definitions', based on the numbers in 'numbering',
+    /// and builds their tables.
+    pub fn build(definitions: &Definitions, numbering: Numbering) -> Result<Tables> {
+        let mut tables = Tables { numbering, skipped: definitions.skipped.clone(), ..Default::default() };
+        // names of the ids by FHIR type or path of the backbone element, resources by their name
+        let mut names = BTreeMap::new();
+        for def in definitions.types.iter() {
+            let name = tables.name(definitions, def, &names)?;
+            names.insert(def.name.clone(), name);
+        }
+        tables.check_complete(definitions, &names)?;
+        tables.backbones.extend(tables.numbering.ids.iter()
+            .filter(|(_, raw)| ID::try_from(**raw).is_ok_and(is_backbone))
+            .map(|(name, _)| name.strip_prefix('L').unwrap_or(name).to_string()));
+
+        for def in definitions.types.iter().filter(|def| def.kind != Kind::Primitive) {
+            let resource = def.kind == Kind::Resource;
+            let name = match resource {
+                true => def.name.to_uppercase(),
+                false => names[&def.name].clone()
+            };
+            // backbone elements the store shares between paths get a single table
+            if tables.types.iter().chain(tables.resources.iter()).any(|table| table.name == name) {
+                continue;
+            }
+            let mut rows = Vec::with_capacity(def.elements.len());
+            for element in def.elements.iter() {
+                let key = tables.numbering.key(&element.name)?;
+                let (expects, types) = tables.expects(element, &names)?;
+                if element.choice {
+                    let context = match resource {
+                        true => ChoiceContext::Resource(def.name.clone()),
+                        false => ChoiceContext::Type(name.clone())
+                    };
+                    let comment = format!("{} {}[x]", def.name, element.name);
+                    tables.choices.push(Choice { context, key, types, comment });
+                }
+                rows.push(Row { key, expects, comment: comment(element) });
+            }
+            if resource {
+                let numbers = vec![tables.numbering.resources[&def.name]];
+                tables.resources.push(Table { name, numbers, rows });
+            } else {
+                tables.types.push(Table { name, numbers: Vec::new(), rows });
+            }
+        }
+        for table in tables.types.iter_mut() {
+            table.numbers = [table.name.clone(), format!("L{}", table.name)].iter()
+                .filter_map(|name| tables.numbering.ids.get(name).copied())
+                .collect();
+        }
+        let resource_type = tables.numbering.key("resourceType")?;
+        tables.domain_resource.name = "DOMAINRESOURCE".to_string();
+        tables.domain_resource.rows.push(Row { key: resource_type, expects: "STRING".to_string(), comment: "resourceType [string] 1..1".to_string() });
+        for element in definitions.domain_resource.iter() {
+            let key = tables.numbering.key(&element.name)?;
+            let (expects, _) = tables.expects(element, &names)?;
+            tables.domain_resource.rows.push(Row { key, expects, comment: comment(element) });
+        }
+        tables.global_expects();
+        Ok(tables)
+    }
+
+    // A type the store numbers but the package lacks would lose its table, so the
+    // generated code could no longer read the resources holding it.
+    fn check_complete(&self, definitions: &Definitions, names: &BTreeMap<String, String>) -> Result<()> {
+        let defined: BTreeSet<&String> = names.values().collect();
+        let mut missing: Vec<&str> = self.numbering.ids.iter()
+            .filter(|(name, raw)| {
+                let typ = PRIMITIVES.contains(*raw) || PRIMITIVE_EXT.contains(*raw) || GENERAL_PURPOSE.contains(*raw);
+                // ELEMENT holds the '_' siblings of primitives and has no table of its own
+                typ && name.as_str() != "ELEMENT" && !defined.contains(name)
+            })
+            .map(|(name, _)| name.as_str())
+            .collect();
+        missing.extend(self.numbering.resources.keys()
+            .filter(|name| name.as_str() != "Empty" && definitions.get(name).is_none())
+            .map(|name| name.as_str()));
+        if definitions.domain_resource.is_empty() {
+            missing.push("DomainResource");
+        }
+        match missing.is_empty() {
+            true => Ok(()),
+            false => Err(Error::Custom(format!("missing from the package: {}", missing.join(", "))))
+        }
+    }
+
+    // Returns the name of the ID of 'def', the name of resources, and numbers it.
+    fn name(&mut self, definitions: &Definitions, def: &TypeDef, names: &BTreeMap<String, String>) -> Result<String> {
+        match def.kind {
+            Kind::Primitive => {
+                let name = self.numbering.type_name(&def.name);
+                let raw = *self.numbering.ids.get(&name)
+                    .ok_or_else(|| Error::Custom(format!("primitive {} has no encoding in the store", def.name)))?;
+                self.suffixes.insert(raw, suffix(&def.name));
+                Ok(name)
+            },
+            Kind::Datatype => {
+                let name = self.numbering.type_name(&def.name);
+                let raw = self.numbering.id(&name, GENERAL_PURPOSE)?;
+                self.suffixes.insert(raw, suffix(&def.name));
+                Ok(name)
+            },
+            Kind::Resource => {
+                self.numbering.resource(&def.name);
+                Ok(def.name.clone())
+            },
+            Kind::Backbone => {
+                let (parent, segment) = def.name.rsplit_once('.')
+                    .ok_or_else(|| Error::Custom(format!("backbone element {} without parent", def.name)))?;
+                let root = def.name.split('.').next().unwrap_or_default();
+                let context = match definitions.get(root) {
+                    Some(root_def) if root_def.kind == Kind::Resource && parent == root => None,
+                    _ => Some(names[parent].as_str())
+                };
+                let name = match self.numbering.backbone_name(root, context, segment) {
+                    Some(name) => name,
+                    None => {
+                        let name = format!("BACKBONE{}", segment.to_uppercase());
+                        if self.numbering.ids.contains_key(&name) {
+                            format!("BACKBONE{}", def.name.replace('.', "").to_uppercase())
+                        } else {
+                            name
+                        }
+                    }
+                };
+                self.numbering.id(&name, GENERAL_PURPOSE)?;
+                self.backbones.insert(name.clone());
+                Ok(name)
+            }
+        }
+    }
+
+    // Returns the name of the ID 'element' expects and the types allowed for a choice element.
+    fn expects(&mut self, element: &Element, names: &BTreeMap<String, String>) -> Result<(String, Vec<String>)> {
+        if element.choice {
+            let types = element.types.iter()
+                .map(|typ| self.type_name(typ, names))
+                .collect::<Result<Vec<String>>>()?;
+            return Ok(("MULTIPLETYPES".to_string(), types))
+        }
+        let typ = element.types.first()
+            .ok_or_else(|| Error::Custom(format!("element {} without type", element.name)))?;
+        let name = self.type_name(typ, names)?;
+        if element.is_list() {
+            self.numbering.list(&name)?;
+            Ok((format!("L{name}"), Vec::new()))
+        } else {
+            Ok((name, Vec::new()))
+        }
+    }
+
+    // Types not in the package are taken from the store.
+    fn type_name(&self, typ: &ElementType, names: &BTreeMap<String, String>) -> Result<String> {
+        let (ElementType::Named(name) | ElementType::Backbone(name)) = typ;
+        if let Some(id) = names.get(name) {
+            return Ok(id.clone())
+        }
+        let id = self.numbering.type_name(name);
+        match (typ, self.numbering.ids.contains_key(&id)) {
+            (ElementType::Named(_), true) => Ok(id),
+            _ => Err(Error::Custom(format!("unknown type {name}")))
+        }
+    }
+
+    // Patient first, then DomainResource, then what the store expects now and at last
+    // the first resource defining a key. Elements of datatypes stay in their own tables.
+    fn global_expects(&mut self) {
+        let patient = self.resources.iter().find(|table| table.name == "PATIENT");
+        let rows = patient.into_iter().flat_map(|table| table.rows.iter())
+            .chain(self.domain_resource.rows.iter());
+        for row in rows {
+            self.expects.entry(row.key).or_insert_with(|| row.expects.clone());
+        }
+        for raw in self.numbering.keys.values() {
+            if let Some(id) = get_expects(*raw) {
+                self.expects.entry(*raw).or_insert_with(|| format!("{id:?}"));
+            }
+        }
+        for row in self.resources.iter().flat_map(|table| table.rows.iter()) {
+            self.expects.entry(row.key).or_insert_with(|| row.expects.clone());
+        }
+    }
+}
+
+// The suffix of a choice element of type 'typ', e.g. 'DateTime' for deceasedDateTime.
+fn suffix(typ: &str) -> String {
+    match typ {
+        "SimpleQuantity" | "MoneyQuantity" => "Quantity".to_string(),
+        _ => {
+            let mut chars = typ.chars();
+            chars.next().map(|first| first.to_uppercase().chain(chars).collect()).unwrap_or_default()
+        }
+    }
+}
+
+fn comment(element: &Element) -> String {
+    let types: Vec<&str> = element.types.iter()
+        .map(|typ| match typ {
+            ElementType::Named(name) => name.as_str(),
+            ElementType::Backbone(_) => "BackboneElement"
+        })
+        .collect();
+    let choice = if element.choice { "[x]" } else { "" };
+    format!("{}{choice} [{}] {}..{}", element.name, types.join("|"), element.min, element.max)
+}
+
+
+
+#[cfg(test)]
+mod test {
+    use std::path::Path;
+    use fhir_store::datatypes::id::{get_from_sub, get_expects_in};
+    use fhir_store::resourcetypes::ResourceId;
+    use crate::definitions::{from_structure_definitions, read_package};
+    use crate::definitions::test::vendored_with;
+    use super::*;
+
+    fn fixture() -> Tables {
+        let definitions = from_structure_definitions(&vendored_with(&["BodyStructure", "RatioRange"])).unwrap();
+        Tables::build(&definitions, Numbering::from_store()).unwrap()
+    }
+
+    #[test]
+    fn tables_from_package() {
+        let tables = fixture();
+        let ids = &tables.numbering.ids;
+        // existing types keep their numbers and agree with the tables of the store
+        for name in ["HUMANNAME", "PERIOD", "BACKBONECONTACT"] {
+            let table = tables.types.iter().find(|table| table.name == name).unwrap();
+            let list = ids.get(&format!("L{name}"));
+            assert_eq!(table.numbers, [Some(&ids[name]), list].into_iter().flatten().copied().collect::<Vec<u16>>());
+            for row in table.rows.iter() {
+                let expects = get_from_sub(table.numbers[0], row.key).unwrap();
+                assert_eq!(format!("{expects:?}"), row.expects, "{name} {}", row.comment);
+            }
+        }
+        let patient = tables.resources.iter().find(|table| table.name == "PATIENT").unwrap();
+        assert_eq!(patient.numbers, [1]);
+        for row in patient.rows.iter() {
+            let key = ID::try_from(row.key).unwrap();
+            let expects = get_expects_in(&ResourceId::Patient, None, key).unwrap();
+            assert_eq!(format!("{expects:?}"), row.expects, "Patient {}", row.comment);
+            assert_eq!(tables.expects[&row.key], row.expects);
+        }
+        // elements of datatypes are no keys of resources
+        assert!(!tables.expects.contains_key(&(ID::Comparator as u16)));
+        assert!(!tables.expects.contains_key(&(ID::MaxDosePerPeriod as u16)));
+        assert!(tables.choices.contains(&Choice {
+            context: ChoiceContext::Resource("Patient".to_string()),
+            key: ID::Deceased as u16,
+            types: vec!["BOOLEAN".to_string(), "DATETIME".to_string()],
+            comment: "Patient deceased[x]".to_string()
+        }));
+        assert_eq!(tables.suffixes[&ids["CODABLECONCEPT"]], "CodeableConcept");
+
+        // new types are appended
+        let body_structure = tables.resources.iter().find(|table| table.name == "BODYSTRUCTURE").unwrap();
+        assert_eq!(body_structure.numbers, [13]);
+        let included = ids["BACKBONEINCLUDEDSTRUCTURE"];
+        assert_eq!(included, ID::BACKBONEPAYLOAD as u16 + 1);
+        assert_eq!(ids["LBACKBONEINCLUDEDSTRUCTURE"], ID::LBACKBONEPAYLOAD as u16 + 1);
+        assert!(tables.backbones.contains("BACKBONEINCLUDEDSTRUCTURE"));
+        let excluded = body_structure.rows.iter().find(|row| row.comment.starts_with("excludedStructure")).unwrap();
+        assert_eq!(excluded.expects, "LBACKBONEINCLUDEDSTRUCTURE");
+        assert!(excluded.key > ID::SessionKey as u16);
+        let ratio_range = tables.types.iter().find(|table| table.name == "RATIORANGE").unwrap();
+        assert_eq!(ratio_range.rows[0].expects, "SIMPLEQUANTITY");
+        assert_eq!(ratio_range.rows[0].comment, "lowNumerator [SimpleQuantity] 0..1");
+
+        assert_eq!(tables.domain_resource.rows[0].expects, "STRING");
+        assert!(tables.domain_resource.rows.iter().any(|row| row.expects == "LEXTENSION"));
+        assert_eq!(tables.skipped, ["DomainResource.contained"]);
+    }
+
+    #[test]
+    fn tables_missing_type() {
+        // the fixture holds a few types only, the store would lose the tables of the others
+        let definitions = read_package(Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/../test_data/structuredefinitions"))).unwrap();
+        let Err(Error::Custom(message)) = Tables::build(&definitions, Numbering::from_store()) else {
+            panic!("a partial package must be refused")
+        };
+        assert!(message.contains("IDENTIFIER") && message.contains("BACKBONEREACTION") && message.contains("Observation"), "{message}");
+        assert!(!message.contains("HUMANNAME") && !message.contains("BACKBONELINK") && !message.contains("Patient") && !message.contains("ELEMENT"), "{message}");
+    }
+}
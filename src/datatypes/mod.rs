@@ -1,6 +1,2 @@
 pub mod id;
-
-
-
-
-
+pub mod varint;
@@ -0,0 +1,75 @@
+use crate::error::{Error, Result};
+
+/// Maximum number of bytes of an encoded [`u64`].
+pub const MAX_LEN: usize = 10;
+
+
+/// Appends 'value' as LEB128 varint, 7 bits per byte with the lowest bits first.
+/// The high bit of a byte is set, if another byte follows.
+pub fn write(value: u64, out: &mut Vec<u8>) -> usize {
+    let mut value = value;
+    let mut written = 1;
+    while value >= 0x80 {
+        out.push((value as u8 & 0x7F) | 0x80);
+        value >>= 7;
+        written += 1;
+    }
+    out.push(value as u8);
+    written
+}
+
+/// Returns 'value' encoded as varint.
+pub fn encode(value: u64) -> Vec<u8> {
+    let mut out = Vec::with_capacity(MAX_LEN);
+    write(value, &mut out);
+    out
+}
+
+/// Returns the number of bytes 'value' takes as varint.
+pub fn encoded_len(value: u64) -> usize {
+    let bits = 64 - value.leading_zeros() as usize;
+    bits.div_ceil(7).max(1)
+}
+
+/// Reads a varint from the start of 'src'. Returns the value and the number of bytes read.
+pub fn read(src: &[u8]) -> Result<(u64, usize)> {
+    let mut value = 0u64;
+    for (i, byte) in src.iter().enumerate() {
+        if i == MAX_LEN || (i == MAX_LEN - 1 && *byte > 1) {
+            return Err(Error::Conversion("varint".to_string(), "u64".to_string()))
+        }
+        value |= ((byte & 0x7F) as u64) << (7 * i);
+        if byte & 0x80 == 0 {
+            return Ok((value, i + 1))
+        }
+    }
+    Err(Error::BufferUnderflow)
+}
+
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn varint_round_trip() {
+        for value in [0, 1, 127, 128, 300, 4096, 16383, 16384, u16::MAX as u64, u32::MAX as u64, u64::MAX] {
+            let bytes = encode(value);
+            assert_eq!(bytes.len(), encoded_len(value));
+            assert_eq!(read(&bytes).unwrap(), (value, bytes.len()));
+        }
+        assert_eq!(encode(127), vec![127]);
+        assert_eq!(encode(300), vec![172, 2]);
+        assert_eq!(encode(4096), vec![128, 32]);
+    }
+
+    #[test]
+    fn varint_broken() {
+        assert!(read(&[]).is_err());
+        assert!(read(&[128, 128]).is_err());
+        assert!(read(&[0xFF; 11]).is_err());
+        assert!(read(&[0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 2]).is_err());
+        assert_eq!(read(&[5, 128]).unwrap(), (5, 1));
+    }
+}
@@ -20,13 +20,48 @@ pub enum Error {
     SegmentationFault,
     IdMaxLen,
     StoreUnitMaxLen,
+    StoreFull,
     UnknownExpect,
     TimeStampOverflow,
     TimeStampParsingError,
     EOF,
+    NoFreeFrame,
+    Io(String),
+    Search(String),
+    MaxDepth(usize),
+    /// An error while parsing json, with the position it occured at.
+    Json(JsonPosition, Box<Error>),
 
 }
 
+/// Position in a json document, see [`Error::Json`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsonPosition {
+    /// Byte offset, starting at 0.
+    pub offset: usize,
+    /// Line, starting at 1.
+    pub line: usize,
+    /// Column in characters, starting at 1.
+    pub column: usize,
+    /// Path of the element, e.g. 'Patient.name[1].given[0]'.
+    pub path: String
+}
+
+impl JsonPosition {
+    /// Returns the position of byte 'offset' in 'src'.
+    pub fn new(src: &[u8], offset: usize, path: String) -> Self {
+        let before = &src[..offset.min(src.len())];
+        let line_start = before.iter().rposition(|b| *b == b'\n').map(|i| i + 1).unwrap_or(0);
+        let column = String::from_utf8_lossy(&before[line_start..]).chars().count() + 1;
+        Self {
+            offset,
+            line: before.iter().filter(|b| **b == b'\n').count() + 1,
+            column,
+            path
+        }
+    }
+}
+
 impl Display for Error {
     fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
         match self {
@@ -46,12 +81,26 @@ impl Display for Error {
             Error::IdMaxLen                => formatter.write_str("CONVERSION: id max length is 64 characters"),
             Error::StoreUnitMaxLen         => formatter.write_fmt(
                 format_args!("CONVERSION: store unit max length of {} reached", u16::MAX)),
+            Error::StoreFull               => formatter.write_fmt(
+                format_args!("STORE: all {} pages are in use", u16::MAX)),
             Error::TimeStampOverflow       => formatter.write_str("FHIR_DATETIME: TimeStamp overflow occured."), 
             Error::TimeStampParsingError   => formatter.write_str("FHIR_DATETIME: TimeStamp parsing error - unspecified."), 
             Error::EOF                     => formatter.write_str("PARSING: unexpected end of input"),
             Error::UnknownExpect           => formatter.write_str("PARSING: error figuring out expected datatype"),
+            Error::NoFreeFrame             => formatter.write_str("MEMORY: all frames of the buffer pool are pinned or dirty"),
+            Error::Io(msg)                 => formatter.write_fmt(format_args!("IO: {msg}")),
+            Error::Search(msg)             => formatter.write_fmt(format_args!("SEARCH: {msg}")),
+            Error::MaxDepth(depth)         => formatter.write_fmt(format_args!("PARSING: maximum nesting depth of {depth} exceeded")),
+            Error::Json(pos, err)          => formatter.write_fmt(format_args!(
+                "{err} at line {}, column {} (byte {}), path '{}'", pos.line, pos.column, pos.offset, pos.path)),
         }
     }
 }
 
 impl std::error::Error for Error {}
+
+impl From<std::io::Error> for Error {
+    fn from(err: std::io::Error) -> Self {
+        Error::Io(err.to_string())
+    }
+}
@@ -0,0 +1,2707 @@
+// Generated by fhir_codegen from the FHIR StructureDefinitions, do not edit.
+// Regenerate with `cargo run -p fhir_codegen -- codegen/structuredefinitions`.
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u16)]
+pub enum ID {
+    EMPTY = 0,
+    STRING = 1,
+    BOOLEAN = 2,
+    CODE = 3,
+    ID = 4,
+    URI = 5,
+    DATETIME = 6,
+    POSITIVEINT = 7,
+    DATE = 8,
+    INTEGER = 9,
+    INTEGER64 = 10,
+    DECIMAL = 11,
+    BASE64BINARY = 12,
+    URL = 13,
+    INSTANT = 14,
+    TIME = 15,
+    UNSIGNEDINT = 16,
+    CANONICAL = 17,
+    OID = 18,
+    UUID = 19,
+    MARKDOWN = 20,
+    ENDOFLIST = 21,
+    LSTRING = 22,
+    LCODE = 23,
+    LDATETIME = 24,
+    LTIME = 25,
+    LCANONICAL = 26,
+    LURI = 27,
+    LURL = 28,
+    XHTML = 256,
+    NARRATIVE = 512,
+    HUMANNAME = 513,
+    IDENTIFIER = 514,
+    CODABLECONCEPT = 515,
+    PERIOD = 516,
+    REFERENCE = 517,
+    CODING = 518,
+    CONTACTPOINT = 519,
+    ADDRESS = 520,
+    ATTACHMENT = 521,
+    BACKBONECONTACT = 522,
+    BACKBONECOMMUNICATION = 523,
+    BACKBONELINK = 524,
+    QUANTITY = 525,
+    SIMPLEQUANTITY = 526,
+    AGE = 527,
+    DURATION = 528,
+    COUNT = 529,
+    DISTANCE = 530,
+    MONEY = 531,
+    RANGE = 532,
+    RATIO = 533,
+    SAMPLEDDATA = 534,
+    ANNOTATION = 535,
+    SIGNATURE = 536,
+    TIMING = 537,
+    BACKBONETIMINGREPEAT = 538,
+    DOSAGE = 539,
+    BACKBONEDOSEANDRATE = 540,
+    CONTACTDETAIL = 541,
+    META = 542,
+    EXPRESSION = 543,
+    RELATEDARTIFACT = 544,
+    EXTENSION = 545,
+    ELEMENT = 546,
+    CODEABLEREFERENCE = 547,
+    BACKBONETRIGGEREDBY = 548,
+    BACKBONEREFERENCERANGE = 549,
+    BACKBONECOMPONENT = 550,
+    BACKBONEPARTICIPANT = 551,
+    BACKBONESTAGE = 552,
+    BACKBONEENCOUNTERPARTICIPANT = 553,
+    BACKBONEENCOUNTERREASON = 554,
+    BACKBONEENCOUNTERDIAGNOSIS = 555,
+    BACKBONEADMISSION = 556,
+    BACKBONEENCOUNTERLOCATION = 557,
+    BACKBONEPROCEDUREPERFORMER = 558,
+    BACKBONEFOCALDEVICE = 559,
+    BACKBONEREACTION = 560,
+    BACKBONEQUALIFICATION = 561,
+    EXTENDEDCONTACTDETAIL = 562,
+    AVAILABILITY = 563,
+    BACKBONEAVAILABLETIME = 564,
+    BACKBONENOTAVAILABLETIME = 565,
+    VIRTUALSERVICEDETAIL = 566,
+    BACKBONEPOSITION = 567,
+    BACKBONEELIGIBILITY = 568,
+    BACKBONEPAYLOAD = 569,
+    MULTIPLETYPES = 2047,
+    LHUMANNAME = 2048,
+    LIDENTIFIER = 2049,
+    LCODING = 2050,
+    LCONTACTPOINT = 2051,
+    LADDRESS = 2052,
+    LATTACHMENT = 2053,
+    LBACKBONECONTACT = 2054,
+    LCODABLECONCEPT = 2055,
+    LBACKBONECOMMUNICATION = 2056,
+    LBACKBONELINK = 2057,
+    LREFERENCE = 2058,
+    LANNOTATION = 2059,
+    LSIGNATURE = 2060,
+    LDOSAGE = 2061,
+    LBACKBONEDOSEANDRATE = 2062,
+    LCONTACTDETAIL = 2063,
+    LRELATEDARTIFACT = 2064,
+    LRATIO = 2065,
+    LEXTENSION = 2066,
+    LELEMENT = 2067,
+    LCODEABLEREFERENCE = 2068,
+    LBACKBONETRIGGEREDBY = 2069,
+    LBACKBONEREFERENCERANGE = 2070,
+    LBACKBONECOMPONENT = 2071,
+    LBACKBONEPARTICIPANT = 2072,
+    LBACKBONESTAGE = 2073,
+    LBACKBONEENCOUNTERPARTICIPANT = 2074,
+    LBACKBONEENCOUNTERREASON = 2075,
+    LBACKBONEENCOUNTERDIAGNOSIS = 2076,
+    LBACKBONEENCOUNTERLOCATION = 2077,
+    LBACKBONEPROCEDUREPERFORMER = 2078,
+    LBACKBONEFOCALDEVICE = 2079,
+    LBACKBONEREACTION = 2080,
+    LBACKBONEQUALIFICATION = 2081,
+    LEXTENDEDCONTACTDETAIL = 2082,
+    LAVAILABILITY = 2083,
+    LBACKBONEAVAILABLETIME = 2084,
+    LBACKBONENOTAVAILABLETIME = 2085,
+    LVIRTUALSERVICEDETAIL = 2086,
+    LBACKBONEELIGIBILITY = 2087,
+    LBACKBONEPAYLOAD = 2088,
+    ResourceType = 4096,
+    Active = 4097,
+    Text = 4098,
+    Status = 4099,
+    Div = 4100,
+    Name = 4101,
+    Use = 4102,
+    Given = 4103,
+    Family = 4104,
+    Id = 4105,
+    Type = 4106,
+    System = 4107,
+    Value = 4108,
+    Period = 4109,
+    Start = 4110,
+    End = 4111,
+    Assigner = 4112,
+    Reference = 4113,
+    Display = 4114,
+    Version = 4115,
+    Code = 4116,
+    UserSelected = 4117,
+    Coding = 4118,
+    Identifier = 4119,
+    Telecom = 4120,
+    Rank = 4121,
+    Gender = 4122,
+    BirthDate = 4123,
+    Deceased = 4124,
+    MultipleBirth = 4125,
+    Address = 4126,
+    Line = 4127,
+    City = 4128,
+    District = 4129,
+    State = 4130,
+    PostalCode = 4131,
+    Country = 4132,
+    MaritalStatus = 4133,
+    Attachment = 4134,
+    Photo = 4135,
+    ContentType = 4136,
+    Language = 4137,
+    Data = 4138,
+    Url = 4139,
+    Size = 4140,
+    Hash = 4141,
+    Title = 4142,
+    Creation = 4143,
+    Height = 4144,
+    Width = 4145,
+    Frames = 4146,
+    Duration = 4147,
+    Pages = 4148,
+    Contact = 4149,
+    Relationship = 4150,
+    Organization = 4151,
+    Communication = 4152,
+    Preferred = 4153,
+    GeneralPractitioner = 4154,
+    ManagingOrganization = 4155,
+    Link = 4156,
+    Other = 4157,
+    Comparator = 4158,
+    Unit = 4159,
+    Currency = 4160,
+    Low = 4161,
+    High = 4162,
+    Numerator = 4163,
+    Denominator = 4164,
+    Origin = 4165,
+    Interval = 4166,
+    IntervalUnit = 4167,
+    Factor = 4168,
+    LowerLimit = 4169,
+    UpperLimit = 4170,
+    Dimensions = 4171,
+    CodeMap = 4172,
+    Offsets = 4173,
+    Time = 4174,
+    When = 4175,
+    Who = 4176,
+    OnBehalfOf = 4177,
+    TargetFormat = 4178,
+    SigFormat = 4179,
+    Event = 4180,
+    Repeat = 4181,
+    Count = 4182,
+    CountMax = 4183,
+    DurationMax = 4184,
+    DurationUnit = 4185,
+    Frequency = 4186,
+    FrequencyMax = 4187,
+    PeriodMax = 4188,
+    PeriodUnit = 4189,
+    DayOfWeek = 4190,
+    TimeOfDay = 4191,
+    Offset = 4192,
+    Sequence = 4193,
+    AdditionalInstruction = 4194,
+    PatientInstruction = 4195,
+    Timing = 4196,
+    AsNeeded = 4197,
+    AsNeededFor = 4198,
+    Site = 4199,
+    Route = 4200,
+    Method = 4201,
+    DoseAndRate = 4202,
+    MaxDosePerPeriod = 4203,
+    MaxDosePerAdministration = 4204,
+    MaxDosePerLifetime = 4205,
+    Meta = 4206,
+    VersionId = 4207,
+    LastUpdated = 4208,
+    Source = 4209,
+    Profile = 4210,
+    Security = 4211,
+    Tag = 4212,
+    Description = 4213,
+    Expression = 4214,
+    Classifier = 4215,
+    Label = 4216,
+    Citation = 4217,
+    Document = 4218,
+    Resource = 4219,
+    ResourceReference = 4220,
+    PublicationStatus = 4221,
+    PublicationDate = 4222,
+    Author = 4223,
+    Bounds = 4224,
+    Dose = 4225,
+    Rate = 4226,
+    Prefix = 4227,
+    Suffix = 4228,
+    Extension = 4229,
+    ModifierExtension = 4230,
+    ImplicitRules = 4231,
+    Instantiates = 4232,
+    BasedOn = 4233,
+    TriggeredBy = 4234,
+    Observation = 4235,
+    Reason = 4236,
+    PartOf = 4237,
+    Category = 4238,
+    Subject = 4239,
+    Focus = 4240,
+    Encounter = 4241,
+    Effective = 4242,
+    Issued = 4243,
+    Performer = 4244,
+    DataAbsentReason = 4245,
+    Interpretation = 4246,
+    Note = 4247,
+    BodySite = 4248,
+    BodyStructure = 4249,
+    Specimen = 4250,
+    Device = 4251,
+    ReferenceRange = 4252,
+    NormalValue = 4253,
+    AppliesTo = 4254,
+    Age = 4255,
+    HasMember = 4256,
+    DerivedFrom = 4257,
+    Component = 4258,
+    ClinicalStatus = 4259,
+    VerificationStatus = 4260,
+    Severity = 4261,
+    Onset = 4262,
+    Abatement = 4263,
+    RecordedDate = 4264,
+    Participant = 4265,
+    Function = 4266,
+    Actor = 4267,
+    Stage = 4268,
+    Summary = 4269,
+    Assessment = 4270,
+    Evidence = 4271,
+    Concept = 4272,
+    Class = 4273,
+    Priority = 4274,
+    ServiceType = 4275,
+    SubjectStatus = 4276,
+    EpisodeOfCare = 4277,
+    CareTeam = 4278,
+    ServiceProvider = 4279,
+    Appointment = 4280,
+    ActualPeriod = 4281,
+    PlannedStartDate = 4282,
+    PlannedEndDate = 4283,
+    Length = 4284,
+    Diagnosis = 4285,
+    Condition = 4286,
+    Account = 4287,
+    DietPreference = 4288,
+    SpecialArrangement = 4289,
+    SpecialCourtesy = 4290,
+    Admission = 4291,
+    PreAdmissionIdentifier = 4292,
+    AdmitSource = 4293,
+    ReAdmission = 4294,
+    Destination = 4295,
+    DischargeDisposition = 4296,
+    Location = 4297,
+    Form = 4298,
+    InstantiatesCanonical = 4299,
+    InstantiatesUri = 4300,
+    StatusReason = 4301,
+    Occurrence = 4302,
+    Recorded = 4303,
+    Recorder = 4304,
+    Reported = 4305,
+    Outcome = 4306,
+    Report = 4307,
+    Complication = 4308,
+    FollowUp = 4309,
+    FocalDevice = 4310,
+    Action = 4311,
+    Manipulated = 4312,
+    Used = 4313,
+    SupportingInfo = 4314,
+    Criticality = 4315,
+    Patient = 4316,
+    LastOccurrence = 4317,
+    Reaction = 4318,
+    Substance = 4319,
+    Manifestation = 4320,
+    ExposureRoute = 4321,
+    Qualification = 4322,
+    Issuer = 4323,
+    Practitioner = 4324,
+    Specialty = 4325,
+    HealthcareService = 4326,
+    Characteristic = 4327,
+    Availability = 4328,
+    Endpoint = 4329,
+    Alias = 4330,
+    OperationalStatus = 4331,
+    Mode = 4332,
+    Position = 4333,
+    Longitude = 4334,
+    Latitude = 4335,
+    Altitude = 4336,
+    HoursOfOperation = 4337,
+    VirtualService = 4338,
+    ProvidedBy = 4339,
+    OfferedIn = 4340,
+    Comment = 4341,
+    ExtraDetails = 4342,
+    CoverageArea = 4343,
+    ServiceProvisionCode = 4344,
+    Eligibility = 4345,
+    Program = 4346,
+    ReferralMethod = 4347,
+    AppointmentRequired = 4348,
+    ConnectionType = 4349,
+    EnvironmentType = 4350,
+    Payload = 4351,
+    MimeType = 4352,
+    Header = 4353,
+    AvailableTime = 4354,
+    NotAvailableTime = 4355,
+    DaysOfWeek = 4356,
+    AllDay = 4357,
+    AvailableStartTime = 4358,
+    AvailableEndTime = 4359,
+    During = 4360,
+    Purpose = 4361,
+    ChannelType = 4362,
+    AdditionalInfo = 4363,
+    MaxParticipants = 4364,
+    SessionKey = 4365,
+}
+
+static IDS: phf::Map<u16, ID> = phf_map! {
+    1u16 => ID::STRING,                           //[0,1]
+    2u16 => ID::BOOLEAN,                          //[0,2]
+    3u16 => ID::CODE,                             //[0,3]
+    4u16 => ID::ID,                               //[0,4]
+    5u16 => ID::URI,                              //[0,5]
+    6u16 => ID::DATETIME,                         //[0,6]
+    7u16 => ID::POSITIVEINT,                      //[0,7]
+    8u16 => ID::DATE,                             //[0,8]
+    9u16 => ID::INTEGER,                          //[0,9]
+    10u16 => ID::INTEGER64,                       //[0,10]
+    11u16 => ID::DECIMAL,                         //[0,11]
+    12u16 => ID::BASE64BINARY,                    //[0,12]
+    13u16 => ID::URL,                             //[0,13]
+    14u16 => ID::INSTANT,                         //[0,14]
+    15u16 => ID::TIME,                            //[0,15]
+    16u16 => ID::UNSIGNEDINT,                     //[0,16]
+    17u16 => ID::CANONICAL,                       //[0,17]
+    18u16 => ID::OID,                             //[0,18]
+    19u16 => ID::UUID,                            //[0,19]
+    20u16 => ID::MARKDOWN,                        //[0,20]
+    21u16 => ID::ENDOFLIST,                       //[0,21]
+    22u16 => ID::LSTRING,                         //[0,22]
+    23u16 => ID::LCODE,                           //[0,23]
+    24u16 => ID::LDATETIME,                       //[0,24]
+    25u16 => ID::LTIME,                           //[0,25]
+    26u16 => ID::LCANONICAL,                      //[0,26]
+    27u16 => ID::LURI,                            //[0,27]
+    28u16 => ID::LURL,                            //[0,28]
+    256u16 => ID::XHTML,                          //[1,0]
+    512u16 => ID::NARRATIVE,                      //[2,0]
+    513u16 => ID::HUMANNAME,                      //[2,1]
+    514u16 => ID::IDENTIFIER,                     //[2,2]
+    515u16 => ID::CODABLECONCEPT,                 //[2,3]
+    516u16 => ID::PERIOD,                         //[2,4]
+    517u16 => ID::REFERENCE,                      //[2,5]
+    518u16 => ID::CODING,                         //[2,6]
+    519u16 => ID::CONTACTPOINT,                   //[2,7]
+    520u16 => ID::ADDRESS,                        //[2,8]
+    521u16 => ID::ATTACHMENT,                     //[2,9]
+    522u16 => ID::BACKBONECONTACT,                //[2,10]
+    523u16 => ID::BACKBONECOMMUNICATION,          //[2,11]
+    524u16 => ID::BACKBONELINK,                   //[2,12]
+    525u16 => ID::QUANTITY,                       //[2,13]
+    526u16 => ID::SIMPLEQUANTITY,                 //[2,14]
+    527u16 => ID::AGE,                            //[2,15]
+    528u16 => ID::DURATION,                       //[2,16]
+    529u16 => ID::COUNT,                          //[2,17]
+    530u16 => ID::DISTANCE,                       //[2,18]
+    531u16 => ID::MONEY,                          //[2,19]
+    532u16 => ID::RANGE,                          //[2,20]
+    533u16 => ID::RATIO,                          //[2,21]
+    534u16 => ID::SAMPLEDDATA,                    //[2,22]
+    535u16 => ID::ANNOTATION,                     //[2,23]
+    536u16 => ID::SIGNATURE,                      //[2,24]
+    537u16 => ID::TIMING,                         //[2,25]
+    538u16 => ID::BACKBONETIMINGREPEAT,           //[2,26]
+    539u16 => ID::DOSAGE,                         //[2,27]
+    540u16 => ID::BACKBONEDOSEANDRATE,            //[2,28]
+    541u16 => ID::CONTACTDETAIL,                  //[2,29]
+    542u16 => ID::META,                           //[2,30]
+    543u16 => ID::EXPRESSION,                     //[2,31]
+    544u16 => ID::RELATEDARTIFACT,                //[2,32]
+    545u16 => ID::EXTENSION,                      //[2,33]
+    546u16 => ID::ELEMENT,                        //[2,34]
+    547u16 => ID::CODEABLEREFERENCE,              //[2,35]
+    548u16 => ID::BACKBONETRIGGEREDBY,            //[2,36]
+    549u16 => ID::BACKBONEREFERENCERANGE,         //[2,37]
+    550u16 => ID::BACKBONECOMPONENT,              //[2,38]
+    551u16 => ID::BACKBONEPARTICIPANT,            //[2,39]
+    552u16 => ID::BACKBONESTAGE,                  //[2,40]
+    553u16 => ID::BACKBONEENCOUNTERPARTICIPANT,   //[2,41]
+    554u16 => ID::BACKBONEENCOUNTERREASON,        //[2,42]
+    555u16 => ID::BACKBONEENCOUNTERDIAGNOSIS,     //[2,43]
+    556u16 => ID::BACKBONEADMISSION,              //[2,44]
+    557u16 => ID::BACKBONEENCOUNTERLOCATION,      //[2,45]
+    558u16 => ID::BACKBONEPROCEDUREPERFORMER,     //[2,46]
+    559u16 => ID::BACKBONEFOCALDEVICE,            //[2,47]
+    560u16 => ID::BACKBONEREACTION,               //[2,48]
+    561u16 => ID::BACKBONEQUALIFICATION,          //[2,49]
+    562u16 => ID::EXTENDEDCONTACTDETAIL,          //[2,50]
+    563u16 => ID::AVAILABILITY,                   //[2,51]
+    564u16 => ID::BACKBONEAVAILABLETIME,          //[2,52]
+    565u16 => ID::BACKBONENOTAVAILABLETIME,       //[2,53]
+    566u16 => ID::VIRTUALSERVICEDETAIL,           //[2,54]
+    567u16 => ID::BACKBONEPOSITION,               //[2,55]
+    568u16 => ID::BACKBONEELIGIBILITY,            //[2,56]
+    569u16 => ID::BACKBONEPAYLOAD,                //[2,57]
+    2047u16 => ID::MULTIPLETYPES,                 //[7,255]
+    2048u16 => ID::LHUMANNAME,                    //[8,0]
+    2049u16 => ID::LIDENTIFIER,                   //[8,1]
+    2050u16 => ID::LCODING,                       //[8,2]
+    2051u16 => ID::LCONTACTPOINT,                 //[8,3]
+    2052u16 => ID::LADDRESS,                      //[8,4]
+    2053u16 => ID::LATTACHMENT,                   //[8,5]
+    2054u16 => ID::LBACKBONECONTACT,              //[8,6]
+    2055u16 => ID::LCODABLECONCEPT,               //[8,7]
+    2056u16 => ID::LBACKBONECOMMUNICATION,        //[8,8]
+    2057u16 => ID::LBACKBONELINK,                 //[8,9]
+    2058u16 => ID::LREFERENCE,                    //[8,10]
+    2059u16 => ID::LANNOTATION,                   //[8,11]
+    2060u16 => ID::LSIGNATURE,                    //[8,12]
+    2061u16 => ID::LDOSAGE,                       //[8,13]
+    2062u16 => ID::LBACKBONEDOSEANDRATE,          //[8,14]
+    2063u16 => ID::LCONTACTDETAIL,                //[8,15]
+    2064u16 => ID::LRELATEDARTIFACT,              //[8,16]
+    2065u16 => ID::LRATIO,                        //[8,17]
+    2066u16 => ID::LEXTENSION,                    //[8,18]
+    2067u16 => ID::LELEMENT,                      //[8,19]
+    2068u16 => ID::LCODEABLEREFERENCE,            //[8,20]
+    2069u16 => ID::LBACKBONETRIGGEREDBY,          //[8,21]
+    2070u16 => ID::LBACKBONEREFERENCERANGE,       //[8,22]
+    2071u16 => ID::LBACKBONECOMPONENT,            //[8,23]
+    2072u16 => ID::LBACKBONEPARTICIPANT,          //[8,24]
+    2073u16 => ID::LBACKBONESTAGE,                //[8,25]
+    2074u16 => ID::LBACKBONEENCOUNTERPARTICIPANT, //[8,26]
+    2075u16 => ID::LBACKBONEENCOUNTERREASON,      //[8,27]
+    2076u16 => ID::LBACKBONEENCOUNTERDIAGNOSIS,   //[8,28]
+    2077u16 => ID::LBACKBONEENCOUNTERLOCATION,    //[8,29]
+    2078u16 => ID::LBACKBONEPROCEDUREPERFORMER,   //[8,30]
+    2079u16 => ID::LBACKBONEFOCALDEVICE,          //[8,31]
+    2080u16 => ID::LBACKBONEREACTION,             //[8,32]
+    2081u16 => ID::LBACKBONEQUALIFICATION,        //[8,33]
+    2082u16 => ID::LEXTENDEDCONTACTDETAIL,        //[8,34]
+    2083u16 => ID::LAVAILABILITY,                 //[8,35]
+    2084u16 => ID::LBACKBONEAVAILABLETIME,        //[8,36]
+    2085u16 => ID::LBACKBONENOTAVAILABLETIME,     //[8,37]
+    2086u16 => ID::LVIRTUALSERVICEDETAIL,         //[8,38]
+    2087u16 => ID::LBACKBONEELIGIBILITY,          //[8,39]
+    2088u16 => ID::LBACKBONEPAYLOAD,              //[8,40]
+    4096u16 => ID::ResourceType,                  //[16,0]
+    4097u16 => ID::Active,                        //[16,1]
+    4098u16 => ID::Text,                          //[16,2]
+    4099u16 => ID::Status,                        //[16,3]
+    4100u16 => ID::Div,                           //[16,4]
+    4101u16 => ID::Name,                          //[16,5]
+    4102u16 => ID::Use,                           //[16,6]
+    4103u16 => ID::Given,                         //[16,7]
+    4104u16 => ID::Family,                        //[16,8]
+    4105u16 => ID::Id,                            //[16,9]
+    4106u16 => ID::Type,                          //[16,10]
+    4107u16 => ID::System,                        //[16,11]
+    4108u16 => ID::Value,                         //[16,12]
+    4109u16 => ID::Period,                        //[16,13]
+    4110u16 => ID::Start,                         //[16,14]
+    4111u16 => ID::End,                           //[16,15]
+    4112u16 => ID::Assigner,                      //[16,16]
+    4113u16 => ID::Reference,                     //[16,17]
+    4114u16 => ID::Display,                       //[16,18]
+    4115u16 => ID::Version,                       //[16,19]
+    4116u16 => ID::Code,                          //[16,20]
+    4117u16 => ID::UserSelected,                  //[16,21]
+    4118u16 => ID::Coding,                        //[16,22]
+    4119u16 => ID::Identifier,                    //[16,23]
+    4120u16 => ID::Telecom,                       //[16,24]
+    4121u16 => ID::Rank,                          //[16,25]
+    4122u16 => ID::Gender,                        //[16,26]
+    4123u16 => ID::BirthDate,                     //[16,27]
+    4124u16 => ID::Deceased,                      //[16,28]
+    4125u16 => ID::MultipleBirth,                 //[16,29]
+    4126u16 => ID::Address,                       //[16,30]
+    4127u16 => ID::Line,                          //[16,31]
+    4128u16 => ID::City,                          //[16,32]
+    4129u16 => ID::District,                      //[16,33]
+    4130u16 => ID::State,                         //[16,34]
+    4131u16 => ID::PostalCode,                    //[16,35]
+    4132u16 => ID::Country,                       //[16,36]
+    4133u16 => ID::MaritalStatus,                 //[16,37]
+    4134u16 => ID::Attachment,                    //[16,38]
+    4135u16 => ID::Photo,                         //[16,39]
+    4136u16 => ID::ContentType,                   //[16,40]
+    4137u16 => ID::Language,                      //[16,41]
+    4138u16 => ID::Data,                          //[16,42]
+    4139u16 => ID::Url,                           //[16,43]
+    4140u16 => ID::Size,                          //[16,44]
+    4141u16 => ID::Hash,                          //[16,45]
+    4142u16 => ID::Title,                         //[16,46]
+    4143u16 => ID::Creation,                      //[16,47]
+    4144u16 => ID::Height,                        //[16,48]
+    4145u16 => ID::Width,                         //[16,49]
+    4146u16 => ID::Frames,                        //[16,50]
+    4147u16 => ID::Duration,                      //[16,51]
+    4148u16 => ID::Pages,                         //[16,52]
+    4149u16 => ID::Contact,                       //[16,53]
+    4150u16 => ID::Relationship,                  //[16,54]
+    4151u16 => ID::Organization,                  //[16,55]
+    4152u16 => ID::Communication,                 //[16,56]
+    4153u16 => ID::Preferred,                     //[16,57]
+    4154u16 => ID::GeneralPractitioner,           //[16,58]
+    4155u16 => ID::ManagingOrganization,          //[16,59]
+    4156u16 => ID::Link,                          //[16,60]
+    4157u16 => ID::Other,                         //[16,61]
+    4158u16 => ID::Comparator,                    //[16,62]
+    4159u16 => ID::Unit,                          //[16,63]
+    4160u16 => ID::Currency,                      //[16,64]
+    4161u16 => ID::Low,                           //[16,65]
+    4162u16 => ID::High,                          //[16,66]
+    4163u16 => ID::Numerator,                     //[16,67]
+    4164u16 => ID::Denominator,                   //[16,68]
+    4165u16 => ID::Origin,                        //[16,69]
+    4166u16 => ID::Interval,                      //[16,70]
+    4167u16 => ID::IntervalUnit,                  //[16,71]
+    4168u16 => ID::Factor,                        //[16,72]
+    4169u16 => ID::LowerLimit,                    //[16,73]
+    4170u16 => ID::UpperLimit,                    //[16,74]
+    4171u16 => ID::Dimensions,                    //[16,75]
+    4172u16 => ID::CodeMap,                       //[16,76]
+    4173u16 => ID::Offsets,                       //[16,77]
+    4174u16 => ID::Time,                          //[16,78]
+    4175u16 => ID::When,                          //[16,79]
+    4176u16 => ID::Who,                           //[16,80]
+    4177u16 => ID::OnBehalfOf,                    //[16,81]
+    4178u16 => ID::TargetFormat,                  //[16,82]
+    4179u16 => ID::SigFormat,                     //[16,83]
+    4180u16 => ID::Event,                         //[16,84]
+    4181u16 => ID::Repeat,                        //[16,85]
+    4182u16 => ID::Count,                         //[16,86]
+    4183u16 => ID::CountMax,                      //[16,87]
+    4184u16 => ID::DurationMax,                   //[16,88]
+    4185u16 => ID::DurationUnit,                  //[16,89]
+    4186u16 => ID::Frequency,                     //[16,90]
+    4187u16 => ID::FrequencyMax,                  //[16,91]
+    4188u16 => ID::PeriodMax,                     //[16,92]
+    4189u16 => ID::PeriodUnit,                    //[16,93]
+    4190u16 => ID::DayOfWeek,                     //[16,94]
+    4191u16 => ID::TimeOfDay,                     //[16,95]
+    4192u16 => ID::Offset,                        //[16,96]
+    4193u16 => ID::Sequence,                      //[16,97]
+    4194u16 => ID::AdditionalInstruction,         //[16,98]
+    4195u16 => ID::PatientInstruction,            //[16,99]
+    4196u16 => ID::Timing,                        //[16,100]
+    4197u16 => ID::AsNeeded,                      //[16,101]
+    4198u16 => ID::AsNeededFor,                   //[16,102]
+    4199u16 => ID::Site,                          //[16,103]
+    4200u16 => ID::Route,                         //[16,104]
+    4201u16 => ID::Method,                        //[16,105]
+    4202u16 => ID::DoseAndRate,                   //[16,106]
+    4203u16 => ID::MaxDosePerPeriod,              //[16,107]
+    4204u16 => ID::MaxDosePerAdministration,      //[16,108]
+    4205u16 => ID::MaxDosePerLifetime,            //[16,109]
+    4206u16 => ID::Meta,                          //[16,110]
+    4207u16 => ID::VersionId,                     //[16,111]
+    4208u16 => ID::LastUpdated,                   //[16,112]
+    4209u16 => ID::Source,                        //[16,113]
+    4210u16 => ID::Profile,                       //[16,114]
+    4211u16 => ID::Security,                      //[16,115]
+    4212u16 => ID::Tag,                           //[16,116]
+    4213u16 => ID::Description,                   //[16,117]
+    4214u16 => ID::Expression,                    //[16,118]
+    4215u16 => ID::Classifier,                    //[16,119]
+    4216u16 => ID::Label,                         //[16,120]
+    4217u16 => ID::Citation,                      //[16,121]
+    4218u16 => ID::Document,                      //[16,122]
+    4219u16 => ID::Resource,                      //[16,123]
+    4220u16 => ID::ResourceReference,             //[16,124]
+    4221u16 => ID::PublicationStatus,             //[16,125]
+    4222u16 => ID::PublicationDate,               //[16,126]
+    4223u16 => ID::Author,                        //[16,127]
+    4224u16 => ID::Bounds,                        //[16,128]
+    4225u16 => ID::Dose,                          //[16,129]
+    4226u16 => ID::Rate,                          //[16,130]
+    4227u16 => ID::Prefix,                        //[16,131]
+    4228u16 => ID::Suffix,                        //[16,132]
+    4229u16 => ID::Extension,                     //[16,133]
+    4230u16 => ID::ModifierExtension,             //[16,134]
+    4231u16 => ID::ImplicitRules,                 //[16,135]
+    4232u16 => ID::Instantiates,                  //[16,136]
+    4233u16 => ID::BasedOn,                       //[16,137]
+    4234u16 => ID::TriggeredBy,                   //[16,138]
+    4235u16 => ID::Observation,                   //[16,139]
+    4236u16 => ID::Reason,                        //[16,140]
+    4237u16 => ID::PartOf,                        //[16,141]
+    4238u16 => ID::Category,                      //[16,142]
+    4239u16 => ID::Subject,                       //[16,143]
+    4240u16 => ID::Focus,                         //[16,144]
+    4241u16 => ID::Encounter,                     //[16,145]
+    4242u16 => ID::Effective,                     //[16,146]
+    4243u16 => ID::Issued,                        //[16,147]
+    4244u16 => ID::Performer,                     //[16,148]
+    4245u16 => ID::DataAbsentReason,              //[16,149]
+    4246u16 => ID::Interpretation,                //[16,150]
+    4247u16 => ID::Note,                          //[16,151]
+    4248u16 => ID::BodySite,                      //[16,152]
+    4249u16 => ID::BodyStructure,                 //[16,153]
+    4250u16 => ID::Specimen,                      //[16,154]
+    4251u16 => ID::Device,                        //[16,155]
+    4252u16 => ID::ReferenceRange,                //[16,156]
+    4253u16 => ID::NormalValue,                   //[16,157]
+    4254u16 => ID::AppliesTo,                     //[16,158]
+    4255u16 => ID::Age,                           //[16,159]
+    4256u16 => ID::HasMember,                     //[16,160]
+    4257u16 => ID::DerivedFrom,                   //[16,161]
+    4258u16 => ID::Component,                     //[16,162]
+    4259u16 => ID::ClinicalStatus,                //[16,163]
+    4260u16 => ID::VerificationStatus,            //[16,164]
+    4261u16 => ID::Severity,                      //[16,165]
+    4262u16 => ID::Onset,                         //[16,166]
+    4263u16 => ID::Abatement,                     //[16,167]
+    4264u16 => ID::RecordedDate,                  //[16,168]
+    4265u16 => ID::Participant,                   //[16,169]
+    4266u16 => ID::Function,                      //[16,170]
+    4267u16 => ID::Actor,                         //[16,171]
+    4268u16 => ID::Stage,                         //[16,172]
+    4269u16 => ID::Summary,                       //[16,173]
+    4270u16 => ID::Assessment,                    //[16,174]
+    4271u16 => ID::Evidence,                      //[16,175]
+    4272u16 => ID::Concept,                       //[16,176]
+    4273u16 => ID::Class,                         //[16,177]
+    4274u16 => ID::Priority,                      //[16,178]
+    4275u16 => ID::ServiceType,                   //[16,179]
+    4276u16 => ID::SubjectStatus,                 //[16,180]
+    4277u16 => ID::EpisodeOfCare,                 //[16,181]
+    4278u16 => ID::CareTeam,                      //[16,182]
+    4279u16 => ID::ServiceProvider,               //[16,183]
+    4280u16 => ID::Appointment,                   //[16,184]
+    4281u16 => ID::ActualPeriod,                  //[16,185]
+    4282u16 => ID::PlannedStartDate,              //[16,186]
+    4283u16 => ID::PlannedEndDate,                //[16,187]
+    4284u16 => ID::Length,                        //[16,188]
+    4285u16 => ID::Diagnosis,                     //[16,189]
+    4286u16 => ID::Condition,                     //[16,190]
+    4287u16 => ID::Account,                       //[16,191]
+    4288u16 => ID::DietPreference,                //[16,192]
+    4289u16 => ID::SpecialArrangement,            //[16,193]
+    4290u16 => ID::SpecialCourtesy,               //[16,194]
+    4291u16 => ID::Admission,                     //[16,195]
+    4292u16 => ID::PreAdmissionIdentifier,        //[16,196]
+    4293u16 => ID::AdmitSource,                   //[16,197]
+    4294u16 => ID::ReAdmission,                   //[16,198]
+    4295u16 => ID::Destination,                   //[16,199]
+    4296u16 => ID::DischargeDisposition,          //[16,200]
+    4297u16 => ID::Location,                      //[16,201]
+    4298u16 => ID::Form,                          //[16,202]
+    4299u16 => ID::InstantiatesCanonical,         //[16,203]
+    4300u16 => ID::InstantiatesUri,               //[16,204]
+    4301u16 => ID::StatusReason,                  //[16,205]
+    4302u16 => ID::Occurrence,                    //[16,206]
+    4303u16 => ID::Recorded,                      //[16,207]
+    4304u16 => ID::Recorder,                      //[16,208]
+    4305u16 => ID::Reported,                      //[16,209]
+    4306u16 => ID::Outcome,                       //[16,210]
+    4307u16 => ID::Report,                        //[16,211]
+    4308u16 => ID::Complication,                  //[16,212]
+    4309u16 => ID::FollowUp,                      //[16,213]
+    4310u16 => ID::FocalDevice,                   //[16,214]
+    4311u16 => ID::Action,                        //[16,215]
+    4312u16 => ID::Manipulated,                   //[16,216]
+    4313u16 => ID::Used,                          //[16,217]
+    4314u16 => ID::SupportingInfo,                //[16,218]
+    4315u16 => ID::Criticality,                   //[16,219]
+    4316u16 => ID::Patient,                       //[16,220]
+    4317u16 => ID::LastOccurrence,                //[16,221]
+    4318u16 => ID::Reaction,                      //[16,222]
+    4319u16 => ID::Substance,                     //[16,223]
+    4320u16 => ID::Manifestation,                 //[16,224]
+    4321u16 => ID::ExposureRoute,                 //[16,225]
+    4322u16 => ID::Qualification,                 //[16,226]
+    4323u16 => ID::Issuer,                        //[16,227]
+    4324u16 => ID::Practitioner,                  //[16,228]
+    4325u16 => ID::Specialty,                     //[16,229]
+    4326u16 => ID::HealthcareService,             //[16,230]
+    4327u16 => ID::Characteristic,                //[16,231]
+    4328u16 => ID::Availability,                  //[16,232]
+    4329u16 => ID::Endpoint,                      //[16,233]
+    4330u16 => ID::Alias,                         //[16,234]
+    4331u16 => ID::OperationalStatus,             //[16,235]
+    4332u16 => ID::Mode,                          //[16,236]
+    4333u16 => ID::Position,                      //[16,237]
+    4334u16 => ID::Longitude,                     //[16,238]
+    4335u16 => ID::Latitude,                      //[16,239]
+    4336u16 => ID::Altitude,                      //[16,240]
+    4337u16 => ID::HoursOfOperation,              //[16,241]
+    4338u16 => ID::VirtualService,                //[16,242]
+    4339u16 => ID::ProvidedBy,                    //[16,243]
+    4340u16 => ID::OfferedIn,                     //[16,244]
+    4341u16 => ID::Comment,                       //[16,245]
+    4342u16 => ID::ExtraDetails,                  //[16,246]
+    4343u16 => ID::CoverageArea,                  //[16,247]
+    4344u16 => ID::ServiceProvisionCode,          //[16,248]
+    4345u16 => ID::Eligibility,                   //[16,249]
+    4346u16 => ID::Program,                       //[16,250]
+    4347u16 => ID::ReferralMethod,                //[16,251]
+    4348u16 => ID::AppointmentRequired,           //[16,252]
+    4349u16 => ID::ConnectionType,                //[16,253]
+    4350u16 => ID::EnvironmentType,               //[16,254]
+    4351u16 => ID::Payload,                       //[16,255]
+    4352u16 => ID::MimeType,                      //[17,0]
+    4353u16 => ID::Header,                        //[17,1]
+    4354u16 => ID::AvailableTime,                 //[17,2]
+    4355u16 => ID::NotAvailableTime,              //[17,3]
+    4356u16 => ID::DaysOfWeek,                    //[17,4]
+    4357u16 => ID::AllDay,                        //[17,5]
+    4358u16 => ID::AvailableStartTime,            //[17,6]
+    4359u16 => ID::AvailableEndTime,              //[17,7]
+    4360u16 => ID::During,                        //[17,8]
+    4361u16 => ID::Purpose,                       //[17,9]
+    4362u16 => ID::ChannelType,                   //[17,10]
+    4363u16 => ID::AdditionalInfo,                //[17,11]
+    4364u16 => ID::MaxParticipants,               //[17,12]
+    4365u16 => ID::SessionKey,                    //[17,13]
+};
+
+static KEYS: phf::Map<&'static str, ID> = phf_map! {
+    "resourcetype" => ID::ResourceType,
+    "active" => ID::Active,
+    "text" => ID::Text,
+    "status" => ID::Status,
+    "div" => ID::Div,
+    "name" => ID::Name,
+    "use" => ID::Use,
+    "given" => ID::Given,
+    "family" => ID::Family,
+    "id" => ID::Id,
+    "type" => ID::Type,
+    "system" => ID::System,
+    "value" => ID::Value,
+    "period" => ID::Period,
+    "start" => ID::Start,
+    "end" => ID::End,
+    "assigner" => ID::Assigner,
+    "reference" => ID::Reference,
+    "display" => ID::Display,
+    "version" => ID::Version,
+    "code" => ID::Code,
+    "userselected" => ID::UserSelected,
+    "coding" => ID::Coding,
+    "identifier" => ID::Identifier,
+    "telecom" => ID::Telecom,
+    "rank" => ID::Rank,
+    "gender" => ID::Gender,
+    "birthdate" => ID::BirthDate,
+    "deceased" => ID::Deceased,
+    "multiplebirth" => ID::MultipleBirth,
+    "address" => ID::Address,
+    "line" => ID::Line,
+    "city" => ID::City,
+    "district" => ID::District,
+    "state" => ID::State,
+    "postalcode" => ID::PostalCode,
+    "country" => ID::Country,
+    "maritalstatus" => ID::MaritalStatus,
+    "attachment" => ID::Attachment,
+    "photo" => ID::Photo,
+    "contenttype" => ID::ContentType,
+    "language" => ID::Language,
+    "data" => ID::Data,
+    "url" => ID::Url,
+    "size" => ID::Size,
+    "hash" => ID::Hash,
+    "title" => ID::Title,
+    "creation" => ID::Creation,
+    "height" => ID::Height,
+    "width" => ID::Width,
+    "frames" => ID::Frames,
+    "duration" => ID::Duration,
+    "pages" => ID::Pages,
+    "contact" => ID::Contact,
+    "relationship" => ID::Relationship,
+    "organization" => ID::Organization,
+    "communication" => ID::Communication,
+    "preferred" => ID::Preferred,
+    "generalpractitioner" => ID::GeneralPractitioner,
+    "managingorganization" => ID::ManagingOrganization,
+    "link" => ID::Link,
+    "other" => ID::Other,
+    "comparator" => ID::Comparator,
+    "unit" => ID::Unit,
+    "currency" => ID::Currency,
+    "low" => ID::Low,
+    "high" => ID::High,
+    "numerator" => ID::Numerator,
+    "denominator" => ID::Denominator,
+    "origin" => ID::Origin,
+    "interval" => ID::Interval,
+    "intervalunit" => ID::IntervalUnit,
+    "factor" => ID::Factor,
+    "lowerlimit" => ID::LowerLimit,
+    "upperlimit" => ID::UpperLimit,
+    "dimensions" => ID::Dimensions,
+    "codemap" => ID::CodeMap,
+    "offsets" => ID::Offsets,
+    "time" => ID::Time,
+    "when" => ID::When,
+    "who" => ID::Who,
+    "onbehalfof" => ID::OnBehalfOf,
+    "targetformat" => ID::TargetFormat,
+    "sigformat" => ID::SigFormat,
+    "event" => ID::Event,
+    "repeat" => ID::Repeat,
+    "count" => ID::Count,
+    "countmax" => ID::CountMax,
+    "durationmax" => ID::DurationMax,
+    "durationunit" => ID::DurationUnit,
+    "frequency" => ID::Frequency,
+    "frequencymax" => ID::FrequencyMax,
+    "periodmax" => ID::PeriodMax,
+    "periodunit" => ID::PeriodUnit,
+    "dayofweek" => ID::DayOfWeek,
+    "timeofday" => ID::TimeOfDay,
+    "offset" => ID::Offset,
+    "sequence" => ID::Sequence,
+    "additionalinstruction" => ID::AdditionalInstruction,
+    "patientinstruction" => ID::PatientInstruction,
+    "timing" => ID::Timing,
+    "asneeded" => ID::AsNeeded,
+    "asneededfor" => ID::AsNeededFor,
+    "site" => ID::Site,
+    "route" => ID::Route,
+    "method" => ID::Method,
+    "doseandrate" => ID::DoseAndRate,
+    "maxdoseperperiod" => ID::MaxDosePerPeriod,
+    "maxdoseperadministration" => ID::MaxDosePerAdministration,
+    "maxdoseperlifetime" => ID::MaxDosePerLifetime,
+    "meta" => ID::Meta,
+    "versionid" => ID::VersionId,
+    "lastupdated" => ID::LastUpdated,
+    "source" => ID::Source,
+    "profile" => ID::Profile,
+    "security" => ID::Security,
+    "tag" => ID::Tag,
+    "description" => ID::Description,
+    "expression" => ID::Expression,
+    "classifier" => ID::Classifier,
+    "label" => ID::Label,
+    "citation" => ID::Citation,
+    "document" => ID::Document,
+    "resource" => ID::Resource,
+    "resourcereference" => ID::ResourceReference,
+    "publicationstatus" => ID::PublicationStatus,
+    "publicationdate" => ID::PublicationDate,
+    "author" => ID::Author,
+    "bounds" => ID::Bounds,
+    "dose" => ID::Dose,
+    "rate" => ID::Rate,
+    "prefix" => ID::Prefix,
+    "suffix" => ID::Suffix,
+    "extension" => ID::Extension,
+    "modifierextension" => ID::ModifierExtension,
+    "implicitrules" => ID::ImplicitRules,
+    "instantiates" => ID::Instantiates,
+    "basedon" => ID::BasedOn,
+    "triggeredby" => ID::TriggeredBy,
+    "observation" => ID::Observation,
+    "reason" => ID::Reason,
+    "partof" => ID::PartOf,
+    "category" => ID::Category,
+    "subject" => ID::Subject,
+    "focus" => ID::Focus,
+    "encounter" => ID::Encounter,
+    "effective" => ID::Effective,
+    "issued" => ID::Issued,
+    "performer" => ID::Performer,
+    "dataabsentreason" => ID::DataAbsentReason,
+    "interpretation" => ID::Interpretation,
+    "note" => ID::Note,
+    "bodysite" => ID::BodySite,
+    "bodystructure" => ID::BodyStructure,
+    "specimen" => ID::Specimen,
+    "device" => ID::Device,
+    "referencerange" => ID::ReferenceRange,
+    "normalvalue" => ID::NormalValue,
+    "appliesto" => ID::AppliesTo,
+    "age" => ID::Age,
+    "hasmember" => ID::HasMember,
+    "derivedfrom" => ID::DerivedFrom,
+    "component" => ID::Component,
+    "clinicalstatus" => ID::ClinicalStatus,
+    "verificationstatus" => ID::VerificationStatus,
+    "severity" => ID::Severity,
+    "onset" => ID::Onset,
+    "abatement" => ID::Abatement,
+    "recordeddate" => ID::RecordedDate,
+    "participant" => ID::Participant,
+    "function" => ID::Function,
+    "actor" => ID::Actor,
+    "stage" => ID::Stage,
+    "summary" => ID::Summary,
+    "assessment" => ID::Assessment,
+    "evidence" => ID::Evidence,
+    "concept" => ID::Concept,
+    "class" => ID::Class,
+    "priority" => ID::Priority,
+    "servicetype" => ID::ServiceType,
+    "subjectstatus" => ID::SubjectStatus,
+    "episodeofcare" => ID::EpisodeOfCare,
+    "careteam" => ID::CareTeam,
+    "serviceprovider" => ID::ServiceProvider,
+    "appointment" => ID::Appointment,
+    "actualperiod" => ID::ActualPeriod,
+    "plannedstartdate" => ID::PlannedStartDate,
+    "plannedenddate" => ID::PlannedEndDate,
+    "length" => ID::Length,
+    "diagnosis" => ID::Diagnosis,
+    "condition" => ID::Condition,
+    "account" => ID::Account,
+    "dietpreference" => ID::DietPreference,
+    "specialarrangement" => ID::SpecialArrangement,
+    "specialcourtesy" => ID::SpecialCourtesy,
+    "admission" => ID::Admission,
+    "preadmissionidentifier" => ID::PreAdmissionIdentifier,
+    "admitsource" => ID::AdmitSource,
+    "readmission" => ID::ReAdmission,
+    "destination" => ID::Destination,
+    "dischargedisposition" => ID::DischargeDisposition,
+    "location" => ID::Location,
+    "form" => ID::Form,
+    "instantiatescanonical" => ID::InstantiatesCanonical,
+    "instantiatesuri" => ID::InstantiatesUri,
+    "statusreason" => ID::StatusReason,
+    "occurrence" => ID::Occurrence,
+    "recorded" => ID::Recorded,
+    "recorder" => ID::Recorder,
+    "reported" => ID::Reported,
+    "outcome" => ID::Outcome,
+    "report" => ID::Report,
+    "complication" => ID::Complication,
+    "followup" => ID::FollowUp,
+    "focaldevice" => ID::FocalDevice,
+    "action" => ID::Action,
+    "manipulated" => ID::Manipulated,
+    "used" => ID::Used,
+    "supportinginfo" => ID::SupportingInfo,
+    "criticality" => ID::Criticality,
+    "patient" => ID::Patient,
+    "lastoccurrence" => ID::LastOccurrence,
+    "reaction" => ID::Reaction,
+    "substance" => ID::Substance,
+    "manifestation" => ID::Manifestation,
+    "exposureroute" => ID::ExposureRoute,
+    "qualification" => ID::Qualification,
+    "issuer" => ID::Issuer,
+    "practitioner" => ID::Practitioner,
+    "specialty" => ID::Specialty,
+    "healthcareservice" => ID::HealthcareService,
+    "characteristic" => ID::Characteristic,
+    "availability" => ID::Availability,
+    "endpoint" => ID::Endpoint,
+    "alias" => ID::Alias,
+    "operationalstatus" => ID::OperationalStatus,
+    "mode" => ID::Mode,
+    "position" => ID::Position,
+    "longitude" => ID::Longitude,
+    "latitude" => ID::Latitude,
+    "altitude" => ID::Altitude,
+    "hoursofoperation" => ID::HoursOfOperation,
+    "virtualservice" => ID::VirtualService,
+    "providedby" => ID::ProvidedBy,
+    "offeredin" => ID::OfferedIn,
+    "comment" => ID::Comment,
+    "extradetails" => ID::ExtraDetails,
+    "coveragearea" => ID::CoverageArea,
+    "serviceprovisioncode" => ID::ServiceProvisionCode,
+    "eligibility" => ID::Eligibility,
+    "program" => ID::Program,
+    "referralmethod" => ID::ReferralMethod,
+    "appointmentrequired" => ID::AppointmentRequired,
+    "connectiontype" => ID::ConnectionType,
+    "environmenttype" => ID::EnvironmentType,
+    "payload" => ID::Payload,
+    "mimetype" => ID::MimeType,
+    "header" => ID::Header,
+    "availabletime" => ID::AvailableTime,
+    "notavailabletime" => ID::NotAvailableTime,
+    "daysofweek" => ID::DaysOfWeek,
+    "allday" => ID::AllDay,
+    "availablestarttime" => ID::AvailableStartTime,
+    "availableendtime" => ID::AvailableEndTime,
+    "during" => ID::During,
+    "purpose" => ID::Purpose,
+    "channeltype" => ID::ChannelType,
+    "additionalinfo" => ID::AdditionalInfo,
+    "maxparticipants" => ID::MaxParticipants,
+    "sessionkey" => ID::SessionKey,
+};
+
+static KEY_NAMES: phf::Map<u16, &'static str> = phf_map! {
+    4096u16 => "resourceType",
+    4097u16 => "active",
+    4098u16 => "text",
+    4099u16 => "status",
+    4100u16 => "div",
+    4101u16 => "name",
+    4102u16 => "use",
+    4103u16 => "given",
+    4104u16 => "family",
+    4105u16 => "id",
+    4106u16 => "type",
+    4107u16 => "system",
+    4108u16 => "value",
+    4109u16 => "period",
+    4110u16 => "start",
+    4111u16 => "end",
+    4112u16 => "assigner",
+    4113u16 => "reference",
+    4114u16 => "display",
+    4115u16 => "version",
+    4116u16 => "code",
+    4117u16 => "userSelected",
+    4118u16 => "coding",
+    4119u16 => "identifier",
+    4120u16 => "telecom",
+    4121u16 => "rank",
+    4122u16 => "gender",
+    4123u16 => "birthDate",
+    4124u16 => "deceased",
+    4125u16 => "multipleBirth",
+    4126u16 => "address",
+    4127u16 => "line",
+    4128u16 => "city",
+    4129u16 => "district",
+    4130u16 => "state",
+    4131u16 => "postalCode",
+    4132u16 => "country",
+    4133u16 => "maritalStatus",
+    4134u16 => "attachment",
+    4135u16 => "photo",
+    4136u16 => "contentType",
+    4137u16 => "language",
+    4138u16 => "data",
+    4139u16 => "url",
+    4140u16 => "size",
+    4141u16 => "hash",
+    4142u16 => "title",
+    4143u16 => "creation",
+    4144u16 => "height",
+    4145u16 => "width",
+    4146u16 => "frames",
+    4147u16 => "duration",
+    4148u16 => "pages",
+    4149u16 => "contact",
+    4150u16 => "relationship",
+    4151u16 => "organization",
+    4152u16 => "communication",
+    4153u16 => "preferred",
+    4154u16 => "generalPractitioner",
+    4155u16 => "managingOrganization",
+    4156u16 => "link",
+    4157u16 => "other",
+    4158u16 => "comparator",
+    4159u16 => "unit",
+    4160u16 => "currency",
+    4161u16 => "low",
+    4162u16 => "high",
+    4163u16 => "numerator",
+    4164u16 => "denominator",
+    4165u16 => "origin",
+    4166u16 => "interval",
+    4167u16 => "intervalUnit",
+    4168u16 => "factor",
+    4169u16 => "lowerLimit",
+    4170u16 => "upperLimit",
+    4171u16 => "dimensions",
+    4172u16 => "codeMap",
+    4173u16 => "offsets",
+    4174u16 => "time",
+    4175u16 => "when",
+    4176u16 => "who",
+    4177u16 => "onBehalfOf",
+    4178u16 => "targetFormat",
+    4179u16 => "sigFormat",
+    4180u16 => "event",
+    4181u16 => "repeat",
+    4182u16 => "count",
+    4183u16 => "countMax",
+    4184u16 => "durationMax",
+    4185u16 => "durationUnit",
+    4186u16 => "frequency",
+    4187u16 => "frequencyMax",
+    4188u16 => "periodMax",
+    4189u16 => "periodUnit",
+    4190u16 => "dayOfWeek",
+    4191u16 => "timeOfDay",
+    4192u16 => "offset",
+    4193u16 => "sequence",
+    4194u16 => "additionalInstruction",
+    4195u16 => "patientInstruction",
+    4196u16 => "timing",
+    4197u16 => "asNeeded",
+    4198u16 => "asNeededFor",
+    4199u16 => "site",
+    4200u16 => "route",
+    4201u16 => "method",
+    4202u16 => "doseAndRate",
+    4203u16 => "maxDosePerPeriod",
+    4204u16 => "maxDosePerAdministration",
+    4205u16 => "maxDosePerLifetime",
+    4206u16 => "meta",
+    4207u16 => "versionId",
+    4208u16 => "lastUpdated",
+    4209u16 => "source",
+    4210u16 => "profile",
+    4211u16 => "security",
+    4212u16 => "tag",
+    4213u16 => "description",
+    4214u16 => "expression",
+    4215u16 => "classifier",
+    4216u16 => "label",
+    4217u16 => "citation",
+    4218u16 => "document",
+    4219u16 => "resource",
+    4220u16 => "resourceReference",
+    4221u16 => "publicationStatus",
+    4222u16 => "publicationDate",
+    4223u16 => "author",
+    4224u16 => "bounds",
+    4225u16 => "dose",
+    4226u16 => "rate",
+    4227u16 => "prefix",
+    4228u16 => "suffix",
+    4229u16 => "extension",
+    4230u16 => "modifierExtension",
+    4231u16 => "implicitRules",
+    4232u16 => "instantiates",
+    4233u16 => "basedOn",
+    4234u16 => "triggeredBy",
+    4235u16 => "observation",
+    4236u16 => "reason",
+    4237u16 => "partOf",
+    4238u16 => "category",
+    4239u16 => "subject",
+    4240u16 => "focus",
+    4241u16 => "encounter",
+    4242u16 => "effective",
+    4243u16 => "issued",
+    4244u16 => "performer",
+    4245u16 => "dataAbsentReason",
+    4246u16 => "interpretation",
+    4247u16 => "note",
+    4248u16 => "bodySite",
+    4249u16 => "bodyStructure",
+    4250u16 => "specimen",
+    4251u16 => "device",
+    4252u16 => "referenceRange",
+    4253u16 => "normalValue",
+    4254u16 => "appliesTo",
+    4255u16 => "age",
+    4256u16 => "hasMember",
+    4257u16 => "derivedFrom",
+    4258u16 => "component",
+    4259u16 => "clinicalStatus",
+    4260u16 => "verificationStatus",
+    4261u16 => "severity",
+    4262u16 => "onset",
+    4263u16 => "abatement",
+    4264u16 => "recordedDate",
+    4265u16 => "participant",
+    4266u16 => "function",
+    4267u16 => "actor",
+    4268u16 => "stage",
+    4269u16 => "summary",
+    4270u16 => "assessment",
+    4271u16 => "evidence",
+    4272u16 => "concept",
+    4273u16 => "class",
+    4274u16 => "priority",
+    4275u16 => "serviceType",
+    4276u16 => "subjectStatus",
+    4277u16 => "episodeOfCare",
+    4278u16 => "careTeam",
+    4279u16 => "serviceProvider",
+    4280u16 => "appointment",
+    4281u16 => "actualPeriod",
+    4282u16 => "plannedStartDate",
+    4283u16 => "plannedEndDate",
+    4284u16 => "length",
+    4285u16 => "diagnosis",
+    4286u16 => "condition",
+    4287u16 => "account",
+    4288u16 => "dietPreference",
+    4289u16 => "specialArrangement",
+    4290u16 => "specialCourtesy",
+    4291u16 => "admission",
+    4292u16 => "preAdmissionIdentifier",
+    4293u16 => "admitSource",
+    4294u16 => "reAdmission",
+    4295u16 => "destination",
+    4296u16 => "dischargeDisposition",
+    4297u16 => "location",
+    4298u16 => "form",
+    4299u16 => "instantiatesCanonical",
+    4300u16 => "instantiatesUri",
+    4301u16 => "statusReason",
+    4302u16 => "occurrence",
+    4303u16 => "recorded",
+    4304u16 => "recorder",
+    4305u16 => "reported",
+    4306u16 => "outcome",
+    4307u16 => "report",
+    4308u16 => "complication",
+    4309u16 => "followUp",
+    4310u16 => "focalDevice",
+    4311u16 => "action",
+    4312u16 => "manipulated",
+    4313u16 => "used",
+    4314u16 => "supportingInfo",
+    4315u16 => "criticality",
+    4316u16 => "patient",
+    4317u16 => "lastOccurrence",
+    4318u16 => "reaction",
+    4319u16 => "substance",
+    4320u16 => "manifestation",
+    4321u16 => "exposureRoute",
+    4322u16 => "qualification",
+    4323u16 => "issuer",
+    4324u16 => "practitioner",
+    4325u16 => "specialty",
+    4326u16 => "healthcareService",
+    4327u16 => "characteristic",
+    4328u16 => "availability",
+    4329u16 => "endpoint",
+    4330u16 => "alias",
+    4331u16 => "operationalStatus",
+    4332u16 => "mode",
+    4333u16 => "position",
+    4334u16 => "longitude",
+    4335u16 => "latitude",
+    4336u16 => "altitude",
+    4337u16 => "hoursOfOperation",
+    4338u16 => "virtualService",
+    4339u16 => "providedBy",
+    4340u16 => "offeredIn",
+    4341u16 => "comment",
+    4342u16 => "extraDetails",
+    4343u16 => "coverageArea",
+    4344u16 => "serviceProvisionCode",
+    4345u16 => "eligibility",
+    4346u16 => "program",
+    4347u16 => "referralMethod",
+    4348u16 => "appointmentRequired",
+    4349u16 => "connectionType",
+    4350u16 => "environmentType",
+    4351u16 => "payload",
+    4352u16 => "mimeType",
+    4353u16 => "header",
+    4354u16 => "availableTime",
+    4355u16 => "notAvailableTime",
+    4356u16 => "daysOfWeek",
+    4357u16 => "allDay",
+    4358u16 => "availableStartTime",
+    4359u16 => "availableEndTime",
+    4360u16 => "during",
+    4361u16 => "purpose",
+    4362u16 => "channelType",
+    4363u16 => "additionalInfo",
+    4364u16 => "maxParticipants",
+    4365u16 => "sessionKey",
+};
+
+///Mapping of [`ID`]s to the suffix of a choice type key, e.g. `deceased` + `Boolean`.
+///A SimpleQuantity is written as `Quantity`.
+static TYPE_SUFFIXES: phf::Map<u16, &'static str> = phf_map! {
+    1u16 => "String",
+    2u16 => "Boolean",
+    3u16 => "Code",
+    4u16 => "Id",
+    5u16 => "Uri",
+    6u16 => "DateTime",
+    7u16 => "PositiveInt",
+    8u16 => "Date",
+    9u16 => "Integer",
+    10u16 => "Integer64",
+    11u16 => "Decimal",
+    12u16 => "Base64Binary",
+    13u16 => "Url",
+    14u16 => "Instant",
+    15u16 => "Time",
+    16u16 => "UnsignedInt",
+    17u16 => "Canonical",
+    18u16 => "Oid",
+    19u16 => "Uuid",
+    20u16 => "Markdown",
+    256u16 => "Xhtml",
+    512u16 => "Narrative",
+    513u16 => "HumanName",
+    514u16 => "Identifier",
+    515u16 => "CodeableConcept",
+    516u16 => "Period",
+    517u16 => "Reference",
+    518u16 => "Coding",
+    519u16 => "ContactPoint",
+    520u16 => "Address",
+    521u16 => "Attachment",
+    525u16 => "Quantity",
+    526u16 => "Quantity",
+    527u16 => "Age",
+    528u16 => "Duration",
+    529u16 => "Count",
+    530u16 => "Distance",
+    531u16 => "Money",
+    532u16 => "Range",
+    533u16 => "Ratio",
+    534u16 => "SampledData",
+    535u16 => "Annotation",
+    536u16 => "Signature",
+    537u16 => "Timing",
+    539u16 => "Dosage",
+    541u16 => "ContactDetail",
+    542u16 => "Meta",
+    543u16 => "Expression",
+    544u16 => "RelatedArtifact",
+    545u16 => "Extension",
+    547u16 => "CodeableReference",
+    562u16 => "ExtendedContactDetail",
+    563u16 => "Availability",
+    566u16 => "VirtualServiceDetail",
+};
+
+///The [`ID`] a key was first given: the keys of the first Patient table and the elements
+///of all resources. Elements of datatypes are only in the table of their type.
+static EXPECTS: phf::Map<u16, ID> = phf_map! {
+    4096u16 => ID::STRING,                      //resourceType
+    4097u16 => ID::BOOLEAN,                     //active
+    4098u16 => ID::NARRATIVE,                   //text
+    4099u16 => ID::STRING,                      //status
+    4100u16 => ID::XHTML,                       //div
+    4101u16 => ID::LHUMANNAME,                  //name
+    4102u16 => ID::CODE,                        //use
+    4103u16 => ID::LSTRING,                     //given
+    4104u16 => ID::STRING,                      //family
+    4105u16 => ID::ID,                          //id
+    4106u16 => ID::CODABLECONCEPT,              //type
+    4107u16 => ID::URI,                         //system
+    4108u16 => ID::STRING,                      //value
+    4109u16 => ID::PERIOD,                      //period
+    4110u16 => ID::DATETIME,                    //start
+    4111u16 => ID::DATETIME,                    //end
+    4112u16 => ID::REFERENCE,                   //assigner
+    4113u16 => ID::STRING,                      //reference
+    4114u16 => ID::STRING,                      //display
+    4115u16 => ID::STRING,                      //version
+    4116u16 => ID::CODE,                        //code
+    4117u16 => ID::BOOLEAN,                     //userSelected
+    4118u16 => ID::LCODING,                     //coding
+    4119u16 => ID::LIDENTIFIER,                 //identifier
+    4120u16 => ID::LCONTACTPOINT,               //telecom
+    4121u16 => ID::POSITIVEINT,                 //rank
+    4122u16 => ID::CODE,                        //gender
+    4123u16 => ID::DATE,                        //birthDate
+    4124u16 => ID::MULTIPLETYPES,               //deceased
+    4125u16 => ID::MULTIPLETYPES,               //multipleBirth
+    4126u16 => ID::LADDRESS,                    //address
+    4127u16 => ID::LSTRING,                     //line
+    4128u16 => ID::STRING,                      //city
+    4129u16 => ID::STRING,                      //district
+    4130u16 => ID::STRING,                      //state
+    4131u16 => ID::STRING,                      //postalCode
+    4132u16 => ID::STRING,                      //country
+    4133u16 => ID::CODABLECONCEPT,              //maritalStatus
+    4134u16 => ID::LATTACHMENT,                 //attachment
+    4135u16 => ID::LATTACHMENT,                 //photo
+    4136u16 => ID::CODE,                        //contentType
+    4137u16 => ID::CODE,                        //language
+    4138u16 => ID::BASE64BINARY,                //data
+    4139u16 => ID::URL,                         //url
+    4140u16 => ID::INTEGER64,                   //size
+    4141u16 => ID::BASE64BINARY,                //hash
+    4142u16 => ID::STRING,                      //title
+    4143u16 => ID::DATETIME,                    //creation
+    4144u16 => ID::POSITIVEINT,                 //height
+    4145u16 => ID::POSITIVEINT,                 //width
+    4146u16 => ID::POSITIVEINT,                 //frames
+    4147u16 => ID::DECIMAL,                     //duration
+    4148u16 => ID::POSITIVEINT,                 //pages
+    4149u16 => ID::LBACKBONECONTACT,            //contact
+    4150u16 => ID::LCODABLECONCEPT,             //relationship
+    4151u16 => ID::REFERENCE,                   //organization
+    4152u16 => ID::LBACKBONECOMMUNICATION,      //communication
+    4153u16 => ID::BOOLEAN,                     //preferred
+    4154u16 => ID::LREFERENCE,                  //generalPractitioner
+    4155u16 => ID::REFERENCE,                   //managingOrganization
+    4156u16 => ID::LBACKBONELINK,               //link
+    4157u16 => ID::REFERENCE,                   //other
+    4201u16 => ID::CODABLECONCEPT,              //method
+    4206u16 => ID::META,                        //meta
+    4213u16 => ID::STRING,                      //description
+    4229u16 => ID::LEXTENSION,                  //extension
+    4230u16 => ID::LEXTENSION,                  //modifierExtension
+    4231u16 => ID::URI,                         //implicitRules
+    4232u16 => ID::MULTIPLETYPES,               //instantiates
+    4233u16 => ID::LREFERENCE,                  //basedOn
+    4234u16 => ID::LBACKBONETRIGGEREDBY,        //triggeredBy
+    4236u16 => ID::LCODEABLEREFERENCE,          //reason
+    4237u16 => ID::LREFERENCE,                  //partOf
+    4238u16 => ID::LCODABLECONCEPT,             //category
+    4239u16 => ID::REFERENCE,                   //subject
+    4240u16 => ID::LREFERENCE,                  //focus
+    4241u16 => ID::REFERENCE,                   //encounter
+    4242u16 => ID::MULTIPLETYPES,               //effective
+    4243u16 => ID::INSTANT,                     //issued
+    4244u16 => ID::LREFERENCE,                  //performer
+    4245u16 => ID::CODABLECONCEPT,              //dataAbsentReason
+    4246u16 => ID::LCODABLECONCEPT,             //interpretation
+    4247u16 => ID::LANNOTATION,                 //note
+    4248u16 => ID::CODABLECONCEPT,              //bodySite
+    4249u16 => ID::REFERENCE,                   //bodyStructure
+    4250u16 => ID::REFERENCE,                   //specimen
+    4251u16 => ID::REFERENCE,                   //device
+    4252u16 => ID::LBACKBONEREFERENCERANGE,     //referenceRange
+    4256u16 => ID::LREFERENCE,                  //hasMember
+    4257u16 => ID::LREFERENCE,                  //derivedFrom
+    4258u16 => ID::LBACKBONECOMPONENT,          //component
+    4259u16 => ID::CODABLECONCEPT,              //clinicalStatus
+    4260u16 => ID::CODABLECONCEPT,              //verificationStatus
+    4261u16 => ID::CODABLECONCEPT,              //severity
+    4262u16 => ID::MULTIPLETYPES,               //onset
+    4263u16 => ID::MULTIPLETYPES,               //abatement
+    4264u16 => ID::DATETIME,                    //recordedDate
+    4265u16 => ID::LBACKBONEPARTICIPANT,        //participant
+    4268u16 => ID::LBACKBONESTAGE,              //stage
+    4271u16 => ID::LCODEABLEREFERENCE,          //evidence
+    4273u16 => ID::LCODABLECONCEPT,             //class
+    4274u16 => ID::CODABLECONCEPT,              //priority
+    4275u16 => ID::LCODEABLEREFERENCE,          //serviceType
+    4276u16 => ID::CODABLECONCEPT,              //subjectStatus
+    4277u16 => ID::LREFERENCE,                  //episodeOfCare
+    4278u16 => ID::LREFERENCE,                  //careTeam
+    4279u16 => ID::REFERENCE,                   //serviceProvider
+    4280u16 => ID::LREFERENCE,                  //appointment
+    4281u16 => ID::PERIOD,                      //actualPeriod
+    4282u16 => ID::DATETIME,                    //plannedStartDate
+    4283u16 => ID::DATETIME,                    //plannedEndDate
+    4284u16 => ID::DURATION,                    //length
+    4285u16 => ID::LBACKBONEENCOUNTERDIAGNOSIS, //diagnosis
+    4287u16 => ID::LREFERENCE,                  //account
+    4288u16 => ID::LCODABLECONCEPT,             //dietPreference
+    4289u16 => ID::LCODABLECONCEPT,             //specialArrangement
+    4290u16 => ID::LCODABLECONCEPT,             //specialCourtesy
+    4291u16 => ID::BACKBONEADMISSION,           //admission
+    4297u16 => ID::LBACKBONEENCOUNTERLOCATION,  //location
+    4298u16 => ID::CODABLECONCEPT,              //form
+    4299u16 => ID::LCANONICAL,                  //instantiatesCanonical
+    4300u16 => ID::LURI,                        //instantiatesUri
+    4301u16 => ID::CODABLECONCEPT,              //statusReason
+    4302u16 => ID::MULTIPLETYPES,               //occurrence
+    4303u16 => ID::DATETIME,                    //recorded
+    4304u16 => ID::REFERENCE,                   //recorder
+    4305u16 => ID::MULTIPLETYPES,               //reported
+    4306u16 => ID::CODABLECONCEPT,              //outcome
+    4307u16 => ID::LREFERENCE,                  //report
+    4308u16 => ID::LCODEABLEREFERENCE,          //complication
+    4309u16 => ID::LCODABLECONCEPT,             //followUp
+    4310u16 => ID::LBACKBONEFOCALDEVICE,        //focalDevice
+    4313u16 => ID::LCODEABLEREFERENCE,          //used
+    4314u16 => ID::LREFERENCE,                  //supportingInfo
+    4315u16 => ID::CODE,                        //criticality
+    4316u16 => ID::REFERENCE,                   //patient
+    4317u16 => ID::DATETIME,                    //lastOccurrence
+    4318u16 => ID::LBACKBONEREACTION,           //reaction
+    4322u16 => ID::LBACKBONEQUALIFICATION,      //qualification
+    4324u16 => ID::REFERENCE,                   //practitioner
+    4325u16 => ID::LCODABLECONCEPT,             //specialty
+    4326u16 => ID::LREFERENCE,                  //healthcareService
+    4327u16 => ID::LCODABLECONCEPT,             //characteristic
+    4328u16 => ID::LAVAILABILITY,               //availability
+    4329u16 => ID::LREFERENCE,                  //endpoint
+    4330u16 => ID::LSTRING,                     //alias
+    4331u16 => ID::CODING,                      //operationalStatus
+    4332u16 => ID::CODE,                        //mode
+    4333u16 => ID::BACKBONEPOSITION,            //position
+    4337u16 => ID::LAVAILABILITY,               //hoursOfOperation
+    4338u16 => ID::LVIRTUALSERVICEDETAIL,       //virtualService
+    4339u16 => ID::REFERENCE,                   //providedBy
+    4340u16 => ID::LREFERENCE,                  //offeredIn
+    4341u16 => ID::MARKDOWN,                    //comment
+    4342u16 => ID::MARKDOWN,                    //extraDetails
+    4343u16 => ID::LREFERENCE,                  //coverageArea
+    4344u16 => ID::LCODABLECONCEPT,             //serviceProvisionCode
+    4345u16 => ID::LBACKBONEELIGIBILITY,        //eligibility
+    4346u16 => ID::LCODABLECONCEPT,             //program
+    4347u16 => ID::LCODABLECONCEPT,             //referralMethod
+    4348u16 => ID::BOOLEAN,                     //appointmentRequired
+    4349u16 => ID::LCODABLECONCEPT,             //connectionType
+    4350u16 => ID::LCODABLECONCEPT,             //environmentType
+    4351u16 => ID::LBACKBONEPAYLOAD,            //payload
+    4353u16 => ID::LSTRING,                     //header
+};
+
+static HAS_SUBS: phf::Set<u16> = phf_set! {
+    512u16,  //NARRATIVE
+    513u16,  //HUMANNAME
+    514u16,  //IDENTIFIER
+    515u16,  //CODABLECONCEPT
+    516u16,  //PERIOD
+    517u16,  //REFERENCE
+    518u16,  //CODING
+    519u16,  //CONTACTPOINT
+    520u16,  //ADDRESS
+    521u16,  //ATTACHMENT
+    522u16,  //BACKBONECONTACT
+    523u16,  //BACKBONECOMMUNICATION
+    524u16,  //BACKBONELINK
+    525u16,  //QUANTITY
+    526u16,  //SIMPLEQUANTITY
+    527u16,  //AGE
+    528u16,  //DURATION
+    529u16,  //COUNT
+    530u16,  //DISTANCE
+    531u16,  //MONEY
+    532u16,  //RANGE
+    533u16,  //RATIO
+    534u16,  //SAMPLEDDATA
+    535u16,  //ANNOTATION
+    536u16,  //SIGNATURE
+    537u16,  //TIMING
+    538u16,  //BACKBONETIMINGREPEAT
+    539u16,  //DOSAGE
+    540u16,  //BACKBONEDOSEANDRATE
+    541u16,  //CONTACTDETAIL
+    542u16,  //META
+    543u16,  //EXPRESSION
+    544u16,  //RELATEDARTIFACT
+    545u16,  //EXTENSION
+    546u16,  //ELEMENT
+    547u16,  //CODEABLEREFERENCE
+    548u16,  //BACKBONETRIGGEREDBY
+    549u16,  //BACKBONEREFERENCERANGE
+    550u16,  //BACKBONECOMPONENT
+    551u16,  //BACKBONEPARTICIPANT
+    552u16,  //BACKBONESTAGE
+    553u16,  //BACKBONEENCOUNTERPARTICIPANT
+    554u16,  //BACKBONEENCOUNTERREASON
+    555u16,  //BACKBONEENCOUNTERDIAGNOSIS
+    556u16,  //BACKBONEADMISSION
+    557u16,  //BACKBONEENCOUNTERLOCATION
+    558u16,  //BACKBONEPROCEDUREPERFORMER
+    559u16,  //BACKBONEFOCALDEVICE
+    560u16,  //BACKBONEREACTION
+    561u16,  //BACKBONEQUALIFICATION
+    562u16,  //EXTENDEDCONTACTDETAIL
+    563u16,  //AVAILABILITY
+    564u16,  //BACKBONEAVAILABLETIME
+    565u16,  //BACKBONENOTAVAILABLETIME
+    566u16,  //VIRTUALSERVICEDETAIL
+    567u16,  //BACKBONEPOSITION
+    568u16,  //BACKBONEELIGIBILITY
+    569u16,  //BACKBONEPAYLOAD
+    2048u16, //LHUMANNAME
+    2049u16, //LIDENTIFIER
+    2050u16, //LCODING
+    2051u16, //LCONTACTPOINT
+    2052u16, //LADDRESS
+    2053u16, //LATTACHMENT
+    2054u16, //LBACKBONECONTACT
+    2055u16, //LCODABLECONCEPT
+    2056u16, //LBACKBONECOMMUNICATION
+    2057u16, //LBACKBONELINK
+    2058u16, //LREFERENCE
+    2059u16, //LANNOTATION
+    2060u16, //LSIGNATURE
+    2061u16, //LDOSAGE
+    2062u16, //LBACKBONEDOSEANDRATE
+    2063u16, //LCONTACTDETAIL
+    2064u16, //LRELATEDARTIFACT
+    2065u16, //LRATIO
+    2066u16, //LEXTENSION
+    2067u16, //LELEMENT
+    2068u16, //LCODEABLEREFERENCE
+    2069u16, //LBACKBONETRIGGEREDBY
+    2070u16, //LBACKBONEREFERENCERANGE
+    2071u16, //LBACKBONECOMPONENT
+    2072u16, //LBACKBONEPARTICIPANT
+    2073u16, //LBACKBONESTAGE
+    2074u16, //LBACKBONEENCOUNTERPARTICIPANT
+    2075u16, //LBACKBONEENCOUNTERREASON
+    2076u16, //LBACKBONEENCOUNTERDIAGNOSIS
+    2077u16, //LBACKBONEENCOUNTERLOCATION
+    2078u16, //LBACKBONEPROCEDUREPERFORMER
+    2079u16, //LBACKBONEFOCALDEVICE
+    2080u16, //LBACKBONEREACTION
+    2081u16, //LBACKBONEQUALIFICATION
+    2082u16, //LEXTENDEDCONTACTDETAIL
+    2083u16, //LAVAILABILITY
+    2084u16, //LBACKBONEAVAILABLETIME
+    2085u16, //LBACKBONENOTAVAILABLETIME
+    2086u16, //LVIRTUALSERVICEDETAIL
+    2087u16, //LBACKBONEELIGIBILITY
+    2088u16, //LBACKBONEPAYLOAD
+};
+
+static ADDRESS_EXPECTS: phf::Map<u16, ID> = phf_map! {
+    4102u16 => ID::CODE,    //use [code] 0..1
+    4106u16 => ID::CODE,    //type [code] 0..1
+    4098u16 => ID::STRING,  //text [string] 0..1
+    4127u16 => ID::LSTRING, //line [string] 0..*
+    4128u16 => ID::STRING,  //city [string] 0..1
+    4129u16 => ID::STRING,  //district [string] 0..1
+    4130u16 => ID::STRING,  //state [string] 0..1
+    4131u16 => ID::STRING,  //postalCode [string] 0..1
+    4132u16 => ID::STRING,  //country [string] 0..1
+    4109u16 => ID::PERIOD,  //period [Period] 0..1
+};
+
+static AGE_EXPECTS: phf::Map<u16, ID> = phf_map! {
+    4108u16 => ID::DECIMAL, //value [decimal] 0..1
+    4158u16 => ID::CODE,    //comparator [code] 0..1
+    4159u16 => ID::STRING,  //unit [string] 0..1
+    4107u16 => ID::URI,     //system [uri] 0..1
+    4116u16 => ID::CODE,    //code [code] 0..1
+};
+
+static BACKBONEPARTICIPANT_EXPECTS: phf::Map<u16, ID> = phf_map! {
+    4266u16 => ID::CODABLECONCEPT, //function [CodeableConcept] 0..1
+    4267u16 => ID::REFERENCE,      //actor [Reference] 1..1
+};
+
+static BACKBONEREACTION_EXPECTS: phf::Map<u16, ID> = phf_map! {
+    4319u16 => ID::CODABLECONCEPT,     //substance [CodeableConcept] 0..1
+    4320u16 => ID::LCODEABLEREFERENCE, //manifestation [CodeableReference] 1..*
+    4213u16 => ID::STRING,             //description [string] 0..1
+    4262u16 => ID::DATETIME,           //onset [dateTime] 0..1
+    4261u16 => ID::CODE,               //severity [code] 0..1
+    4321u16 => ID::CODABLECONCEPT,     //exposureRoute [CodeableConcept] 0..1
+    4247u16 => ID::LANNOTATION,        //note [Annotation] 0..*
+};
+
+static ANNOTATION_EXPECTS: phf::Map<u16, ID> = phf_map! {
+    4223u16 => ID::MULTIPLETYPES, //author[x] [Reference|string] 0..1
+    4174u16 => ID::DATETIME,      //time [dateTime] 0..1
+    4098u16 => ID::MARKDOWN,      //text [markdown] 1..1
+};
+
+static ATTACHMENT_EXPECTS: phf::Map<u16, ID> = phf_map! {
+    4136u16 => ID::CODE,         //contentType [code] 0..1
+    4137u16 => ID::CODE,         //language [code] 0..1
+    4138u16 => ID::BASE64BINARY, //data [base64Binary] 0..1
+    4139u16 => ID::URL,          //url [url] 0..1
+    4140u16 => ID::INTEGER64,    //size [integer64] 0..1
+    4141u16 => ID::BASE64BINARY, //hash [base64Binary] 0..1
+    4142u16 => ID::STRING,       //title [string] 0..1
+    4143u16 => ID::DATETIME,     //creation [dateTime] 0..1
+    4144u16 => ID::POSITIVEINT,  //height [positiveInt] 0..1
+    4145u16 => ID::POSITIVEINT,  //width [positiveInt] 0..1
+    4146u16 => ID::POSITIVEINT,  //frames [positiveInt] 0..1
+    4147u16 => ID::DECIMAL,      //duration [decimal] 0..1
+    4148u16 => ID::POSITIVEINT,  //pages [positiveInt] 0..1
+};
+
+static AVAILABILITY_EXPECTS: phf::Map<u16, ID> = phf_map! {
+    4354u16 => ID::LBACKBONEAVAILABLETIME,    //availableTime [BackboneElement] 0..*
+    4355u16 => ID::LBACKBONENOTAVAILABLETIME, //notAvailableTime [BackboneElement] 0..*
+};
+
+static BACKBONEAVAILABLETIME_EXPECTS: phf::Map<u16, ID> = phf_map! {
+    4356u16 => ID::LCODE,   //daysOfWeek [code] 0..*
+    4357u16 => ID::BOOLEAN, //allDay [boolean] 0..1
+    4358u16 => ID::TIME,    //availableStartTime [time] 0..1
+    4359u16 => ID::TIME,    //availableEndTime [time] 0..1
+};
+
+static BACKBONENOTAVAILABLETIME_EXPECTS: phf::Map<u16, ID> = phf_map! {
+    4213u16 => ID::STRING, //description [string] 0..1
+    4360u16 => ID::PERIOD, //during [Period] 0..1
+};
+
+static CODABLECONCEPT_EXPECTS: phf::Map<u16, ID> = phf_map! {
+    4098u16 => ID::STRING,  //text [string] 0..1
+    4118u16 => ID::LCODING, //coding [Coding] 0..*
+};
+
+static CODEABLEREFERENCE_EXPECTS: phf::Map<u16, ID> = phf_map! {
+    4272u16 => ID::CODABLECONCEPT, //concept [CodeableConcept] 0..1
+    4113u16 => ID::REFERENCE,      //reference [Reference] 0..1
+};
+
+static CODING_EXPECTS: phf::Map<u16, ID> = phf_map! {
+    4107u16 => ID::URI,     //system [uri] 0..1
+    4115u16 => ID::STRING,  //version [string] 0..1
+    4116u16 => ID::CODE,    //code [code] 0..1
+    4114u16 => ID::STRING,  //display [string] 0..1
+    4117u16 => ID::BOOLEAN, //userSelected [boolean] 0..1
+};
+
+static BACKBONESTAGE_EXPECTS: phf::Map<u16, ID> = phf_map! {
+    4269u16 => ID::CODABLECONCEPT, //summary [CodeableConcept] 0..1
+    4270u16 => ID::LREFERENCE,     //assessment [Reference] 0..*
+    4106u16 => ID::CODABLECONCEPT, //type [CodeableConcept] 0..1
+};
+
+static CONTACTDETAIL_EXPECTS: phf::Map<u16, ID> = phf_map! {
+    4101u16 => ID::STRING,        //name [string] 0..1
+    4120u16 => ID::LCONTACTPOINT, //telecom [ContactPoint] 0..*
+};
+
+static CONTACTPOINT_EXPECTS: phf::Map<u16, ID> = phf_map! {
+    4102u16 => ID::CODE,        //use [code] 0..1
+    4107u16 => ID::URI,         //system [uri] 0..1
+    4108u16 => ID::STRING,      //value [string] 0..1
+    4109u16 => ID::PERIOD,      //period [Period] 0..1
+    4121u16 => ID::POSITIVEINT, //rank [positiveInt] 0..1
+};
+
+static COUNT_EXPECTS: phf::Map<u16, ID> = phf_map! {
+    4108u16 => ID::DECIMAL, //value [decimal] 0..1
+    4158u16 => ID::CODE,    //comparator [code] 0..1
+    4159u16 => ID::STRING,  //unit [string] 0..1
+    4107u16 => ID::URI,     //system [uri] 0..1
+    4116u16 => ID::CODE,    //code [code] 0..1
+};
+
+static DISTANCE_EXPECTS: phf::Map<u16, ID> = phf_map! {
+    4108u16 => ID::DECIMAL, //value [decimal] 0..1
+    4158u16 => ID::CODE,    //comparator [code] 0..1
+    4159u16 => ID::STRING,  //unit [string] 0..1
+    4107u16 => ID::URI,     //system [uri] 0..1
+    4116u16 => ID::CODE,    //code [code] 0..1
+};
+
+static DOSAGE_EXPECTS: phf::Map<u16, ID> = phf_map! {
+    4193u16 => ID::INTEGER,              //sequence [integer] 0..1
+    4098u16 => ID::STRING,               //text [string] 0..1
+    4194u16 => ID::LCODABLECONCEPT,      //additionalInstruction [CodeableConcept] 0..*
+    4195u16 => ID::STRING,               //patientInstruction [string] 0..1
+    4196u16 => ID::TIMING,               //timing [Timing] 0..1
+    4197u16 => ID::BOOLEAN,              //asNeeded [boolean] 0..1
+    4198u16 => ID::LCODABLECONCEPT,      //asNeededFor [CodeableConcept] 0..*
+    4199u16 => ID::CODABLECONCEPT,       //site [CodeableConcept] 0..1
+    4200u16 => ID::CODABLECONCEPT,       //route [CodeableConcept] 0..1
+    4201u16 => ID::CODABLECONCEPT,       //method [CodeableConcept] 0..1
+    4202u16 => ID::LBACKBONEDOSEANDRATE, //doseAndRate [BackboneElement] 0..*
+    4203u16 => ID::LRATIO,               //maxDosePerPeriod [Ratio] 0..*
+    4204u16 => ID::SIMPLEQUANTITY,       //maxDosePerAdministration [SimpleQuantity] 0..1
+    4205u16 => ID::SIMPLEQUANTITY,       //maxDosePerLifetime [SimpleQuantity] 0..1
+};
+
+static BACKBONEDOSEANDRATE_EXPECTS: phf::Map<u16, ID> = phf_map! {
+    4106u16 => ID::CODABLECONCEPT, //type [CodeableConcept] 0..1
+    4225u16 => ID::MULTIPLETYPES,  //dose[x] [Range|SimpleQuantity] 0..1
+    4226u16 => ID::MULTIPLETYPES,  //rate[x] [Ratio|Range|SimpleQuantity] 0..1
+};
+
+static DURATION_EXPECTS: phf::Map<u16, ID> = phf_map! {
+    4108u16 => ID::DECIMAL, //value [decimal] 0..1
+    4158u16 => ID::CODE,    //comparator [code] 0..1
+    4159u16 => ID::STRING,  //unit [string] 0..1
+    4107u16 => ID::URI,     //system [uri] 0..1
+    4116u16 => ID::CODE,    //code [code] 0..1
+};
+
+static BACKBONEENCOUNTERPARTICIPANT_EXPECTS: phf::Map<u16, ID> = phf_map! {
+    4106u16 => ID::LCODABLECONCEPT, //type [CodeableConcept] 0..*
+    4109u16 => ID::PERIOD,          //period [Period] 0..1
+    4267u16 => ID::REFERENCE,       //actor [Reference] 0..1
+};
+
+static BACKBONEENCOUNTERREASON_EXPECTS: phf::Map<u16, ID> = phf_map! {
+    4102u16 => ID::LCODABLECONCEPT,    //use [CodeableConcept] 0..*
+    4108u16 => ID::LCODEABLEREFERENCE, //value [CodeableReference] 0..*
+};
+
+static BACKBONEENCOUNTERDIAGNOSIS_EXPECTS: phf::Map<u16, ID> = phf_map! {
+    4286u16 => ID::LCODEABLEREFERENCE, //condition [CodeableReference] 0..*
+    4102u16 => ID::LCODABLECONCEPT,    //use [CodeableConcept] 0..*
+};
+
+static BACKBONEADMISSION_EXPECTS: phf::Map<u16, ID> = phf_map! {
+    4292u16 => ID::IDENTIFIER,     //preAdmissionIdentifier [Identifier] 0..1
+    4165u16 => ID::REFERENCE,      //origin [Reference] 0..1
+    4293u16 => ID::CODABLECONCEPT, //admitSource [CodeableConcept] 0..1
+    4294u16 => ID::CODABLECONCEPT, //reAdmission [CodeableConcept] 0..1
+    4295u16 => ID::REFERENCE,      //destination [Reference] 0..1
+    4296u16 => ID::CODABLECONCEPT, //dischargeDisposition [CodeableConcept] 0..1
+};
+
+static BACKBONEENCOUNTERLOCATION_EXPECTS: phf::Map<u16, ID> = phf_map! {
+    4297u16 => ID::REFERENCE,      //location [Reference] 1..1
+    4099u16 => ID::CODE,           //status [code] 0..1
+    4298u16 => ID::CODABLECONCEPT, //form [CodeableConcept] 0..1
+    4109u16 => ID::PERIOD,         //period [Period] 0..1
+};
+
+static BACKBONEPAYLOAD_EXPECTS: phf::Map<u16, ID> = phf_map! {
+    4106u16 => ID::LCODABLECONCEPT, //type [CodeableConcept] 0..*
+    4352u16 => ID::LCODE,           //mimeType [code] 0..*
+};
+
+static EXPRESSION_EXPECTS: phf::Map<u16, ID> = phf_map! {
+    4213u16 => ID::STRING, //description [string] 0..1
+    4101u16 => ID::CODE,   //name [code] 0..1
+    4137u16 => ID::CODE,   //language [code] 0..1
+    4214u16 => ID::STRING, //expression [string] 0..1
+    4113u16 => ID::URI,    //reference [uri] 0..1
+};
+
+static EXTENDEDCONTACTDETAIL_EXPECTS: phf::Map<u16, ID> = phf_map! {
+    4361u16 => ID::CODABLECONCEPT, //purpose [CodeableConcept] 0..1
+    4101u16 => ID::LHUMANNAME,     //name [HumanName] 0..*
+    4120u16 => ID::LCONTACTPOINT,  //telecom [ContactPoint] 0..*
+    4126u16 => ID::ADDRESS,        //address [Address] 0..1
+    4151u16 => ID::REFERENCE,      //organization [Reference] 0..1
+    4109u16 => ID::PERIOD,         //period [Period] 0..1
+};
+
+static EXTENSION_EXPECTS: phf::Map<u16, ID> = phf_map! {
+    4139u16 => ID::URI,           //url [uri] 1..1
+    4108u16 => ID::MULTIPLETYPES, //value[x] [base64Binary|boolean|canonical|code|date|dateTime|decimal|id|instant|integer|integer64|markdown|oid|positiveInt|string|time|unsignedInt|uri|url|uuid|Address|Age|Annotation|Attachment|CodeableConcept|Coding|ContactPoint|Count|Distance|Duration|HumanName|Identifier|Money|Period|Quantity|Range|Ratio|Reference|SampledData|Signature|Timing|ContactDetail|Expression|RelatedArtifact|Dosage|Meta|CodeableReference|Availability|ExtendedContactDetail|VirtualServiceDetail] 0..1
+};
+
+static BACKBONEELIGIBILITY_EXPECTS: phf::Map<u16, ID> = phf_map! {
+    4116u16 => ID::CODABLECONCEPT, //code [CodeableConcept] 0..1
+    4341u16 => ID::MARKDOWN,       //comment [markdown] 0..1
+};
+
+static HUMANNAME_EXPECTS: phf::Map<u16, ID> = phf_map! {
+    4102u16 => ID::CODE,    //use [code] 0..1
+    4098u16 => ID::STRING,  //text [string] 0..1
+    4104u16 => ID::STRING,  //family [string] 0..1
+    4103u16 => ID::LSTRING, //given [string] 0..*
+    4227u16 => ID::LSTRING, //prefix [string] 0..*
+    4228u16 => ID::LSTRING, //suffix [string] 0..*
+    4109u16 => ID::PERIOD,  //period [Period] 0..1
+};
+
+static IDENTIFIER_EXPECTS: phf::Map<u16, ID> = phf_map! {
+    4102u16 => ID::CODE,           //use [code] 0..1
+    4106u16 => ID::CODABLECONCEPT, //type [CodeableConcept] 0..1
+    4107u16 => ID::URI,            //system [uri] 0..1
+    4108u16 => ID::STRING,         //value [string] 0..1
+    4109u16 => ID::PERIOD,         //period [Period] 0..1
+    4112u16 => ID::REFERENCE,      //assigner [Reference] 0..1
+};
+
+static BACKBONEPOSITION_EXPECTS: phf::Map<u16, ID> = phf_map! {
+    4334u16 => ID::DECIMAL, //longitude [decimal] 1..1
+    4335u16 => ID::DECIMAL, //latitude [decimal] 1..1
+    4336u16 => ID::DECIMAL, //altitude [decimal] 0..1
+};
+
+static META_EXPECTS: phf::Map<u16, ID> = phf_map! {
+    4207u16 => ID::ID,         //versionId [id] 0..1
+    4208u16 => ID::INSTANT,    //lastUpdated [instant] 0..1
+    4209u16 => ID::URI,        //source [uri] 0..1
+    4210u16 => ID::LCANONICAL, //profile [canonical] 0..*
+    4211u16 => ID::LCODING,    //security [Coding] 0..*
+    4212u16 => ID::LCODING,    //tag [Coding] 0..*
+};
+
+static MONEY_EXPECTS: phf::Map<u16, ID> = phf_map! {
+    4108u16 => ID::DECIMAL, //value [decimal] 0..1
+    4160u16 => ID::CODE,    //currency [code] 0..1
+};
+
+static NARRATIVE_EXPECTS: phf::Map<u16, ID> = phf_map! {
+    4099u16 => ID::CODE,  //status [code] 0..1
+    4100u16 => ID::XHTML, //div [xhtml] 0..1
+};
+
+static BACKBONETRIGGEREDBY_EXPECTS: phf::Map<u16, ID> = phf_map! {
+    4235u16 => ID::REFERENCE, //observation [Reference] 1..1
+    4106u16 => ID::CODE,      //type [code] 1..1
+    4236u16 => ID::STRING,    //reason [string] 0..1
+};
+
+static BACKBONEREFERENCERANGE_EXPECTS: phf::Map<u16, ID> = phf_map! {
+    4161u16 => ID::SIMPLEQUANTITY,  //low [SimpleQuantity] 0..1
+    4162u16 => ID::SIMPLEQUANTITY,  //high [SimpleQuantity] 0..1
+    4253u16 => ID::CODABLECONCEPT,  //normalValue [CodeableConcept] 0..1
+    4106u16 => ID::CODABLECONCEPT,  //type [CodeableConcept] 0..1
+    4254u16 => ID::LCODABLECONCEPT, //appliesTo [CodeableConcept] 0..*
+    4255u16 => ID::RANGE,           //age [Range] 0..1
+    4098u16 => ID::MARKDOWN,        //text [markdown] 0..1
+};
+
+static BACKBONECOMPONENT_EXPECTS: phf::Map<u16, ID> = phf_map! {
+    4116u16 => ID::CODABLECONCEPT,          //code [CodeableConcept] 1..1
+    4108u16 => ID::MULTIPLETYPES,           //value[x] [Quantity|CodeableConcept|string|boolean|integer|Range|Ratio|SampledData|time|dateTime|Period|Attachment|Reference] 0..1
+    4245u16 => ID::CODABLECONCEPT,          //dataAbsentReason [CodeableConcept] 0..1
+    4246u16 => ID::LCODABLECONCEPT,         //interpretation [CodeableConcept] 0..*
+    4252u16 => ID::LBACKBONEREFERENCERANGE, //referenceRange [BackboneElement] 0..*
+};
+
+static BACKBONEQUALIFICATION_EXPECTS: phf::Map<u16, ID> = phf_map! {
+    4119u16 => ID::LIDENTIFIER,    //identifier [Identifier] 0..*
+    4116u16 => ID::CODABLECONCEPT, //code [CodeableConcept] 1..1
+    4109u16 => ID::PERIOD,         //period [Period] 0..1
+    4323u16 => ID::REFERENCE,      //issuer [Reference] 0..1
+};
+
+static BACKBONECONTACT_EXPECTS: phf::Map<u16, ID> = phf_map! {
+    4150u16 => ID::LCODABLECONCEPT, //relationship [CodeableConcept] 0..*
+    4101u16 => ID::HUMANNAME,       //name [HumanName] 0..1
+    4120u16 => ID::LCONTACTPOINT,   //telecom [ContactPoint] 0..*
+    4126u16 => ID::ADDRESS,         //address [Address] 0..1
+    4122u16 => ID::CODE,            //gender [code] 0..1
+    4151u16 => ID::REFERENCE,       //organization [Reference] 0..1
+    4109u16 => ID::PERIOD,          //period [Period] 0..1
+};
+
+static BACKBONECOMMUNICATION_EXPECTS: phf::Map<u16, ID> = phf_map! {
+    4137u16 => ID::CODABLECONCEPT, //language [CodeableConcept] 1..1
+    4153u16 => ID::BOOLEAN,        //preferred [boolean] 1..1
+};
+
+static BACKBONELINK_EXPECTS: phf::Map<u16, ID> = phf_map! {
+    4157u16 => ID::REFERENCE, //other [Reference] 1..1
+    4106u16 => ID::CODE,      //type [code] 1..1
+};
+
+static PERIOD_EXPECTS: phf::Map<u16, ID> = phf_map! {
+    4110u16 => ID::DATETIME, //start [dateTime] 0..1
+    4111u16 => ID::DATETIME, //end [dateTime] 0..1
+};
+
+static BACKBONEPROCEDUREPERFORMER_EXPECTS: phf::Map<u16, ID> = phf_map! {
+    4266u16 => ID::CODABLECONCEPT, //function [CodeableConcept] 0..1
+    4267u16 => ID::REFERENCE,      //actor [Reference] 1..1
+    4177u16 => ID::REFERENCE,      //onBehalfOf [Reference] 0..1
+    4109u16 => ID::PERIOD,         //period [Period] 0..1
+};
+
+static BACKBONEFOCALDEVICE_EXPECTS: phf::Map<u16, ID> = phf_map! {
+    4311u16 => ID::CODABLECONCEPT, //action [CodeableConcept] 0..1
+    4312u16 => ID::REFERENCE,      //manipulated [Reference] 1..1
+};
+
+static QUANTITY_EXPECTS: phf::Map<u16, ID> = phf_map! {
+    4108u16 => ID::DECIMAL, //value [decimal] 0..1
+    4158u16 => ID::CODE,    //comparator [code] 0..1
+    4159u16 => ID::STRING,  //unit [string] 0..1
+    4107u16 => ID::URI,     //system [uri] 0..1
+    4116u16 => ID::CODE,    //code [code] 0..1
+};
+
+static RANGE_EXPECTS: phf::Map<u16, ID> = phf_map! {
+    4161u16 => ID::SIMPLEQUANTITY, //low [SimpleQuantity] 0..1
+    4162u16 => ID::SIMPLEQUANTITY, //high [SimpleQuantity] 0..1
+};
+
+static RATIO_EXPECTS: phf::Map<u16, ID> = phf_map! {
+    4163u16 => ID::QUANTITY,       //numerator [Quantity] 0..1
+    4164u16 => ID::SIMPLEQUANTITY, //denominator [SimpleQuantity] 0..1
+};
+
+static REFERENCE_EXPECTS: phf::Map<u16, ID> = phf_map! {
+    4113u16 => ID::STRING,     //reference [string] 0..1
+    4106u16 => ID::URI,        //type [uri] 0..1
+    4119u16 => ID::IDENTIFIER, //identifier [Identifier] 0..1
+    4114u16 => ID::STRING,     //display [string] 0..1
+};
+
+static RELATEDARTIFACT_EXPECTS: phf::Map<u16, ID> = phf_map! {
+    4106u16 => ID::CODE,            //type [code] 1..1
+    4215u16 => ID::LCODABLECONCEPT, //classifier [CodeableConcept] 0..*
+    4216u16 => ID::STRING,          //label [string] 0..1
+    4114u16 => ID::STRING,          //display [string] 0..1
+    4217u16 => ID::MARKDOWN,        //citation [markdown] 0..1
+    4218u16 => ID::ATTACHMENT,      //document [Attachment] 0..1
+    4219u16 => ID::CANONICAL,       //resource [canonical] 0..1
+    4220u16 => ID::REFERENCE,       //resourceReference [Reference] 0..1
+    4221u16 => ID::CODE,            //publicationStatus [code] 0..1
+    4222u16 => ID::DATE,            //publicationDate [date] 0..1
+};
+
+static SAMPLEDDATA_EXPECTS: phf::Map<u16, ID> = phf_map! {
+    4165u16 => ID::SIMPLEQUANTITY, //origin [SimpleQuantity] 1..1
+    4166u16 => ID::DECIMAL,        //interval [decimal] 0..1
+    4167u16 => ID::CODE,           //intervalUnit [code] 1..1
+    4109u16 => ID::DECIMAL,        //period [decimal] 1..1
+    4168u16 => ID::DECIMAL,        //factor [decimal] 0..1
+    4169u16 => ID::DECIMAL,        //lowerLimit [decimal] 0..1
+    4170u16 => ID::DECIMAL,        //upperLimit [decimal] 0..1
+    4171u16 => ID::POSITIVEINT,    //dimensions [positiveInt] 1..1
+    4172u16 => ID::CANONICAL,      //codeMap [canonical] 0..1
+    4173u16 => ID::STRING,         //offsets [string] 0..1
+    4138u16 => ID::STRING,         //data [string] 0..1
+};
+
+static SIGNATURE_EXPECTS: phf::Map<u16, ID> = phf_map! {
+    4106u16 => ID::LCODING,      //type [Coding] 0..*
+    4175u16 => ID::INSTANT,      //when [instant] 0..1
+    4176u16 => ID::REFERENCE,    //who [Reference] 0..1
+    4177u16 => ID::REFERENCE,    //onBehalfOf [Reference] 0..1
+    4178u16 => ID::CODE,         //targetFormat [code] 0..1
+    4179u16 => ID::CODE,         //sigFormat [code] 0..1
+    4138u16 => ID::BASE64BINARY, //data [base64Binary] 0..1
+};
+
+static SIMPLEQUANTITY_EXPECTS: phf::Map<u16, ID> = phf_map! {
+    4108u16 => ID::DECIMAL, //value [decimal] 0..1
+    4159u16 => ID::STRING,  //unit [string] 0..1
+    4107u16 => ID::URI,     //system [uri] 0..1
+    4116u16 => ID::CODE,    //code [code] 0..1
+};
+
+static TIMING_EXPECTS: phf::Map<u16, ID> = phf_map! {
+    4180u16 => ID::LDATETIME,            //event [dateTime] 0..*
+    4181u16 => ID::BACKBONETIMINGREPEAT, //repeat [BackboneElement] 0..1
+    4116u16 => ID::CODABLECONCEPT,       //code [CodeableConcept] 0..1
+};
+
+static BACKBONETIMINGREPEAT_EXPECTS: phf::Map<u16, ID> = phf_map! {
+    4224u16 => ID::MULTIPLETYPES, //bounds[x] [Duration|Range|Period] 0..1
+    4182u16 => ID::POSITIVEINT,   //count [positiveInt] 0..1
+    4183u16 => ID::POSITIVEINT,   //countMax [positiveInt] 0..1
+    4147u16 => ID::DECIMAL,       //duration [decimal] 0..1
+    4184u16 => ID::DECIMAL,       //durationMax [decimal] 0..1
+    4185u16 => ID::CODE,          //durationUnit [code] 0..1
+    4186u16 => ID::POSITIVEINT,   //frequency [positiveInt] 0..1
+    4187u16 => ID::POSITIVEINT,   //frequencyMax [positiveInt] 0..1
+    4109u16 => ID::DECIMAL,       //period [decimal] 0..1
+    4188u16 => ID::DECIMAL,       //periodMax [decimal] 0..1
+    4189u16 => ID::CODE,          //periodUnit [code] 0..1
+    4190u16 => ID::LCODE,         //dayOfWeek [code] 0..*
+    4191u16 => ID::LTIME,         //timeOfDay [time] 0..*
+    4175u16 => ID::LCODE,         //when [code] 0..*
+    4192u16 => ID::UNSIGNEDINT,   //offset [unsignedInt] 0..1
+};
+
+static VIRTUALSERVICEDETAIL_EXPECTS: phf::Map<u16, ID> = phf_map! {
+    4362u16 => ID::CODING,        //channelType [Coding] 0..1
+    4126u16 => ID::MULTIPLETYPES, //address[x] [url|string|ContactPoint|ExtendedContactDetail] 0..1
+    4363u16 => ID::LURL,          //additionalInfo [url] 0..*
+    4364u16 => ID::POSITIVEINT,   //maxParticipants [positiveInt] 0..1
+    4365u16 => ID::STRING,        //sessionKey [string] 0..1
+};
+
+static ALLERGYINTOLERANCE_EXPECTS: phf::Map<u16, ID> = phf_map! {
+    4119u16 => ID::LIDENTIFIER,          //identifier [Identifier] 0..*
+    4259u16 => ID::CODABLECONCEPT,       //clinicalStatus [CodeableConcept] 0..1
+    4260u16 => ID::CODABLECONCEPT,       //verificationStatus [CodeableConcept] 0..1
+    4106u16 => ID::CODABLECONCEPT,       //type [CodeableConcept] 0..1
+    4238u16 => ID::LCODE,                //category [code] 0..*
+    4315u16 => ID::CODE,                 //criticality [code] 0..1
+    4116u16 => ID::CODABLECONCEPT,       //code [CodeableConcept] 0..1
+    4316u16 => ID::REFERENCE,            //patient [Reference] 1..1
+    4241u16 => ID::REFERENCE,            //encounter [Reference] 0..1
+    4262u16 => ID::MULTIPLETYPES,        //onset[x] [dateTime|Age|Period|Range|string] 0..1
+    4264u16 => ID::DATETIME,             //recordedDate [dateTime] 0..1
+    4265u16 => ID::LBACKBONEPARTICIPANT, //participant [BackboneElement] 0..*
+    4317u16 => ID::DATETIME,             //lastOccurrence [dateTime] 0..1
+    4247u16 => ID::LANNOTATION,          //note [Annotation] 0..*
+    4318u16 => ID::LBACKBONEREACTION,    //reaction [BackboneElement] 0..*
+};
+
+static CONDITION_EXPECTS: phf::Map<u16, ID> = phf_map! {
+    4119u16 => ID::LIDENTIFIER,          //identifier [Identifier] 0..*
+    4259u16 => ID::CODABLECONCEPT,       //clinicalStatus [CodeableConcept] 1..1
+    4260u16 => ID::CODABLECONCEPT,       //verificationStatus [CodeableConcept] 0..1
+    4238u16 => ID::LCODABLECONCEPT,      //category [CodeableConcept] 0..*
+    4261u16 => ID::CODABLECONCEPT,       //severity [CodeableConcept] 0..1
+    4116u16 => ID::CODABLECONCEPT,       //code [CodeableConcept] 0..1
+    4248u16 => ID::LCODABLECONCEPT,      //bodySite [CodeableConcept] 0..*
+    4239u16 => ID::REFERENCE,            //subject [Reference] 1..1
+    4241u16 => ID::REFERENCE,            //encounter [Reference] 0..1
+    4262u16 => ID::MULTIPLETYPES,        //onset[x] [dateTime|Age|Period|Range|string] 0..1
+    4263u16 => ID::MULTIPLETYPES,        //abatement[x] [dateTime|Age|Period|Range|string] 0..1
+    4264u16 => ID::DATETIME,             //recordedDate [dateTime] 0..1
+    4265u16 => ID::LBACKBONEPARTICIPANT, //participant [BackboneElement] 0..*
+    4268u16 => ID::LBACKBONESTAGE,       //stage [BackboneElement] 0..*
+    4271u16 => ID::LCODEABLEREFERENCE,   //evidence [CodeableReference] 0..*
+    4247u16 => ID::LANNOTATION,          //note [Annotation] 0..*
+};
+
+static ENCOUNTER_EXPECTS: phf::Map<u16, ID> = phf_map! {
+    4119u16 => ID::LIDENTIFIER,                   //identifier [Identifier] 0..*
+    4099u16 => ID::CODE,                          //status [code] 1..1
+    4273u16 => ID::LCODABLECONCEPT,               //class [CodeableConcept] 0..*
+    4274u16 => ID::CODABLECONCEPT,                //priority [CodeableConcept] 0..1
+    4106u16 => ID::LCODABLECONCEPT,               //type [CodeableConcept] 0..*
+    4275u16 => ID::LCODEABLEREFERENCE,            //serviceType [CodeableReference] 0..*
+    4239u16 => ID::REFERENCE,                     //subject [Reference] 0..1
+    4276u16 => ID::CODABLECONCEPT,                //subjectStatus [CodeableConcept] 0..1
+    4277u16 => ID::LREFERENCE,                    //episodeOfCare [Reference] 0..*
+    4233u16 => ID::LREFERENCE,                    //basedOn [Reference] 0..*
+    4278u16 => ID::LREFERENCE,                    //careTeam [Reference] 0..*
+    4237u16 => ID::REFERENCE,                     //partOf [Reference] 0..1
+    4279u16 => ID::REFERENCE,                     //serviceProvider [Reference] 0..1
+    4265u16 => ID::LBACKBONEENCOUNTERPARTICIPANT, //participant [BackboneElement] 0..*
+    4280u16 => ID::LREFERENCE,                    //appointment [Reference] 0..*
+    4281u16 => ID::PERIOD,                        //actualPeriod [Period] 0..1
+    4282u16 => ID::DATETIME,                      //plannedStartDate [dateTime] 0..1
+    4283u16 => ID::DATETIME,                      //plannedEndDate [dateTime] 0..1
+    4284u16 => ID::DURATION,                      //length [Duration] 0..1
+    4236u16 => ID::LBACKBONEENCOUNTERREASON,      //reason [BackboneElement] 0..*
+    4285u16 => ID::LBACKBONEENCOUNTERDIAGNOSIS,   //diagnosis [BackboneElement] 0..*
+    4287u16 => ID::LREFERENCE,                    //account [Reference] 0..*
+    4288u16 => ID::LCODABLECONCEPT,               //dietPreference [CodeableConcept] 0..*
+    4289u16 => ID::LCODABLECONCEPT,               //specialArrangement [CodeableConcept] 0..*
+    4290u16 => ID::LCODABLECONCEPT,               //specialCourtesy [CodeableConcept] 0..*
+    4291u16 => ID::BACKBONEADMISSION,             //admission [BackboneElement] 0..1
+    4297u16 => ID::LBACKBONEENCOUNTERLOCATION,    //location [BackboneElement] 0..*
+};
+
+static ENDPOINT_EXPECTS: phf::Map<u16, ID> = phf_map! {
+    4119u16 => ID::LIDENTIFIER,      //identifier [Identifier] 0..*
+    4099u16 => ID::CODE,             //status [code] 1..1
+    4349u16 => ID::LCODABLECONCEPT,  //connectionType [CodeableConcept] 1..*
+    4101u16 => ID::STRING,           //name [string] 0..1
+    4213u16 => ID::STRING,           //description [string] 0..1
+    4350u16 => ID::LCODABLECONCEPT,  //environmentType [CodeableConcept] 0..*
+    4155u16 => ID::REFERENCE,        //managingOrganization [Reference] 0..1
+    4149u16 => ID::LCONTACTPOINT,    //contact [ContactPoint] 0..*
+    4109u16 => ID::PERIOD,           //period [Period] 0..1
+    4351u16 => ID::LBACKBONEPAYLOAD, //payload [BackboneElement] 0..*
+    4126u16 => ID::URL,              //address [url] 1..1
+    4353u16 => ID::LSTRING,          //header [string] 0..*
+};
+
+static HEALTHCARESERVICE_EXPECTS: phf::Map<u16, ID> = phf_map! {
+    4119u16 => ID::LIDENTIFIER,            //identifier [Identifier] 0..*
+    4097u16 => ID::BOOLEAN,                //active [boolean] 0..1
+    4339u16 => ID::REFERENCE,              //providedBy [Reference] 0..1
+    4340u16 => ID::LREFERENCE,             //offeredIn [Reference] 0..*
+    4238u16 => ID::LCODABLECONCEPT,        //category [CodeableConcept] 0..*
+    4106u16 => ID::LCODABLECONCEPT,        //type [CodeableConcept] 0..*
+    4325u16 => ID::LCODABLECONCEPT,        //specialty [CodeableConcept] 0..*
+    4297u16 => ID::LREFERENCE,             //location [Reference] 0..*
+    4101u16 => ID::STRING,                 //name [string] 0..1
+    4341u16 => ID::MARKDOWN,               //comment [markdown] 0..1
+    4342u16 => ID::MARKDOWN,               //extraDetails [markdown] 0..1
+    4135u16 => ID::ATTACHMENT,             //photo [Attachment] 0..1
+    4149u16 => ID::LEXTENDEDCONTACTDETAIL, //contact [ExtendedContactDetail] 0..*
+    4343u16 => ID::LREFERENCE,             //coverageArea [Reference] 0..*
+    4344u16 => ID::LCODABLECONCEPT,        //serviceProvisionCode [CodeableConcept] 0..*
+    4345u16 => ID::LBACKBONEELIGIBILITY,   //eligibility [BackboneElement] 0..*
+    4346u16 => ID::LCODABLECONCEPT,        //program [CodeableConcept] 0..*
+    4327u16 => ID::LCODABLECONCEPT,        //characteristic [CodeableConcept] 0..*
+    4152u16 => ID::LCODABLECONCEPT,        //communication [CodeableConcept] 0..*
+    4347u16 => ID::LCODABLECONCEPT,        //referralMethod [CodeableConcept] 0..*
+    4348u16 => ID::BOOLEAN,                //appointmentRequired [boolean] 0..1
+    4328u16 => ID::LAVAILABILITY,          //availability [Availability] 0..*
+    4329u16 => ID::LREFERENCE,             //endpoint [Reference] 0..*
+};
+
+static LOCATION_EXPECTS: phf::Map<u16, ID> = phf_map! {
+    4119u16 => ID::LIDENTIFIER,            //identifier [Identifier] 0..*
+    4099u16 => ID::CODE,                   //status [code] 0..1
+    4331u16 => ID::CODING,                 //operationalStatus [Coding] 0..1
+    4101u16 => ID::STRING,                 //name [string] 0..1
+    4330u16 => ID::LSTRING,                //alias [string] 0..*
+    4213u16 => ID::MARKDOWN,               //description [markdown] 0..1
+    4332u16 => ID::CODE,                   //mode [code] 0..1
+    4106u16 => ID::LCODABLECONCEPT,        //type [CodeableConcept] 0..*
+    4149u16 => ID::LEXTENDEDCONTACTDETAIL, //contact [ExtendedContactDetail] 0..*
+    4126u16 => ID::ADDRESS,                //address [Address] 0..1
+    4298u16 => ID::CODABLECONCEPT,         //form [CodeableConcept] 0..1
+    4333u16 => ID::BACKBONEPOSITION,       //position [BackboneElement] 0..1
+    4155u16 => ID::REFERENCE,              //managingOrganization [Reference] 0..1
+    4237u16 => ID::REFERENCE,              //partOf [Reference] 0..1
+    4327u16 => ID::LCODABLECONCEPT,        //characteristic [CodeableConcept] 0..*
+    4337u16 => ID::LAVAILABILITY,          //hoursOfOperation [Availability] 0..*
+    4338u16 => ID::LVIRTUALSERVICEDETAIL,  //virtualService [VirtualServiceDetail] 0..*
+    4329u16 => ID::LREFERENCE,             //endpoint [Reference] 0..*
+};
+
+static OBSERVATION_EXPECTS: phf::Map<u16, ID> = phf_map! {
+    4119u16 => ID::LIDENTIFIER,             //identifier [Identifier] 0..*
+    4232u16 => ID::MULTIPLETYPES,           //instantiates[x] [canonical|Reference] 0..1
+    4233u16 => ID::LREFERENCE,              //basedOn [Reference] 0..*
+    4234u16 => ID::LBACKBONETRIGGEREDBY,    //triggeredBy [BackboneElement] 0..*
+    4237u16 => ID::LREFERENCE,              //partOf [Reference] 0..*
+    4099u16 => ID::CODE,                    //status [code] 1..1
+    4238u16 => ID::LCODABLECONCEPT,         //category [CodeableConcept] 0..*
+    4116u16 => ID::CODABLECONCEPT,          //code [CodeableConcept] 1..1
+    4239u16 => ID::REFERENCE,               //subject [Reference] 0..1
+    4240u16 => ID::LREFERENCE,              //focus [Reference] 0..*
+    4241u16 => ID::REFERENCE,               //encounter [Reference] 0..1
+    4242u16 => ID::MULTIPLETYPES,           //effective[x] [dateTime|Period|Timing|instant] 0..1
+    4243u16 => ID::INSTANT,                 //issued [instant] 0..1
+    4244u16 => ID::LREFERENCE,              //performer [Reference] 0..*
+    4108u16 => ID::MULTIPLETYPES,           //value[x] [Quantity|CodeableConcept|string|boolean|integer|Range|Ratio|SampledData|time|dateTime|Period|Attachment|Reference] 0..1
+    4245u16 => ID::CODABLECONCEPT,          //dataAbsentReason [CodeableConcept] 0..1
+    4246u16 => ID::LCODABLECONCEPT,         //interpretation [CodeableConcept] 0..*
+    4247u16 => ID::LANNOTATION,             //note [Annotation] 0..*
+    4248u16 => ID::CODABLECONCEPT,          //bodySite [CodeableConcept] 0..1
+    4249u16 => ID::REFERENCE,               //bodyStructure [Reference] 0..1
+    4201u16 => ID::CODABLECONCEPT,          //method [CodeableConcept] 0..1
+    4250u16 => ID::REFERENCE,               //specimen [Reference] 0..1
+    4251u16 => ID::REFERENCE,               //device [Reference] 0..1
+    4252u16 => ID::LBACKBONEREFERENCERANGE, //referenceRange [BackboneElement] 0..*
+    4256u16 => ID::LREFERENCE,              //hasMember [Reference] 0..*
+    4257u16 => ID::LREFERENCE,              //derivedFrom [Reference] 0..*
+    4258u16 => ID::LBACKBONECOMPONENT,      //component [BackboneElement] 0..*
+};
+
+static ORGANIZATION_EXPECTS: phf::Map<u16, ID> = phf_map! {
+    4119u16 => ID::LIDENTIFIER,            //identifier [Identifier] 0..*
+    4097u16 => ID::BOOLEAN,                //active [boolean] 0..1
+    4106u16 => ID::LCODABLECONCEPT,        //type [CodeableConcept] 0..*
+    4101u16 => ID::STRING,                 //name [string] 0..1
+    4330u16 => ID::LSTRING,                //alias [string] 0..*
+    4213u16 => ID::MARKDOWN,               //description [markdown] 0..1
+    4149u16 => ID::LEXTENDEDCONTACTDETAIL, //contact [ExtendedContactDetail] 0..*
+    4237u16 => ID::REFERENCE,              //partOf [Reference] 0..1
+    4329u16 => ID::LREFERENCE,             //endpoint [Reference] 0..*
+    4322u16 => ID::LBACKBONEQUALIFICATION, //qualification [BackboneElement] 0..*
+};
+
+static PATIENT_EXPECTS: phf::Map<u16, ID> = phf_map! {
+    4119u16 => ID::LIDENTIFIER,            //identifier [Identifier] 0..*
+    4097u16 => ID::BOOLEAN,                //active [boolean] 0..1
+    4101u16 => ID::LHUMANNAME,             //name [HumanName] 0..*
+    4120u16 => ID::LCONTACTPOINT,          //telecom [ContactPoint] 0..*
+    4122u16 => ID::CODE,                   //gender [code] 0..1
+    4123u16 => ID::DATE,                   //birthDate [date] 0..1
+    4124u16 => ID::MULTIPLETYPES,          //deceased[x] [boolean|dateTime] 0..1
+    4126u16 => ID::LADDRESS,               //address [Address] 0..*
+    4133u16 => ID::CODABLECONCEPT,         //maritalStatus [CodeableConcept] 0..1
+    4125u16 => ID::MULTIPLETYPES,          //multipleBirth[x] [boolean|integer] 0..1
+    4135u16 => ID::LATTACHMENT,            //photo [Attachment] 0..*
+    4149u16 => ID::LBACKBONECONTACT,       //contact [BackboneElement] 0..*
+    4152u16 => ID::LBACKBONECOMMUNICATION, //communication [BackboneElement] 0..*
+    4154u16 => ID::LREFERENCE,             //generalPractitioner [Reference] 0..*
+    4155u16 => ID::REFERENCE,              //managingOrganization [Reference] 0..1
+    4156u16 => ID::LBACKBONELINK,          //link [BackboneElement] 0..*
+};
+
+static PRACTITIONER_EXPECTS: phf::Map<u16, ID> = phf_map! {
+    4119u16 => ID::LIDENTIFIER,            //identifier [Identifier] 0..*
+    4097u16 => ID::BOOLEAN,                //active [boolean] 0..1
+    4101u16 => ID::LHUMANNAME,             //name [HumanName] 0..*
+    4120u16 => ID::LCONTACTPOINT,          //telecom [ContactPoint] 0..*
+    4122u16 => ID::CODE,                   //gender [code] 0..1
+    4123u16 => ID::DATE,                   //birthDate [date] 0..1
+    4124u16 => ID::MULTIPLETYPES,          //deceased[x] [boolean|dateTime] 0..1
+    4126u16 => ID::LADDRESS,               //address [Address] 0..*
+    4135u16 => ID::LATTACHMENT,            //photo [Attachment] 0..*
+    4322u16 => ID::LBACKBONEQUALIFICATION, //qualification [BackboneElement] 0..*
+    4152u16 => ID::LBACKBONECOMMUNICATION, //communication [BackboneElement] 0..*
+};
+
+static PRACTITIONERROLE_EXPECTS: phf::Map<u16, ID> = phf_map! {
+    4119u16 => ID::LIDENTIFIER,            //identifier [Identifier] 0..*
+    4097u16 => ID::BOOLEAN,                //active [boolean] 0..1
+    4109u16 => ID::PERIOD,                 //period [Period] 0..1
+    4324u16 => ID::REFERENCE,              //practitioner [Reference] 0..1
+    4151u16 => ID::REFERENCE,              //organization [Reference] 0..1
+    4116u16 => ID::LCODABLECONCEPT,        //code [CodeableConcept] 0..*
+    4325u16 => ID::LCODABLECONCEPT,        //specialty [CodeableConcept] 0..*
+    4297u16 => ID::LREFERENCE,             //location [Reference] 0..*
+    4326u16 => ID::LREFERENCE,             //healthcareService [Reference] 0..*
+    4149u16 => ID::LEXTENDEDCONTACTDETAIL, //contact [ExtendedContactDetail] 0..*
+    4327u16 => ID::LCODABLECONCEPT,        //characteristic [CodeableConcept] 0..*
+    4152u16 => ID::LCODABLECONCEPT,        //communication [CodeableConcept] 0..*
+    4328u16 => ID::LAVAILABILITY,          //availability [Availability] 0..*
+    4329u16 => ID::LREFERENCE,             //endpoint [Reference] 0..*
+};
+
+static PROCEDURE_EXPECTS: phf::Map<u16, ID> = phf_map! {
+    4119u16 => ID::LIDENTIFIER,                 //identifier [Identifier] 0..*
+    4299u16 => ID::LCANONICAL,                  //instantiatesCanonical [canonical] 0..*
+    4300u16 => ID::LURI,                        //instantiatesUri [uri] 0..*
+    4233u16 => ID::LREFERENCE,                  //basedOn [Reference] 0..*
+    4237u16 => ID::LREFERENCE,                  //partOf [Reference] 0..*
+    4099u16 => ID::CODE,                        //status [code] 1..1
+    4301u16 => ID::CODABLECONCEPT,              //statusReason [CodeableConcept] 0..1
+    4238u16 => ID::LCODABLECONCEPT,             //category [CodeableConcept] 0..*
+    4116u16 => ID::CODABLECONCEPT,              //code [CodeableConcept] 0..1
+    4239u16 => ID::REFERENCE,                   //subject [Reference] 1..1
+    4240u16 => ID::REFERENCE,                   //focus [Reference] 0..1
+    4241u16 => ID::REFERENCE,                   //encounter [Reference] 0..1
+    4302u16 => ID::MULTIPLETYPES,               //occurrence[x] [dateTime|Period|string|Age|Range|Timing] 0..1
+    4303u16 => ID::DATETIME,                    //recorded [dateTime] 0..1
+    4304u16 => ID::REFERENCE,                   //recorder [Reference] 0..1
+    4305u16 => ID::MULTIPLETYPES,               //reported[x] [boolean|Reference] 0..1
+    4244u16 => ID::LBACKBONEPROCEDUREPERFORMER, //performer [BackboneElement] 0..*
+    4297u16 => ID::REFERENCE,                   //location [Reference] 0..1
+    4236u16 => ID::LCODEABLEREFERENCE,          //reason [CodeableReference] 0..*
+    4248u16 => ID::LCODABLECONCEPT,             //bodySite [CodeableConcept] 0..*
+    4306u16 => ID::CODABLECONCEPT,              //outcome [CodeableConcept] 0..1
+    4307u16 => ID::LREFERENCE,                  //report [Reference] 0..*
+    4308u16 => ID::LCODEABLEREFERENCE,          //complication [CodeableReference] 0..*
+    4309u16 => ID::LCODABLECONCEPT,             //followUp [CodeableConcept] 0..*
+    4247u16 => ID::LANNOTATION,                 //note [Annotation] 0..*
+    4310u16 => ID::LBACKBONEFOCALDEVICE,        //focalDevice [BackboneElement] 0..*
+    4313u16 => ID::LCODEABLEREFERENCE,          //used [CodeableReference] 0..*
+    4314u16 => ID::LREFERENCE,                  //supportingInfo [Reference] 0..*
+};
+
+///Elements every resource has, see [`get_resource_expects`].
+static DOMAINRESOURCE_EXPECTS: phf::Map<u16, ID> = phf_map! {
+    4096u16 => ID::STRING,     //resourceType [string] 1..1
+    4105u16 => ID::ID,         //id [id] 0..1
+    4206u16 => ID::META,       //meta [Meta] 0..1
+    4231u16 => ID::URI,        //implicitRules [uri] 0..1
+    4137u16 => ID::CODE,       //language [code] 0..1
+    4098u16 => ID::NARRATIVE,  //text [Narrative] 0..1
+    4229u16 => ID::LEXTENSION, //extension [Extension] 0..*
+    4230u16 => ID::LEXTENSION, //modifierExtension [Extension] 0..*
+};
+
+///Mapping of list [`ID`]s to the [`ID`] of their items.
+static LIST_ITEMS: phf::Map<u16, ID> = phf_map! {
+    22u16 => ID::STRING,                         //LSTRING
+    23u16 => ID::CODE,                           //LCODE
+    24u16 => ID::DATETIME,                       //LDATETIME
+    25u16 => ID::TIME,                           //LTIME
+    26u16 => ID::CANONICAL,                      //LCANONICAL
+    27u16 => ID::URI,                            //LURI
+    28u16 => ID::URL,                            //LURL
+    2048u16 => ID::HUMANNAME,                    //LHUMANNAME
+    2049u16 => ID::IDENTIFIER,                   //LIDENTIFIER
+    2050u16 => ID::CODING,                       //LCODING
+    2051u16 => ID::CONTACTPOINT,                 //LCONTACTPOINT
+    2052u16 => ID::ADDRESS,                      //LADDRESS
+    2053u16 => ID::ATTACHMENT,                   //LATTACHMENT
+    2054u16 => ID::BACKBONECONTACT,              //LBACKBONECONTACT
+    2055u16 => ID::CODABLECONCEPT,               //LCODABLECONCEPT
+    2056u16 => ID::BACKBONECOMMUNICATION,        //LBACKBONECOMMUNICATION
+    2057u16 => ID::BACKBONELINK,                 //LBACKBONELINK
+    2058u16 => ID::REFERENCE,                    //LREFERENCE
+    2059u16 => ID::ANNOTATION,                   //LANNOTATION
+    2060u16 => ID::SIGNATURE,                    //LSIGNATURE
+    2061u16 => ID::DOSAGE,                       //LDOSAGE
+    2062u16 => ID::BACKBONEDOSEANDRATE,          //LBACKBONEDOSEANDRATE
+    2063u16 => ID::CONTACTDETAIL,                //LCONTACTDETAIL
+    2064u16 => ID::RELATEDARTIFACT,              //LRELATEDARTIFACT
+    2065u16 => ID::RATIO,                        //LRATIO
+    2066u16 => ID::EXTENSION,                    //LEXTENSION
+    2067u16 => ID::ELEMENT,                      //LELEMENT
+    2068u16 => ID::CODEABLEREFERENCE,            //LCODEABLEREFERENCE
+    2069u16 => ID::BACKBONETRIGGEREDBY,          //LBACKBONETRIGGEREDBY
+    2070u16 => ID::BACKBONEREFERENCERANGE,       //LBACKBONEREFERENCERANGE
+    2071u16 => ID::BACKBONECOMPONENT,            //LBACKBONECOMPONENT
+    2072u16 => ID::BACKBONEPARTICIPANT,          //LBACKBONEPARTICIPANT
+    2073u16 => ID::BACKBONESTAGE,                //LBACKBONESTAGE
+    2074u16 => ID::BACKBONEENCOUNTERPARTICIPANT, //LBACKBONEENCOUNTERPARTICIPANT
+    2075u16 => ID::BACKBONEENCOUNTERREASON,      //LBACKBONEENCOUNTERREASON
+    2076u16 => ID::BACKBONEENCOUNTERDIAGNOSIS,   //LBACKBONEENCOUNTERDIAGNOSIS
+    2077u16 => ID::BACKBONEENCOUNTERLOCATION,    //LBACKBONEENCOUNTERLOCATION
+    2078u16 => ID::BACKBONEPROCEDUREPERFORMER,   //LBACKBONEPROCEDUREPERFORMER
+    2079u16 => ID::BACKBONEFOCALDEVICE,          //LBACKBONEFOCALDEVICE
+    2080u16 => ID::BACKBONEREACTION,             //LBACKBONEREACTION
+    2081u16 => ID::BACKBONEQUALIFICATION,        //LBACKBONEQUALIFICATION
+    2082u16 => ID::EXTENDEDCONTACTDETAIL,        //LEXTENDEDCONTACTDETAIL
+    2083u16 => ID::AVAILABILITY,                 //LAVAILABILITY
+    2084u16 => ID::BACKBONEAVAILABLETIME,        //LBACKBONEAVAILABLETIME
+    2085u16 => ID::BACKBONENOTAVAILABLETIME,     //LBACKBONENOTAVAILABLETIME
+    2086u16 => ID::VIRTUALSERVICEDETAIL,         //LVIRTUALSERVICEDETAIL
+    2087u16 => ID::BACKBONEELIGIBILITY,          //LBACKBONEELIGIBILITY
+    2088u16 => ID::BACKBONEPAYLOAD,              //LBACKBONEPAYLOAD
+};
+
+/// The object a choice element is part of, see [`choices`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ChoiceContext {
+    /// The resource itself.
+    Resource(ResourceId),
+    /// An object of a complex type, items of a list by the type of the items.
+    Type(ID)
+}
+
+///Types allowed for a choice element (`[x]`) by the object it is part of and its key,
+///see [`get_choice`].
+fn choices(context: &ChoiceContext, key: ID) -> Option<&'static [ID]> {
+    use ChoiceContext::{Resource, Type};
+    let types: &'static [ID] = match (context, key) {
+        (Resource(ResourceId::AllergyIntolerance), ID::Onset) => &[ID::DATETIME, ID::AGE, ID::PERIOD, ID::RANGE, ID::STRING],
+        (Type(ID::ANNOTATION), ID::Author)                    => &[ID::REFERENCE, ID::STRING],
+        (Resource(ResourceId::Condition), ID::Onset)          => &[ID::DATETIME, ID::AGE, ID::PERIOD, ID::RANGE, ID::STRING],
+        (Resource(ResourceId::Condition), ID::Abatement)      => &[ID::DATETIME, ID::AGE, ID::PERIOD, ID::RANGE, ID::STRING],
+        (Type(ID::BACKBONEDOSEANDRATE), ID::Dose)             => &[ID::RANGE, ID::SIMPLEQUANTITY],
+        (Type(ID::BACKBONEDOSEANDRATE), ID::Rate)             => &[ID::RATIO, ID::RANGE, ID::SIMPLEQUANTITY],
+        (Type(ID::EXTENSION), ID::Value)                      => &[ID::BASE64BINARY, ID::BOOLEAN, ID::CANONICAL, ID::CODE, ID::DATE, ID::DATETIME, ID::DECIMAL, ID::ID, ID::INSTANT, ID::INTEGER, ID::INTEGER64, ID::MARKDOWN, ID::OID, ID::POSITIVEINT, ID::STRING, ID::TIME, ID::UNSIGNEDINT, ID::URI, ID::URL, ID::UUID, ID::ADDRESS, ID::AGE, ID::ANNOTATION, ID::ATTACHMENT, ID::CODABLECONCEPT, ID::CODING, ID::CONTACTPOINT, ID::COUNT, ID::DISTANCE, ID::DURATION, ID::HUMANNAME, ID::IDENTIFIER, ID::MONEY, ID::PERIOD, ID::QUANTITY, ID::RANGE, ID::RATIO, ID::REFERENCE, ID::SAMPLEDDATA, ID::SIGNATURE, ID::TIMING, ID::CONTACTDETAIL, ID::EXPRESSION, ID::RELATEDARTIFACT, ID::DOSAGE, ID::META, ID::CODEABLEREFERENCE, ID::AVAILABILITY, ID::EXTENDEDCONTACTDETAIL, ID::VIRTUALSERVICEDETAIL],
+        (Resource(ResourceId::Observation), ID::Instantiates) => &[ID::CANONICAL, ID::REFERENCE],
+        (Resource(ResourceId::Observation), ID::Effective)    => &[ID::DATETIME, ID::PERIOD, ID::TIMING, ID::INSTANT],
+        (Resource(ResourceId::Observation), ID::Value)        => &[ID::QUANTITY, ID::CODABLECONCEPT, ID::STRING, ID::BOOLEAN, ID::INTEGER, ID::RANGE, ID::RATIO, ID::SAMPLEDDATA, ID::TIME, ID::DATETIME, ID::PERIOD, ID::ATTACHMENT, ID::REFERENCE],
+        (Type(ID::BACKBONECOMPONENT), ID::Value)              => &[ID::QUANTITY, ID::CODABLECONCEPT, ID::STRING, ID::BOOLEAN, ID::INTEGER, ID::RANGE, ID::RATIO, ID::SAMPLEDDATA, ID::TIME, ID::DATETIME, ID::PERIOD, ID::ATTACHMENT, ID::REFERENCE],
+        (Resource(ResourceId::Patient), ID::Deceased)         => &[ID::BOOLEAN, ID::DATETIME],
+        (Resource(ResourceId::Patient), ID::MultipleBirth)    => &[ID::BOOLEAN, ID::INTEGER],
+        (Resource(ResourceId::Practitioner), ID::Deceased)    => &[ID::BOOLEAN, ID::DATETIME],
+        (Resource(ResourceId::Procedure), ID::Occurrence)     => &[ID::DATETIME, ID::PERIOD, ID::STRING, ID::AGE, ID::RANGE, ID::TIMING],
+        (Resource(ResourceId::Procedure), ID::Reported)       => &[ID::BOOLEAN, ID::REFERENCE],
+        (Type(ID::BACKBONETIMINGREPEAT), ID::Bounds)          => &[ID::DURATION, ID::RANGE, ID::PERIOD],
+        (Type(ID::VIRTUALSERVICEDETAIL), ID::Address)         => &[ID::URL, ID::STRING, ID::CONTACTPOINT, ID::EXTENDEDCONTACTDETAIL],
+        _ => return None
+    };
+    Some(types)
+}
+
+/// Returns if 'id' is a backbone element (or a list of them), which may carry `modifierExtension`s.
+pub fn is_backbone(id: ID) -> bool {
+    matches!(id,
+        ID::BACKBONECONTACT | ID::LBACKBONECONTACT |
+        ID::BACKBONECOMMUNICATION | ID::LBACKBONECOMMUNICATION |
+        ID::BACKBONELINK | ID::LBACKBONELINK |
+        ID::BACKBONETIMINGREPEAT |
+        ID::DOSAGE | ID::LDOSAGE |
+        ID::BACKBONEDOSEANDRATE | ID::LBACKBONEDOSEANDRATE |
+        ID::BACKBONETRIGGEREDBY | ID::LBACKBONETRIGGEREDBY |
+        ID::BACKBONEREFERENCERANGE | ID::LBACKBONEREFERENCERANGE |
+        ID::BACKBONECOMPONENT | ID::LBACKBONECOMPONENT |
+        ID::BACKBONEPARTICIPANT | ID::LBACKBONEPARTICIPANT |
+        ID::BACKBONESTAGE | ID::LBACKBONESTAGE |
+        ID::BACKBONEENCOUNTERPARTICIPANT | ID::LBACKBONEENCOUNTERPARTICIPANT |
+        ID::BACKBONEENCOUNTERREASON | ID::LBACKBONEENCOUNTERREASON |
+        ID::BACKBONEENCOUNTERDIAGNOSIS | ID::LBACKBONEENCOUNTERDIAGNOSIS |
+        ID::BACKBONEADMISSION |
+        ID::BACKBONEENCOUNTERLOCATION | ID::LBACKBONEENCOUNTERLOCATION |
+        ID::BACKBONEPROCEDUREPERFORMER | ID::LBACKBONEPROCEDUREPERFORMER |
+        ID::BACKBONEFOCALDEVICE | ID::LBACKBONEFOCALDEVICE |
+        ID::BACKBONEREACTION | ID::LBACKBONEREACTION |
+        ID::BACKBONEQUALIFICATION | ID::LBACKBONEQUALIFICATION |
+        ID::BACKBONEAVAILABLETIME | ID::LBACKBONEAVAILABLETIME |
+        ID::BACKBONENOTAVAILABLETIME | ID::LBACKBONENOTAVAILABLETIME |
+        ID::BACKBONEPOSITION |
+        ID::BACKBONEELIGIBILITY | ID::LBACKBONEELIGIBILITY |
+        ID::BACKBONEPAYLOAD | ID::LBACKBONEPAYLOAD
+    )
+}
+
+/// Returns the expected [`ID`] of 'key' on the level of the resource: the elements of
+/// its own table and the ones common to all resources. A resource of unknown type is
+/// read as Patient.
+pub fn get_resource_expects(resource: &ResourceId, key: ID) -> Option<ID> {
+    let table = match resource {
+        ResourceId::Empty | ResourceId::Patient => &PATIENT_EXPECTS,
+        ResourceId::Observation => &OBSERVATION_EXPECTS,
+        ResourceId::Condition => &CONDITION_EXPECTS,
+        ResourceId::Encounter => &ENCOUNTER_EXPECTS,
+        ResourceId::Procedure => &PROCEDURE_EXPECTS,
+        ResourceId::AllergyIntolerance => &ALLERGYINTOLERANCE_EXPECTS,
+        ResourceId::Practitioner => &PRACTITIONER_EXPECTS,
+        ResourceId::PractitionerRole => &PRACTITIONERROLE_EXPECTS,
+        ResourceId::Organization => &ORGANIZATION_EXPECTS,
+        ResourceId::Location => &LOCATION_EXPECTS,
+        ResourceId::HealthcareService => &HEALTHCARESERVICE_EXPECTS,
+        ResourceId::Endpoint => &ENDPOINT_EXPECTS,
+    };
+    table.get(&(key as u16))
+        .or_else(|| DOMAINRESOURCE_EXPECTS.get(&(key as u16)))
+        .cloned()
+}
+
+pub fn get_from_sub<I: Into<u16>>(id: I, expects_for: u16) -> Option<ID> {
+    match id.into() {
+        520u16 | 2052u16 => {
+            ADDRESS_EXPECTS.get(&expects_for).cloned()
+        },
+        527u16 => {
+            AGE_EXPECTS.get(&expects_for).cloned()
+        },
+        551u16 | 2072u16 => {
+            BACKBONEPARTICIPANT_EXPECTS.get(&expects_for).cloned()
+        },
+        560u16 | 2080u16 => {
+            BACKBONEREACTION_EXPECTS.get(&expects_for).cloned()
+        },
+        535u16 | 2059u16 => {
+            ANNOTATION_EXPECTS.get(&expects_for).cloned()
+        },
+        521u16 | 2053u16 => {
+            ATTACHMENT_EXPECTS.get(&expects_for).cloned()
+        },
+        563u16 | 2083u16 => {
+            AVAILABILITY_EXPECTS.get(&expects_for).cloned()
+        },
+        564u16 | 2084u16 => {
+            BACKBONEAVAILABLETIME_EXPECTS.get(&expects_for).cloned()
+        },
+        565u16 | 2085u16 => {
+            BACKBONENOTAVAILABLETIME_EXPECTS.get(&expects_for).cloned()
+        },
+        515u16 | 2055u16 => {
+            CODABLECONCEPT_EXPECTS.get(&expects_for).cloned()
+        },
+        547u16 | 2068u16 => {
+            CODEABLEREFERENCE_EXPECTS.get(&expects_for).cloned()
+        },
+        518u16 | 2050u16 => {
+            CODING_EXPECTS.get(&expects_for).cloned()
+        },
+        552u16 | 2073u16 => {
+            BACKBONESTAGE_EXPECTS.get(&expects_for).cloned()
+        },
+        541u16 | 2063u16 => {
+            CONTACTDETAIL_EXPECTS.get(&expects_for).cloned()
+        },
+        519u16 | 2051u16 => {
+            CONTACTPOINT_EXPECTS.get(&expects_for).cloned()
+        },
+        529u16 => {
+            COUNT_EXPECTS.get(&expects_for).cloned()
+        },
+        530u16 => {
+            DISTANCE_EXPECTS.get(&expects_for).cloned()
+        },
+        539u16 | 2061u16 => {
+            DOSAGE_EXPECTS.get(&expects_for).cloned()
+        },
+        540u16 | 2062u16 => {
+            BACKBONEDOSEANDRATE_EXPECTS.get(&expects_for).cloned()
+        },
+        528u16 => {
+            DURATION_EXPECTS.get(&expects_for).cloned()
+        },
+        553u16 | 2074u16 => {
+            BACKBONEENCOUNTERPARTICIPANT_EXPECTS.get(&expects_for).cloned()
+        },
+        554u16 | 2075u16 => {
+            BACKBONEENCOUNTERREASON_EXPECTS.get(&expects_for).cloned()
+        },
+        555u16 | 2076u16 => {
+            BACKBONEENCOUNTERDIAGNOSIS_EXPECTS.get(&expects_for).cloned()
+        },
+        556u16 => {
+            BACKBONEADMISSION_EXPECTS.get(&expects_for).cloned()
+        },
+        557u16 | 2077u16 => {
+            BACKBONEENCOUNTERLOCATION_EXPECTS.get(&expects_for).cloned()
+        },
+        569u16 | 2088u16 => {
+            BACKBONEPAYLOAD_EXPECTS.get(&expects_for).cloned()
+        },
+        543u16 => {
+            EXPRESSION_EXPECTS.get(&expects_for).cloned()
+        },
+        562u16 | 2082u16 => {
+            EXTENDEDCONTACTDETAIL_EXPECTS.get(&expects_for).cloned()
+        },
+        545u16 | 2066u16 => {
+            EXTENSION_EXPECTS.get(&expects_for).cloned()
+        },
+        568u16 | 2087u16 => {
+            BACKBONEELIGIBILITY_EXPECTS.get(&expects_for).cloned()
+        },
+        513u16 | 2048u16 => {
+            HUMANNAME_EXPECTS.get(&expects_for).cloned()
+        },
+        514u16 | 2049u16 => {
+            IDENTIFIER_EXPECTS.get(&expects_for).cloned()
+        },
+        567u16 => {
+            BACKBONEPOSITION_EXPECTS.get(&expects_for).cloned()
+        },
+        542u16 => {
+            META_EXPECTS.get(&expects_for).cloned()
+        },
+        531u16 => {
+            MONEY_EXPECTS.get(&expects_for).cloned()
+        },
+        512u16 => {
+            NARRATIVE_EXPECTS.get(&expects_for).cloned()
+        },
+        548u16 | 2069u16 => {
+            BACKBONETRIGGEREDBY_EXPECTS.get(&expects_for).cloned()
+        },
+        549u16 | 2070u16 => {
+            BACKBONEREFERENCERANGE_EXPECTS.get(&expects_for).cloned()
+        },
+        550u16 | 2071u16 => {
+            BACKBONECOMPONENT_EXPECTS.get(&expects_for).cloned()
+        },
+        561u16 | 2081u16 => {
+            BACKBONEQUALIFICATION_EXPECTS.get(&expects_for).cloned()
+        },
+        522u16 | 2054u16 => {
+            BACKBONECONTACT_EXPECTS.get(&expects_for).cloned()
+        },
+        523u16 | 2056u16 => {
+            BACKBONECOMMUNICATION_EXPECTS.get(&expects_for).cloned()
+        },
+        524u16 | 2057u16 => {
+            BACKBONELINK_EXPECTS.get(&expects_for).cloned()
+        },
+        516u16 => {
+            PERIOD_EXPECTS.get(&expects_for).cloned()
+        },
+        558u16 | 2078u16 => {
+            BACKBONEPROCEDUREPERFORMER_EXPECTS.get(&expects_for).cloned()
+        },
+        559u16 | 2079u16 => {
+            BACKBONEFOCALDEVICE_EXPECTS.get(&expects_for).cloned()
+        },
+        525u16 => {
+            QUANTITY_EXPECTS.get(&expects_for).cloned()
+        },
+        532u16 => {
+            RANGE_EXPECTS.get(&expects_for).cloned()
+        },
+        533u16 | 2065u16 => {
+            RATIO_EXPECTS.get(&expects_for).cloned()
+        },
+        517u16 | 2058u16 => {
+            REFERENCE_EXPECTS.get(&expects_for).cloned()
+        },
+        544u16 | 2064u16 => {
+            RELATEDARTIFACT_EXPECTS.get(&expects_for).cloned()
+        },
+        534u16 => {
+            SAMPLEDDATA_EXPECTS.get(&expects_for).cloned()
+        },
+        536u16 | 2060u16 => {
+            SIGNATURE_EXPECTS.get(&expects_for).cloned()
+        },
+        526u16 => {
+            SIMPLEQUANTITY_EXPECTS.get(&expects_for).cloned()
+        },
+        537u16 => {
+            TIMING_EXPECTS.get(&expects_for).cloned()
+        },
+        538u16 => {
+            BACKBONETIMINGREPEAT_EXPECTS.get(&expects_for).cloned()
+        },
+        566u16 | 2086u16 => {
+            VIRTUALSERVICEDETAIL_EXPECTS.get(&expects_for).cloned()
+        },
+        _ => None
+    }
+}
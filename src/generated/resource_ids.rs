@@ -0,0 +1,72 @@
+// Generated by fhir_codegen from the FHIR StructureDefinitions, do not edit.
+// Regenerate with `cargo run -p fhir_codegen -- codegen/structuredefinitions`.
+
+use crate::error::{Result, Error};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[repr(u16)]
+pub enum ResourceId {
+    Empty = 0,
+    Patient = 1,
+    Observation = 2,
+    Condition = 3,
+    Encounter = 4,
+    Procedure = 5,
+    AllergyIntolerance = 6,
+    Practitioner = 7,
+    PractitionerRole = 8,
+    Organization = 9,
+    Location = 10,
+    HealthcareService = 11,
+    Endpoint = 12
+}
+
+
+impl TryFrom<&str> for ResourceId {
+    type Error = Error;
+    fn try_from(value: &str) -> Result<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "patient" => Ok(ResourceId::Patient),
+            "observation" => Ok(ResourceId::Observation),
+            "condition" => Ok(ResourceId::Condition),
+            "encounter" => Ok(ResourceId::Encounter),
+            "procedure" => Ok(ResourceId::Procedure),
+            "allergyintolerance" => Ok(ResourceId::AllergyIntolerance),
+            "practitioner" => Ok(ResourceId::Practitioner),
+            "practitionerrole" => Ok(ResourceId::PractitionerRole),
+            "organization" => Ok(ResourceId::Organization),
+            "location" => Ok(ResourceId::Location),
+            "healthcareservice" => Ok(ResourceId::HealthcareService),
+            "endpoint" => Ok(ResourceId::Endpoint),
+            _ => Err(Error::UnknownResourceStr(value.to_string()))
+        }
+    }
+}
+
+impl TryFrom<u16> for ResourceId {
+    type Error = Error;
+    fn try_from(value: u16) -> Result<Self> {
+        match value {
+            0 => Ok(ResourceId::Empty),
+            1 => Ok(ResourceId::Patient),
+            2 => Ok(ResourceId::Observation),
+            3 => Ok(ResourceId::Condition),
+            4 => Ok(ResourceId::Encounter),
+            5 => Ok(ResourceId::Procedure),
+            6 => Ok(ResourceId::AllergyIntolerance),
+            7 => Ok(ResourceId::Practitioner),
+            8 => Ok(ResourceId::PractitionerRole),
+            9 => Ok(ResourceId::Organization),
+            10 => Ok(ResourceId::Location),
+            11 => Ok(ResourceId::HealthcareService),
+            12 => Ok(ResourceId::Endpoint),
+            _ => Err(Error::UnknownResourceId(value))
+        }
+    }
+}
+
+impl From<ResourceId> for u16 {
+    fn from(id: ResourceId) -> u16 {
+        id as u16
+    }
+}
@@ -3,6 +3,8 @@ pub mod store;
 pub mod parser;
 pub mod datatypes;
 pub mod resourcetypes;
+pub mod server;
+pub mod search;
 
 
 
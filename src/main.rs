@@ -0,0 +1,14 @@
+use fhir_store::error::Result;
+use fhir_store::store::store::Store;
+use fhir_store::server::rest::FhirServer;
+
+const THREADS: usize = 4;
+
+/// Usage: fhir_store [store file] [address]
+fn main() -> Result<()> {
+    let mut args = std::env::args().skip(1);
+    let path = args.next().unwrap_or_else(|| "store.db".to_string());
+    let addr = args.next().unwrap_or_else(|| "127.0.0.1:8080".to_string());
+    let store = Store::open_path(&path)?.into_shared()?;
+    FhirServer::new(store, &format!("http://{addr}")).run(&addr, THREADS)
+}
@@ -1,5 +1,5 @@
 use crate::error::{Error, Result};
-use chrono::{DateTime, Utc, naive::Days, Months, NaiveDateTime, FixedOffset};
+use chrono::{DateTime, Utc, naive::Days, Months, SecondsFormat};
 
 //From https://build.fhir.org/datatypes.html#dateTime
 //YYYY                          2018          
@@ -32,20 +32,20 @@ impl Fhir_DateTime {
                             if let Some(dt) = dt.checked_add_months(Months::new(to_u32(month)?-1)) {
                                 if let Some(days) = parts.next() {
                                     if let Some(dt) = dt.checked_add_days(Days::new(to_u32(days)? as u64 -1)) {
-                                        let dt = DateTime::<Utc>::from_utc(dt, Utc);
+                                        let dt = dt.and_utc();
                                         Ok(Self {c:dt})
                                     } else {
                                         Err(Error::TimeStampParsingError)
                                     }
                                 } else {
-                                    let dt = DateTime::<Utc>::from_utc(dt, Utc);
+                                    let dt = dt.and_utc();
                                     Ok(Self {c:dt})
                                 }
                             } else {
                                 Err(Error::TimeStampParsingError)
                             }
                         } else {
-                            let dt = DateTime::<Utc>::from_utc(dt, Utc);
+                            let dt = dt.and_utc();
                             Ok(Self {c:dt})
                         }
                     } else {
@@ -57,9 +57,16 @@ impl Fhir_DateTime {
         }
     }
 
+    /// Parses a FHIR instant, which unlike dateTime always has seconds and a timezone.
+    pub fn from_instant_string(s: &str) -> Result<Self> {
+        match DateTime::parse_from_rfc3339(s) {
+            Ok(c) if s.as_bytes().get(10) == Some(&b'T') => Ok(Self {c: c.into()}),
+            _ => Err(Error::TimeStampParsingError)
+        }
+    }
+
     pub fn from_timestamp_millis(ts: i64) -> Result<Self> {
-        if let Some(ndt) = NaiveDateTime::from_timestamp_millis(ts) {
-            let dt = DateTime::<Utc>::from_utc(ndt, Utc);
+        if let Some(dt) = DateTime::<Utc>::from_timestamp_millis(ts) {
             Ok(Self { c: dt })
         } else {
             Err(Error::TimeStampParsingError)
@@ -86,7 +93,21 @@ impl Fhir_DateTime {
     pub fn timestamp_millis_bytes(&self) -> [u8; 8] {
         self.timestamp_millis().to_be_bytes()
     }
-} 
+
+    /// Renders as FHIR date: YYYY-MM-DD
+    pub fn to_date_string(&self) -> String {
+        self.c.format("%Y-%m-%d").to_string()
+    }
+
+    /// Renders as FHIR dateTime in utc. Milliseconds are only added if present.
+    pub fn to_datetime_string(&self) -> String {
+        if self.c.timestamp_subsec_millis() > 0 {
+            self.c.to_rfc3339_opts(SecondsFormat::Millis, true)
+        } else {
+            self.c.to_rfc3339_opts(SecondsFormat::Secs, true)
+        }
+    }
+}
 
 
 fn to_i32(s: &str) -> Result<i32> {
@@ -148,6 +169,16 @@ mod test {
         assert_eq!(conv_2.timestamp_millis(),1483228800000);
     }
 
+    #[test]
+    fn fhir_dt_from_instant_string() {
+        let conv = Fhir_DateTime::from_instant_string("2015-02-07T13:28:17.239+02:00").unwrap();
+        assert_eq!(conv.timestamp_millis(), 1423308497239);
+        assert_eq!(conv.to_datetime_string(), "2015-02-07T11:28:17.239Z");
+        assert!(Fhir_DateTime::from_instant_string("2015-02-07").is_err());
+        assert!(Fhir_DateTime::from_instant_string("2015-02-07T13:28:17").is_err());
+        assert!(Fhir_DateTime::from_instant_string("2015-02-07 13:28:17Z").is_err());
+    }
+
     #[test]
     fn fhir_dt_from_timestamp() {
         let ts = 1483228800000i64;
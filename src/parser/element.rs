@@ -0,0 +1,238 @@
+use crate::datatypes::id::{ID, get_list_item};
+use crate::error::{Result, Error};
+use crate::store::header::Head;
+use crate::store::resourcewriter::{ResourceHeader, RESOURCE_HEADER_LEN, FORMAT_VERSION};
+use super::datetime::Fhir_DateTime;
+use super::primitive;
+use super::reader::Reader;
+
+
+/// A value of a stored resource, decoded into a tree. Unlike [`super::serializer::to_json`],
+/// which writes the whole resource, this is meant for looking at single values, e.g. when
+/// searching. The ids and extensions of primitives ([`ID::ELEMENT`]) and null items of
+/// primitive lists are left out.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Element {
+    Primitive(Primitive),
+    List(Vec<Element>),
+    Object(Vec<(ID, Element)>)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Primitive {
+    String(String),
+    Boolean(bool),
+    Integer(i64),
+    /// As it is written in json, to keep its precision.
+    Decimal(String),
+    /// [`ID::DATE`], [`ID::DATETIME`] or [`ID::INSTANT`] as timestamp in milliseconds.
+    DateTime(ID, i64)
+}
+
+impl Primitive {
+    /// Returns the value as it is written in json, without quotes.
+    pub fn to_json_string(&self) -> Result<String> {
+        Ok(match self {
+            Primitive::String(s) => s.clone(),
+            Primitive::Boolean(b) => b.to_string(),
+            Primitive::Integer(i) => i.to_string(),
+            Primitive::Decimal(d) => d.clone(),
+            Primitive::DateTime(ID::DATE, ts) => Fhir_DateTime::from_timestamp_millis(*ts)?.to_date_string(),
+            Primitive::DateTime(_, ts) => Fhir_DateTime::from_timestamp_millis(*ts)?.to_datetime_string()
+        })
+    }
+}
+
+impl Element {
+    /// Returns all primitives found by following 'path' (a list of keys), lists on
+    /// the way are flattened.
+    pub fn select(&self, path: &[ID]) -> Vec<&Primitive> {
+        let mut result = Vec::new();
+        self.collect(path, &mut result);
+        result
+    }
+
+    /// Returns all elements found by following 'path', lists on the way are flattened.
+    pub fn select_elements(&self, path: &[ID]) -> Vec<&Element> {
+        let mut result = Vec::new();
+        self.collect_elements(path, &mut result);
+        result
+    }
+
+    fn collect<'e>(&'e self, path: &[ID], result: &mut Vec<&'e Primitive>) {
+        match (self, path.split_first()) {
+            (Element::Primitive(p), None) => result.push(p),
+            (Element::List(items), _) => items.iter().for_each(|item| item.collect(path, result)),
+            (Element::Object(entries), Some((key, rest))) => {
+                entries.iter()
+                    .filter(|(k, _)| k == key)
+                    .for_each(|(_, value)| value.collect(rest, result));
+            },
+            _ => ()
+        }
+    }
+
+    fn collect_elements<'e>(&'e self, path: &[ID], result: &mut Vec<&'e Element>) {
+        match (self, path.split_first()) {
+            (Element::List(items), _) => items.iter().for_each(|item| item.collect_elements(path, result)),
+            (element, None) => result.push(element),
+            (Element::Object(entries), Some((key, rest))) => {
+                entries.iter()
+                    .filter(|(k, _)| k == key)
+                    .for_each(|(_, value)| value.collect_elements(rest, result));
+            },
+            _ => ()
+        }
+    }
+}
+
+
+/// Decodes a stored resource (without [`crate::store::resourcewriter::ResourceHeader`])
+/// into an [`Element::Object`].
+pub fn decode(src: &[u8]) -> Result<Element> {
+    decode_format(src, FORMAT_VERSION)
+}
+
+/// Same as [`decode`], but for a resource as it is stored, i.e. starting with its
+/// [`ResourceHeader`], which tells the format of the data.
+pub fn decode_stored(stored: &[u8]) -> Result<Element> {
+    if stored.len() < RESOURCE_HEADER_LEN {
+        return Err(Error::BufferUnderflow)
+    }
+    let header = ResourceHeader::from_store(stored);
+    decode_format(&stored[RESOURCE_HEADER_LEN..], header.format())
+}
+
+fn decode_format(src: &[u8], format: u8) -> Result<Element> {
+    let mut decoder = Decoder { reader: Reader::new(src, format)? };
+    let end = decoder.reader.read_resource_end()?;
+    decoder.decode_object(end)
+}
+
+
+struct Decoder<'d> {
+    reader: Reader<'d>
+}
+
+impl<'d> Decoder<'d> {
+    fn decode_object(&mut self, end: usize) -> Result<Element> {
+        let mut entries = Vec::new();
+        while self.reader.cursor < end {
+            let key = self.reader.read_key()?;
+            let value_end = self.reader.read_end()?;
+            let id = self.reader.read_value_id(value_end)?;
+            if id == ID::ELEMENT || id == ID::LELEMENT {
+                self.reader.cursor = value_end;
+                continue;
+            }
+            entries.push((key, self.decode_value(id, value_end)?));
+        }
+        if self.reader.cursor != end {
+            return Err(Error::BufferOverflow)
+        }
+        Ok(Element::Object(entries))
+    }
+
+    // Same layout as read by the serializer, see [`super::serializer`].
+    fn decode_value(&mut self, id: ID, end: usize) -> Result<Element> {
+        if id.is_general_purpose() {
+            return self.decode_object(end)
+        }
+        if id.is_primitive() {
+            let data = self.reader.read_slice(end - self.reader.cursor)?;
+            return Ok(Element::Primitive(decode_primitive(id, data, self.reader.format())?))
+        }
+        if id.is_primitive_list() {
+            let item = get_list_item(id).ok_or(Error::UnknownStoreId(id as u16))?;
+            let mut items = Vec::new();
+            while self.reader.cursor < end {
+                let len = self.reader.read_num()?;
+                if len == 0 {
+                    continue;
+                }
+                let data = self.reader.read_slice(len)?;
+                items.push(Element::Primitive(decode_primitive(item, data, self.reader.format())?));
+            }
+            return Ok(Element::List(items))
+        }
+        if id.is_gp_list() {
+            let mut items = Vec::new();
+            while self.reader.cursor < end {
+                let item_end = self.reader.read_end()?;
+                items.push(self.decode_object(item_end)?);
+            }
+            return Ok(Element::List(items))
+        }
+        Err(Error::UnknownStoreId(id as u16))
+    }
+}
+
+fn decode_primitive(id: ID, data: &[u8], format: u8) -> Result<Primitive> {
+    Ok(match id {
+        ID::BOOLEAN => match data {
+            [0] => Primitive::Boolean(false),
+            [1] => Primitive::Boolean(true),
+            _ => return Err(Error::Conversion("bytes".to_string(), "BOOLEAN".to_string()))
+        },
+        ID::POSITIVEINT | ID::INTEGER => {
+            let bytes: [u8; 4] = data.try_into()
+                .map_err(|_| Error::Conversion("bytes".to_string(), "i32".to_string()))?;
+            Primitive::Integer(i32::from_be_bytes(bytes) as i64)
+        },
+        ID::UNSIGNEDINT => Primitive::Integer(primitive::decode_unsigned(data)? as i64),
+        ID::INTEGER64 => {
+            let bytes: [u8; 8] = data.try_into()
+                .map_err(|_| Error::Conversion("bytes".to_string(), "i64".to_string()))?;
+            Primitive::Integer(i64::from_be_bytes(bytes))
+        },
+        ID::DECIMAL => Primitive::Decimal(primitive::decode_decimal(data, format)?),
+        ID::DATE | ID::DATETIME | ID::INSTANT => {
+            let bytes: [u8; 8] = data.try_into()
+                .map_err(|_| Error::Conversion("bytes".to_string(), "i64".to_string()))?;
+            Primitive::DateTime(id, i64::from_be_bytes(bytes))
+        },
+        _ => Primitive::String(primitive::decode_string(id, data)?)
+    })
+}
+
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::parser::json::from_json;
+    use crate::resourcetypes::ResourceId;
+    use crate::store::resourcewriter::FORMAT_U16;
+    use uuid::Uuid;
+
+    #[test]
+    fn element_decode_select() {
+        let data = br#"{"resourceType": "patient", "active": true, "name": [{"family": "Chalmers", "given": ["Peter", "James"]}, {"given": ["Jim"]}], "birthDate": "1974-12-25"}"#;
+        let element = decode(&from_json(data).unwrap()).unwrap();
+        let given: Vec<String> = element.select(&[ID::Name, ID::Given]).iter().map(|p| p.to_json_string().unwrap()).collect();
+        assert_eq!(given, vec!["Peter", "James", "Jim"]);
+        assert_eq!(element.select(&[ID::Active]), vec![&Primitive::Boolean(true)]);
+        assert_eq!(element.select(&[ID::BirthDate])[0].to_json_string().unwrap(), "1974-12-25");
+        assert_eq!(element.select_elements(&[ID::Name]).len(), 2);
+        assert!(element.select(&[ID::Gender]).is_empty());
+        assert!(decode(&[10, 0]).is_err());
+
+        let data = br#"{"birthDate": "1974-12-25", "_birthDate": {"id": "b"}, "name": [{"given": ["Peter", null], "_given": [null, {"id": "g"}]}]}"#;
+        let element = decode(&from_json(data).unwrap()).unwrap();
+        assert_eq!(element.select_elements(&[ID::BirthDate]).len(), 1);
+        assert_eq!(element.select(&[ID::Name, ID::Given]), vec![&Primitive::String("Peter".to_string())]);
+    }
+
+    #[test]
+    fn element_decode_old_format() {
+        // {"active": true, "name": [{"given": ["Jim"]}]} with u16 lengths and ids
+        let body = [0,32, 0,2, 16,1, 0,3, 0,2, 1, 0,2, 16,5, 0,17, 8,0, 0,13, 0,2, 16,7, 0,7, 0,22, 0,3, 74, 105, 109];
+        let mut stored = ResourceHeader::tombstone(ResourceId::Patient, Uuid::new_v4()).unwrap();
+        stored[18] = FORMAT_U16;
+        stored.extend(body);
+        let element = decode_stored(&stored).unwrap();
+        assert_eq!(element.select(&[ID::Active]), vec![&Primitive::Boolean(true)]);
+        assert_eq!(element.select(&[ID::Name, ID::Given]), vec![&Primitive::String("Jim".to_string())]);
+        assert_eq!(element, decode(&from_json(br#"{"active": true, "name": [{"given": ["Jim"]}]}"#).unwrap()).unwrap());
+    }
+}
@@ -1,54 +1,151 @@
-use crate::datatypes::id::{ID, get_expects, get_from_sub, get_key_id, ID_LEN, copy_multiple, TypeClass};
-use crate::error::{Result, Error};
+use crate::datatypes::id::{ID, get_choice, get_expects_in, get_key_id, get_list_item, get_primitive_element};
+use crate::datatypes::varint;
+use crate::error::{Result, Error, JsonPosition};
 use crate::resourcetypes::ResourceId;
 use crate::store::resourcewriter::ResourceWriter;
-use super::datetime::Fhir_DateTime;
+use super::primitive;
 use super::stacks::*;
-use std::ptr::slice_from_raw_parts;
-use fast_float;
 use std::str;
+use uuid::Uuid;
 
-pub fn from_json(src: &[u8]) -> Vec<u8> {
-    let mut parser = JsonParser {
-        src,
-        cursor: 0,
-        writer: ResourceWriter::new(ResourceId::Patient).unwrap(),
-        lengths: LengthStack::default(),
-        keys: KeyStack::default(),
-        after_comma: false
-    };
-    parser.parse();
-    parser.get_buffer()
+/// Maximum nesting depth of objects and lists, the resource itself being the first level.
+pub const MAX_DEPTH: usize = 64;
+
+/// Parses the json into the store format, without [`crate::store::resourcewriter::ResourceHeader`].
+/// Malformed json returns an [`Error::Json`] telling where parsing failed.
+pub fn from_json(src: &[u8]) -> Result<Vec<u8>> {
+    from_json_with_max_depth(src, MAX_DEPTH)
+}
+
+// Same as [`from_json`], but objects and lists may only be nested 'max_depth' levels deep.
+fn from_json_with_max_depth(src: &[u8], max_depth: usize) -> Result<Vec<u8>> {
+    let mut parser = JsonParser::with_max_depth(src, max_depth)?;
+    parser.run()?;
+    Ok(parser.get_buffer())
+}
+
+/// Parses the json into the store format, but keeps the [`crate::store::resourcewriter::ResourceHeader`].
+/// Returns the [`Uuid`] assigned to the resource and the buffer ready to be stored. Unlike
+/// [`from_json`], the json has to have a 'resourceType'.
+pub fn resource_from_json(src: &[u8]) -> Result<(Uuid, Vec<u8>)> {
+    let mut parser = JsonParser::new(src)?;
+    parser.run()?;
+    parser.check_resource_type()?;
+    let id = parser.writer.id();
+    Ok((id, parser.writer.finish()?))
+}
+
+/// Same as [`resource_from_json`], but the resource keeps the given [`Uuid`]. An `id`
+/// in the json has to be the same.
+pub fn resource_from_json_with_id(src: &[u8], id: &Uuid) -> Result<Vec<u8>> {
+    let mut parser = JsonParser::new(src)?;
+    parser.run()?;
+    parser.check_resource_type()?;
+    if let Some(body_id) = parser.body_id.as_deref().filter(|body_id| *body_id != id.to_string()) {
+        return Err(Error::Expected(format!("id '{id}'"), body_id.to_string()))
+    }
+    parser.writer.set_id(*id);
+    parser.writer.finish()
 }
 
 
 
 
+/// What the parser expects next.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    /// The '{' of the resource.
+    Start,
+    /// A key or the '}' of an empty object.
+    FirstKey,
+    Key,
+    Colon,
+    Value,
+    /// An item or the ']' of an empty list.
+    FirstItem,
+    Item,
+    /// A ',' or the end of the object or list.
+    Next,
+    End
+}
+
 struct JsonParser<'p> {
+    input: &'p[u8],
     src: &'p[u8],
-    cursor: usize,
     writer: ResourceWriter,
     lengths: LengthStack,
     keys: KeyStack,
-    after_comma: bool 
-
+    path: PathStack,
+    state: State,
+    max_depth: usize,
+    resource_type: Option<String>,
+    /// The `id` of the resource, if the json has one.
+    body_id: Option<String>,
+    /// Decides the element tables, the 'resourceType' looked up ahead or Patient.
+    resource: ResourceId,
+    /// If the resource has elements before its 'resourceType'.
+    keys_before_type: bool,
+    /// The chosen type of the '_' sibling of a choice element, written in front of its
+    /// [`ID::ELEMENT`] content.
+    element_choice: Option<ID>
 }
 
 impl<'p> JsonParser<'p> {
-    
-    fn print_buffer(&self) {
-        let data = unsafe {&*slice_from_raw_parts(self.writer.get_mut_ptr(), self.writer.len())};
-        let relevant = data.to_vec().drain(self.writer.get_header_len()..).collect::<Vec<u8>>();
-        println!("==================================================================");
-        println!("DATA: {:?}", relevant);
-        println!("DATA LENGTH: {}",relevant.len());
-        println!("WRITER LEN (HEADER ADJ): {}", self.writer.len() - self.writer.get_header_len());
-        println!("LengthCollector DATA: {:?}", self.lengths);
-        println!("==================================================================");
+
+    fn new(src: &'p[u8]) -> Result<Self> {
+        Ok(Self {
+            input: src,
+            src,
+            writer: ResourceWriter::new(ResourceId::Patient)?,
+            lengths: LengthStack::default(),
+            keys: KeyStack::default(),
+            path: PathStack::default(),
+            state: State::Start,
+            max_depth: MAX_DEPTH,
+            resource_type: None,
+            body_id: None,
+            resource: peek_resource_type(src)
+                .and_then(|name| ResourceId::try_from(name).ok())
+                .unwrap_or(ResourceId::Patient),
+            keys_before_type: false,
+            element_choice: None
+        })
+    }
+
+    fn with_max_depth(src: &'p[u8], max_depth: usize) -> Result<Self> {
+        let mut parser = JsonParser::new(src)?;
+        parser.max_depth = max_depth;
+        Ok(parser)
+    }
+
+    // Parses the whole input, errors get the position they occured at.
+    fn run(&mut self) -> Result<()> {
+        self.parse().map_err(|err| self.locate(err))
+    }
+
+    fn locate(&self, err: Error) -> Error {
+        if let Error::Json(_, _) = err {
+            return err
+        }
+        let root = self.resource_type.as_deref().unwrap_or("Resource");
+        let position = JsonPosition::new(self.input, self.offset(), self.path.to_path(root));
+        Error::Json(position, Box::new(err))
+    }
+
+    // A resource to be stored has to tell its type, the error points to its end.
+    fn check_resource_type(&self) -> Result<()> {
+        match self.resource_type {
+            Some(_) => Ok(()),
+            None => Err(self.locate(Error::Expected("resourceType".to_string(), "none".to_string())))
+        }
+    }
+
+    fn offset(&self) -> usize {
+        self.input.len() - self.src.len()
     }
 
     fn get_buffer(&self) -> Vec<u8> {
-        let data = unsafe {&*slice_from_raw_parts(self.writer.get_mut_ptr(), self.writer.len())};
+        let data = self.writer.as_slice();
         data.to_vec().drain(self.writer.get_header_len()..).collect::<Vec<u8>>()
     }
 
@@ -57,7 +154,7 @@ impl<'p> JsonParser<'p> {
     }
 
     fn peek_char(&self) -> Option<&u8>  {
-        if self.src.len() == 0 {
+        if self.src.is_empty() {
             None
         } else {
             Some(&self.src[0])
@@ -82,126 +179,180 @@ impl<'p> JsonParser<'p> {
         let _ = self.consume_while(|c| c.is_ascii_whitespace());
     }
 
-    fn next_is(&self, target: u8) {
-        assert!(self.peek_char().is_some() && self.peek_char().unwrap() == &target, "EXPECTED {target}")
-    }
-    fn check_n_eat(&mut self, target: u8, call: &str) {
-        if let Some(ch) = self.peek_char() {
-            if ch == &target {
+    fn check_n_eat(&mut self, target: u8) -> Result<()> {
+        match self.peek_char() {
+            Some(ch) if *ch == target => {
                 self.eat_char();
-            } else {
-                panic!("EXPECTED HERE '{}' got '{}' called by {call}", target as char, *ch as char)
-            }
-        } else {
-            panic!("EXPECTED '{}'", target as char)
+                Ok(())
+            },
+            Some(ch) => Err(unexpected(&(target as char).to_string(), *ch)),
+            None => Err(Error::EOF)
         }
     }
 
 
 
-    fn parse(&mut self) {
-        self.eat_whitespace(); 
-        if self.peek_char().is_none() {
-            //println!("PARSING DONE");
-            //self.print_buffer();
-            return
+    // Runs the state machine over the whole input, one token per step. Nesting is kept
+    // on the [`KeyStack`], [`LengthStack`] and [`PathStack`], not on the call stack.
+    fn parse(&mut self) -> Result<()> {
+        loop {
+            self.eat_whitespace();
+            let ch = match self.peek_char() {
+                Some(ch) => *ch,
+                None if self.state == State::End => return Ok(()),
+                None => return Err(Error::EOF)
+            };
+            self.state = match self.state {
+                State::Start => {
+                    self.check_n_eat(b'{')?;
+                    self.open_object()?
+                },
+                // an empty item would read back as null
+                State::FirstKey if ch == b'}' && self.keys.last() == Some(&ID::LELEMENT) => {
+                    return Err(Error::Expected("null or id and extensions".to_string(), "{}".to_string()))
+                },
+                State::FirstKey if ch == b'}' => self.close_object()?,
+                State::FirstKey | State::Key => {
+                    self.set_key()?;
+                    State::Colon
+                },
+                State::Colon => {
+                    self.check_n_eat(b':')?;
+                    State::Value
+                },
+                State::Value => self.parse_value(ch)?,
+                State::FirstItem if ch == b']' => self.close_list()?,
+                State::FirstItem | State::Item => self.parse_item(ch)?,
+                State::Next => match ch {
+                    b',' => {
+                        self.eat_char();
+                        self.path.next_item();
+                        if self.path.in_object() { State::Key } else { State::Item }
+                    },
+                    b'}' => self.close_object()?,
+                    b']' => self.close_list()?,
+                    _ => return Err(unexpected(&format!(", or {}", self.path.closer()), ch))
+                },
+                State::End => return Err(unexpected("end of input", ch))
+            };
         }
-        let ch = self.peek_char().unwrap();
-        match *ch {
+    }
+
+    // The value of a key.
+    fn parse_value(&mut self, ch: u8) -> Result<State> {
+        match ch {
             b'{' => {
-                self.eat_char();
-                self.eat_whitespace();
-                let len = self.writer.len();
-                self.writer.reserve_two().unwrap();
-                self.lengths.push(len);
-                if self.keys.len() > 0 {
-                    if let Some(k) = self.keys.last() {
-                        if k.is_general_purpose() {
-                            //insert gp id and length
-                            self.writer.set_u16(2u16).unwrap();
-                            self.writer.set_u16(*k as u16).unwrap();
-                        }
-                    }
-                }
-                self.after_comma = false;
-                self.set_key();
-                return self.parse()
-            },
-            b'}' => {
-                self.eat_char();
-                self.eat_whitespace();
-                let offset = self.writer.len();
-                if let Some((location, length)) = self.lengths.get_length(offset) {
-                    self.writer.set_u16_at(length-2, location).unwrap();
+                if !self.keys.last_is_general_purpose() {
+                    let expected = self.keys.last().map(|k| format!("{:?}", k)).unwrap_or_default();
+                    return Err(Error::Expected(expected, "object".to_string()))
                 }
-                self.keys.pop();
-                return self.parse()
-            }
-            b'[' => {
                 self.eat_char();
-                self.eat_whitespace();
-                //check expected
-                if self.keys.last_is_primitive_list() {
-                    self.parse_primitive_list();   
-                }
-                if self.keys.last_is_general_purpose_list() {
-                    self.prepare_gp_list();
-                    return self.parse()
-                }
-                return self.parse()
+                return self.open_object()
             },
-            b']' => {
+            b'[' => {
                 self.eat_char();
-                self.eat_whitespace();
-                let offset = self.writer.len();
-                if let Some((location, length)) =self.lengths.get_length(offset) {
-                    self.writer.set_u16_at(length-2, location).unwrap();
-                }
-                self.keys.pop();
-                return self.parse()
+                return self.open_list()
             },
             b'"' => {
-                let mut data = self.parse_string();
-                self.set_string(&mut data);
-                self.keys.pop();
-                return self.parse()
+                let mut data = self.parse_string()?;
+                self.set_string(&mut data)?;
             },
-            b':' => {
-                self.eat_char();
-                return self.parse()
-            },
-            b',' => {
-                self.eat_char();
-                self.eat_whitespace();
-                if let Some(peeked) =  self.peek_char() {
-                    if peeked == &b'{' || peeked == &b'[' {
-                        self.after_comma = true;
-                        return self.parse()
-                    }
-                }
-                self.set_key();
-                return self.parse()
-            },
-            //TODO handle signed numbers
-            //TODO handle floats/decimals
-            b'0'..=b'9' => {
-                match self.parse_numeric(false) {
-                    Ok(_) => self.parse(),
-                    Err(err) => panic!("{}", err)
-                };
-            },
-            b'-' => {
-                match self.parse_numeric(true) {
-                    Ok(_) => self.parse(),
-                    Err(err) => panic!("{}", err)
-                };
-            },
-            b't' | b'f' => {
-                self.set_bool(*ch);
-                return self.parse()
+            b'0'..=b'9' => self.parse_numeric(false)?,
+            b'-' => self.parse_numeric(true)?,
+            b't' | b'f' => self.set_bool(ch)?,
+            _ => return Err(unexpected("json value", ch))
+        }
+        self.keys.pop();
+        Ok(State::Next)
+    }
+
+    // An item of the list on top of the [`KeyStack`]. Items of primitive lists and
+    // their ELEMENTs may be null, which is stored as an empty item.
+    fn parse_item(&mut self, ch: u8) -> Result<State> {
+        if ch == b'n' && (self.keys.last_is_primitive_list() || self.keys.last() == Some(&ID::LELEMENT)) {
+            if !self.src.starts_with(b"null") {
+                return Err(unexpected("null", ch))
+            }
+            self.eat_chars(4);
+            self.writer.set_varint(0)?;
+            return Ok(State::Next)
+        }
+        if self.keys.last_is_primitive_list() {
+            let mut data = self.parse_string()?;
+            self.set_primitive_list_item(&mut data)?;
+            return Ok(State::Next)
+        }
+        self.check_n_eat(b'{')?;
+        self.open_object()
+    }
+
+    // The length of an object is inserted in front of it, once it is closed.
+    fn open_object(&mut self) -> Result<State> {
+        self.check_depth()?;
+        let offset = self.writer.len();
+        self.lengths.push(offset);
+        if let Some(k) = self.keys.last() {
+            if k.is_general_purpose() {
+                //insert gp id
+                self.writer.set_varint(*k as usize)?;
             }
-            _ => panic!("UNKNOWN CHARACTER -> {}, data: {}", *ch as char, String::from_utf8(self.src.to_vec()).unwrap())
         }
+        if let Some(chosen) = self.element_choice.take() {
+            self.writer.set_varint(chosen as usize)?;
+        }
+        self.path.push_object();
+        Ok(State::FirstKey)
+    }
+
+    fn close_object(&mut self) -> Result<State> {
+        if !self.path.pop_object() {
+            return Err(unexpected(self.path.closer(), b'}'))
+        }
+        self.eat_char();
+        let offset = self.writer.len();
+        if let Some((location, length)) = self.lengths.get_length(offset) {
+            self.writer.insert_varint_at(length, location)?;
+        }
+        // items of a list are no values of a key
+        if self.path.in_object() {
+            self.keys.pop();
+        }
+        Ok(if self.path.is_empty() { State::End } else { State::Next })
+    }
+
+    fn open_list(&mut self) -> Result<State> {
+        if !self.keys.last_is_primitive_list() && !self.keys.last_is_general_purpose_list() {
+            let expected = self.keys.last().map(|k| format!("{:?}", k)).unwrap_or_default();
+            return Err(Error::Expected(expected, "list".to_string()))
+        }
+        self.check_depth()?;
+        let offset = self.writer.len();
+        self.lengths.push(offset);
+        self.path.push_list();
+        if let Some(expects) = self.keys.last() {
+            self.writer.set_varint(*expects as usize)?;
+        }
+        Ok(State::FirstItem)
+    }
+
+    fn close_list(&mut self) -> Result<State> {
+        if !self.path.pop_list() {
+            return Err(unexpected(self.path.closer(), b']'))
+        }
+        self.eat_char();
+        let offset = self.writer.len();
+        if let Some((location, length)) = self.lengths.get_length(offset) {
+            self.writer.insert_varint_at(length, location)?;
+        }
+        self.keys.pop();
+        Ok(State::Next)
+    }
+
+    fn check_depth(&self) -> Result<()> {
+        if self.path.depth() >= self.max_depth {
+            return Err(Error::MaxDepth(self.max_depth))
+        }
+        Ok(())
     }
 
     fn consume_while<P: FnMut(u8) -> bool>(&mut self, mut pred: P) -> Vec<u8> {
@@ -212,48 +363,69 @@ impl<'p> JsonParser<'p> {
         result
     }
 
-    //will most likely only be used at [`ID::STRING`] and any numeric [`ID`]
-    fn handle_multiple(&mut self, class: TypeClass) {
-        if self.keys.last_is_mulitple() {
-            match class {
-                TypeClass::STRING | TypeClass::NUMERIC => {
-                    let multies = self.keys.multiple.clone();
-                    self.keys.pop();
-                    if let Some(id) = multies.get(&(class as u16)) {
-                        if let Ok(key) = ID::try_from(*id) {
-                            self.keys.push(key)
-                        }
-                    }
-                },
-                TypeClass::BOOLEAN => {
-                    self.keys.pop();
+    // Decodes the escape sequences of RFC 8259, the result is valid UTF-8.
+    fn parse_string(&mut self) -> Result<Vec<u8>> {
+        self.check_n_eat(b'"')?;
+        let mut result = Vec::<u8>::new();
+        while let Some(ch) = self.next_char() {
+            match ch {
+                b'"' => {
+                    str::from_utf8(&result).map_err(|_| Error::Conversion("bytes".to_string(), "utf8".to_string()))?;
+                    return Ok(result)
                 },
+                b'\\' => self.parse_escape(&mut result)?,
+                ch if ch < 0x20 => return Err(unexpected("escaped control character", ch)),
+                ch => result.push(ch)
             }
         }
+        Err(Error::EOF)
     }
 
-    fn parse_string(&mut self) -> Vec<u8> {
-        self.check_n_eat(b'"', "parse_string()");
-        let mut result = Vec::<u8>::new(); 
-        while self.peek_char().is_some() {
-            let ch = self.next_char().unwrap();
-            if ch == b'\\' {
-                if let Some(next) = self.peek_char() {
-                    if next == &b'"' {
-                        result.push(self.next_char().unwrap());
-                    } else {
-                        result.push(ch);
-                    }
-                }
-            } else {
-                if ch == b'"' {
-                    return result
-                } else {
-                    result.push(ch)
-                }
-            }
+    // Appends the character of the escape sequence following a '\' to 'out'.
+    fn parse_escape(&mut self, out: &mut Vec<u8>) -> Result<()> {
+        let ch = self.next_char().ok_or(Error::EOF)?;
+        let decoded = match ch {
+            b'"' => '"',
+            b'\\' => '\\',
+            b'/' => '/',
+            b'b' => '\u{8}',
+            b'f' => '\u{c}',
+            b'n' => '\n',
+            b'r' => '\r',
+            b't' => '\t',
+            b'u' => {
+                let high = self.parse_hex()?;
+                let code = match high {
+                    0xD800..=0xDBFF => {
+                        self.check_n_eat(b'\\')?;
+                        self.check_n_eat(b'u')?;
+                        let low = self.parse_hex()?;
+                        if !(0xDC00..=0xDFFF).contains(&low) {
+                            return Err(Error::Expected("low surrogate".to_string(), format!("\\u{:04x}", low)))
+                        }
+                        0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00)
+                    },
+                    0xDC00..=0xDFFF => return Err(Error::Expected("high surrogate".to_string(), format!("\\u{:04x}", high))),
+                    _ => high
+                };
+                char::from_u32(code).ok_or(Error::Conversion("u32".to_string(), "char".to_string()))?
+            },
+            ch => return Err(unexpected("escape character", ch))
+        };
+        let mut buf = [0u8; 4];
+        out.extend_from_slice(decoded.encode_utf8(&mut buf).as_bytes());
+        Ok(())
+    }
+
+    // Reads the four hex digits of a '\u' escape.
+    fn parse_hex(&mut self) -> Result<u32> {
+        let mut code = 0;
+        for _ in 0..4 {
+            let ch = self.next_char().ok_or(Error::EOF)?;
+            let digit = (ch as char).to_digit(16).ok_or(unexpected("hex digit", ch))?;
+            code = code * 16 + digit;
         }
-        result
+        Ok(code)
     }
 
 
@@ -261,11 +433,9 @@ impl<'p> JsonParser<'p> {
 
 
     fn parse_numeric(&mut self, is_negative: bool) -> Result<()> {
-        self.handle_multiple(TypeClass::NUMERIC);
         if is_negative {
             self.eat_char();
         }
-        //TODO OVERFLOW CHECK SOMEWHERE!!!
         if let Some(key) = self.keys.last() {
             match key {
                 ID::POSITIVEINT => {
@@ -273,242 +443,257 @@ impl<'p> JsonParser<'p> {
                     if is_negative {
                         return Err(Error::Expected("POSITIVEINT".to_string(), "negative number".to_string()))
                     }
-                    if let Ok(num) = i32::try_from(num) {
-                        self.set_unit(ID::POSITIVEINT, &mut (num as i32).to_be_bytes());
-                        Ok(())
-                    } else {return Err(Error::Conversion("u64".to_string(), "i32".to_string()))}
+                    if num == 0 {
+                        return Err(Error::Expected("POSITIVEINT".to_string(), "0".to_string()))
+                    }
+                    let num = i32::try_from(num)
+                        .map_err(|_| Error::Conversion("u64".to_string(), "i32".to_string()))?;
+                    self.set_unit(ID::POSITIVEINT, &num.to_be_bytes())
+                },
+                ID::UNSIGNEDINT => {
+                    let num = self.parse_number()?;
+                    if is_negative {
+                        return Err(Error::Expected("UNSIGNEDINT".to_string(), "negative number".to_string()))
+                    }
+                    self.set_unit(ID::UNSIGNEDINT, &primitive::encode_unsigned(num)?)
                 },
                 ID::INTEGER => {
                     let num = self.parse_number()?;
-                    if let Ok(mut num) = i32::try_from(num) {
-                        if is_negative {
-                            num = num.wrapping_neg();
-                        }
-                        self.set_unit(ID::INTEGER, &mut num.to_be_bytes());
-                        Ok(())
-
-                    } else {return Err(Error::Conversion("u64".to_string(), "i32".to_string()))}
+                    let num = signed(num, is_negative)
+                        .and_then(|num| i32::try_from(num).ok())
+                        .ok_or(Error::Conversion("u64".to_string(), "i32".to_string()))?;
+                    self.set_unit(ID::INTEGER, &num.to_be_bytes())
                 },
                 ID::INTEGER64 => {
                     let num = self.parse_number()?;
-                    if let Ok(mut num) = i64::try_from(num) {
-                        if is_negative {
-                            num = num.wrapping_neg();
-                        }
-                        self.set_unit(ID::INTEGER64, &mut num.to_be_bytes());
-                        Ok(())
-
-                    } else {return Err(Error::Conversion("u64".to_string(), "i32".to_string()))}
+                    let num = signed(num, is_negative)
+                        .ok_or(Error::Conversion("u64".to_string(), "i64".to_string()))?;
+                    self.set_unit(ID::INTEGER64, &num.to_be_bytes())
                 },
                 ID::DECIMAL => {
-                    let mut dec = self.parse_decimal()?;
-                    if is_negative {
-                        dec = -dec;
-                    }
-                    self.set_unit(ID::DECIMAL, &mut dec.to_be_bytes());
-                    Ok(())
+                    let dec = self.parse_decimal(is_negative)?;
+                    self.set_unit(ID::DECIMAL, &dec)
                 },
-            _ => return Err(Error::Expected("Integer".to_string(), "something else".to_string()))
+                key => Err(Error::Expected(format!("{:?}", key), "number".to_string()))
             }
         } else {
-           return Err(Error::UnknownExpect)
+            Err(Error::UnknownExpect)
         }
     }
 
     fn parse_number(&mut self) -> Result<u64>
     {
-        let mut int = match self.next_char().unwrap() {
-            ch @ b'0'..=b'9' => u64::from(ch - b'0'),
-            _ => return Err(Error::Expected("Integer".to_string(), "something else".to_string()))
+        let mut int = match self.next_char() {
+            Some(ch @ b'0'..=b'9') => u64::from(ch - b'0'),
+            Some(ch) => return Err(unexpected("digit", ch)),
+            None => return Err(Error::EOF)
         };
 
-        while self.peek_char().is_some() {
-            if !self.peek_char().unwrap().is_ascii_digit() {
-                return Ok(int)
-            }
-            match self.next_char() {
-                Some(ch @ b'0'..=b'9') => {
-                    int *= 10;
-                    int += u64::from(ch-b'0');
-                },
-                 _ => {
-                     return Ok(int)
-                 } 
-            }
+        while let Some(ch @ b'0'..=b'9') = self.peek_char().copied() {
+            self.eat_char();
+            int = int.checked_mul(10)
+                .and_then(|int| int.checked_add(u64::from(ch - b'0')))
+                .ok_or(Error::Conversion("number".to_string(), "u64".to_string()))?;
         }
         Ok(int)
     }
 
-    fn parse_decimal(&mut self) -> Result<f64> {
-        let mut buf = String::new();
-        while self.peek_char().is_some() {
-            let p = self.peek_char().unwrap();
-            if p != &b'.' && !p.is_ascii_digit() {
-                match fast_float::parse(buf) {
-                   Ok(f) => {return Ok(f)}
-                   Err(_) => {return Err(Error::Custom("unable to construct DECIMAL".to_string()))}
-                }
-            }
-            match self.next_char() {
-                Some(ch @ b'0'..=b'9') => {
-                    buf.push(ch as char);
-                },
-                Some(p @ b'.') => {
-                    buf.push(p as char);
+    // Keeps the decimal as it was written, e.g. '1.50' or '1e3', see [`primitive::encode_decimal`].
+    fn parse_decimal(&mut self, is_negative: bool) -> Result<Vec<u8>> {
+        let mut buf = if is_negative { vec![b'-'] } else { Vec::new() };
+        buf.extend(self.consume_while(|c| matches!(c, b'0'..=b'9' | b'.' | b'e' | b'E' | b'+' | b'-')));
+        let as_str = str::from_utf8(&buf)
+            .map_err(|_| Error::Conversion("bytes".to_string(), "utf8".to_string()))?;
+        primitive::encode_decimal(as_str)
+    }
+ 
+
+    fn set_unit(&mut self, id: ID, data: &[u8]) -> Result<()> {
+        let len = data.len();
+        self.writer.set_varint(varint::encoded_len(id as u64) + len)?;
+        self.writer.set_varint(id as usize)?;
+        self.writer.set(data)?;
+        Ok(())
+    }
+
+    fn set_string(&mut self, data: &mut [u8]) -> Result<()> {
+        match self.keys.last().copied() {
+            Some(key) => {
+                let as_str = str::from_utf8(data)
+                    .map_err(|_| Error::Conversion("bytes".to_string(), "utf8".to_string()))?;
+                let stored = primitive::encode_string(key, as_str)?;
+                match self.path.to_path("").as_str() {
+                    ".resourceType" => self.set_resource_type(as_str)?,
+                    ".id" => self.body_id = Some(as_str.to_string()),
+                    _ => ()
                 }
-                 _ => {
-                     println!("BUF: {}", buf);
-                     match fast_float::parse(buf) {
-                        Ok(f) => {return Ok(f)}
-                        Err(_) => {return Err(Error::Custom("unable to construct DECIMAL".to_string()))}
-                     }
-                 } 
-            }
+                self.set_unit(key, &stored)
+            },
+            None => Err(Error::UnknownExpect)
         }
-        match fast_float::parse(buf) {
-           Ok(f) => {return Ok(f)}
-           Err(_) => {return Err(Error::Custom("unable to construct DECIMAL".to_string()))}
+    }
+
+    // Elements before the 'resourceType' are parsed as part of a Patient, so any other
+    // type has to be given first.
+    fn set_resource_type(&mut self, name: &str) -> Result<()> {
+        let typ = ResourceId::try_from(name)?;
+        if typ != self.resource && self.keys_before_type {
+            return Err(Error::Expected("resourceType as first key".to_string(), name.to_string()))
         }
+        self.writer.set_typ(typ.clone())?;
+        self.resource = typ;
+        self.resource_type = Some(name.to_string());
+        Ok(())
     }
- 
 
-    fn parse_primitive_list(&mut self) {
-        let offset = self.writer.len();
-        let _ = self.writer.reserve_two();
-        self.lengths.push(offset);
-        if let Some(expects) = self.keys.clone().last() {
-            let _ = self.writer.set_u16(*expects as u16);
-            while self.peek_char().is_some() {
-                 match *expects {
-                    ID::LSTRING => {
-                        self.eat_whitespace();
-                        let mut data = self.parse_string();
-                        self.set_primitive_list_item(&mut data);
-                    }
-                    _ => unimplemented!("at parse_primitive_list")
-                }
-                if let Some(peeked) = self.peek_char() {
-                    if peeked != &b',' {
-                        break;
-                    } else {
-                        self.eat_char();
-                        self.eat_whitespace();
-                    }
-                }
-            }
+    fn set_primitive_list_item(&mut self, data: &mut [u8]) -> Result<()> {
+        let item = self.keys.last().and_then(|list| get_list_item(*list)).ok_or(Error::UnknownExpect)?;
+        // an empty item is a null
+        if data.is_empty() {
+            return Err(Error::Expected(format!("{:?}", item), "empty string".to_string()))
         }
+        let as_str = str::from_utf8(data)
+            .map_err(|_| Error::Conversion("bytes".to_string(), "utf8".to_string()))?;
+        let stored = primitive::encode_string(item, as_str)?;
+        let len = stored.len();
+        self.writer.set_varint(len)?;
+        self.writer.set(&stored)?;
+        Ok(())
     }
 
-    fn prepare_gp_list(&mut self) {
-        let offset = self.writer.len();
-        let _ = self.writer.reserve_two();
-        self.lengths.push(offset);
-        if let Some(expects) = self.keys.clone().last() {
-            let _ = self.writer.set_u16(*expects as u16);
+    fn set_bool(&mut self, ch: u8) -> Result<()> {
+        if self.keys.last().is_some_and(|key| *key != ID::BOOLEAN) {
+            let key = self.keys.last().unwrap();
+            return Err(Error::Expected(format!("{:?}", key), "boolean".to_string()))
+        }
+        if self.src.starts_with(b"true") {
+            self.eat_chars(4);
+            self.set_unit(ID::BOOLEAN, &[1])
+        } else if self.src.starts_with(b"false") {
+            self.eat_chars(5);
+            self.set_unit(ID::BOOLEAN, &[0])
+        } else {
+            Err(unexpected("true or false", ch))
         }
     }
 
-    fn set_unit(&mut self, id: ID, data: &mut [u8]) {
-        let len = data.len();
-        self.writer.set_u16(2u16 + len as u16).unwrap();
-        self.writer.set_u16(id as u16).unwrap();
-        self.writer.set(data.as_mut_ptr(), len).unwrap();
+    // The type of the object the next key belongs to, None for the resource itself.
+    // Items of a list of objects have the list on top of the [`KeyStack`].
+    fn context(&self) -> Option<ID> {
+        if self.keys.last_is_general_purpose() || self.keys.last_is_general_purpose_list() {
+            self.keys.last().copied()
+        } else {
+            None
+        }
     }
 
-    fn set_string(&mut self, data: &mut [u8]) {
-        self.handle_multiple(TypeClass::STRING);
-        match self.keys.last() {
-            Some(key) => {
-                if *key == ID::DATE || *key == ID::DATETIME {
-                    //TODO handle Error
-                    let as_str = str::from_utf8(data).unwrap();
-                    let dt = Fhir_DateTime::from_string(as_str).unwrap();
-                    self.set_unit(key.clone(), &mut dt.timestamp_millis_bytes())
-                } else {
-                    self.set_unit(key.clone(), data)
-                }
+    fn set_key(&mut self) -> Result<()> {
+        self.check_n_eat(b'"')?;
+        let key_bytes = self.consume_while(|c| c != b'"');
+        let name = String::from_utf8_lossy(&key_bytes).to_string();
+        self.path.set_key(name.clone());
+        let context = self.context();
+        let resource = &self.resource;
+        let (key_id, expects, chosen) = match name.strip_prefix('_') {
+            Some(primitive) => primitive_element_key(resource, context, primitive),
+            None => match get_key_id(&key_bytes) {
+                Some(key_id) => get_expects_in(resource, context, key_id).map(|expects| (key_id, expects, None)),
+                None => choice_key(resource, context, &name).map(|(key_id, chosen)| (key_id, chosen, None))
             }
-            None => ()
+        }.ok_or(Error::UnknownKeyInJson(name.clone()))?;
+        if self.path.depth() == 1 && key_id != ID::ResourceType {
+            self.keys_before_type = true;
+        }
+        if expects.is_multiple() {
+            return Err(Error::Expected(format!("{}[x] with the type as suffix", name), name))
         }
+        self.keys.push(expects);
+        self.element_choice = chosen;
+        self.writer.set_varint(key_id as usize)?;
+        self.check_n_eat(b'"')
     }
+}
 
-    fn set_primitive_list_item(&mut self, data: &mut [u8]) {
-        let len = data.len();
-        self.writer.set_u16(len as u16).unwrap();
-        self.writer.set(data.as_mut_ptr(), len).unwrap();
+// Splits the key of a choice element into the key and the chosen type,
+// e.g. 'valueQuantity' into [`ID::Value`] and [`ID::QUANTITY`].
+fn choice_key(resource: &ResourceId, context: Option<ID>, name: &str) -> Option<(ID, ID)> {
+    name.char_indices()
+        .filter(|(_, c)| c.is_ascii_uppercase())
+        .find_map(|(i, _)| {
+            let key = get_key_id(&name.as_bytes()[..i])?;
+            get_choice(resource, context, key, &name[i..]).map(|chosen| (key, chosen))
+        })
+}
+
+// The key of the '_' sibling of a primitive, e.g. '_birthDate', is the key of the
+// primitive, its value an [`ID::ELEMENT`] or for primitive lists an [`ID::LELEMENT`].
+// The sibling of a choice element, e.g. '_valueString', also returns the chosen type,
+// which is kept in front of the content of the ELEMENT.
+fn primitive_element_key(resource: &ResourceId, context: Option<ID>, name: &str) -> Option<(ID, ID, Option<ID>)> {
+    match get_key_id(name.as_bytes()) {
+        Some(key) => Some((key, get_primitive_element(get_expects_in(resource, context, key)?)?, None)),
+        None => {
+            let (key, chosen) = choice_key(resource, context, name)?;
+            Some((key, get_primitive_element(chosen)?, Some(chosen)))
+        }
     }
+}
 
-    fn set_bool(&mut self, ch: u8) {
-        match ch {
-            b't' => {
-                self.eat_chars(4);
-                self.set_unit(ID::BOOLEAN, &mut [1]);
+// Looks ahead for the 'resourceType' on the level of the resource, so keys in front of it
+// are read with the tables of the right resource. Malformed json is left to the parser.
+fn peek_resource_type(src: &[u8]) -> Option<&str> {
+    let mut depth = 0usize;
+    let mut key_next = false;
+    let mut i = 0;
+    while i < src.len() {
+        match src[i] {
+            b'{' => {
+                depth += 1;
+                key_next = depth == 1;
             },
-            b'f' => {
-                self.eat_chars(5);
-                self.set_unit(ID::BOOLEAN, &mut [0]);
-            }
-            _ => unreachable!("this is likely a bug in the json parser.")
+            b'[' => depth += 1,
+            b'}' | b']' => depth = depth.checked_sub(1)?,
+            b',' => key_next = depth == 1,
+            b'"' => {
+                let end = i + 1 + string_len(&src[i + 1..])?;
+                if key_next && &src[i + 1..end] == b"resourceType" {
+                    let value = src[end + 1..].trim_ascii_start().strip_prefix(b":")?.trim_ascii_start().strip_prefix(b"\"")?;
+                    return str::from_utf8(&value[..string_len(value)?]).ok()
+                }
+                key_next = false;
+                i = end;
+            },
+            _ => {}
         }
+        i += 1;
     }
+    None
+}
 
-    fn set_key(&mut self) {
-        self.check_n_eat(b'"', "set_key() first_check");
-        let key_bytes = self.consume_while(|c| c != b'"');
-        println!("FOUND KEY: {}", String::from_utf8(key_bytes.clone()).unwrap());
-        if let Some(key_id) = get_key_id(&key_bytes) {
-            if self.keys.len() > 0 {
-                println!("KeyStack has members {:?}", self.keys);
-                //if last is gp take the get_expected as function
-                if self.keys.last_is_general_purpose() {
-                    println!("Key is general purpose");
-                    let k = self.keys.last().unwrap();
-                    if let Some(expects) = get_from_sub::<u16>(*k as u16, key_id.clone().into()) {
-                        println!("Key expects {:?}", expects);
-                        if expects.is_multiple() {
-                            let map = copy_multiple(key_id.clone());
-                            self.keys.push_multiples(map);
-                        }
-                        self.keys.push(expects);
-                    } else {
-                        panic!("No EXPECTS found at general purpose.")
-                    }
-                } else {
-                    println!("Key is no general purpose");
-                    if let Some(expects) = get_expects::<u16>(key_id.clone().into()) {
-                        if expects.is_multiple() {
-                            let map = copy_multiple(key_id.clone());
-                            self.keys.push_multiples(map);
-                        }
-                        println!("Key expects {:?}", expects);
-                        self.keys.push(expects);
-                    } else {
-                        panic!("No EXPECTS found.")
-                    }
-                }
-                let _ = self.writer.set_u16(ID_LEN);
-                let _ = self.writer.set_u16(key_id);
-                self.check_n_eat(b'"', "set_key() has keys");
-            } else {
-                println!("KeyStack has no members");
-                if let Some(expects) = get_expects::<u16>(key_id.clone().into()) {
-                    println!("Key expects {:?}", expects);
-                        if expects.is_multiple() {
-                            let map = copy_multiple(key_id.clone());
-                            self.keys.push_multiples(map);
-                        }
-                    self.keys.push(expects);
-                } else {
-                    panic!("No EXPECTS found.")
-                }
-                let _ = self.writer.set_u16(ID_LEN);
-                let _ = self.writer.set_u16(key_id);
-                self.check_n_eat(b'"', "set_key() has no keys");
-                }
-            } else {
-                self.print_buffer();
-                panic!("Unknown Key {}", String::from_utf8(key_bytes).unwrap());
-            }
+// The number of bytes of a json string up to its closing '"'.
+fn string_len(src: &[u8]) -> Option<usize> {
+    let mut i = 0;
+    while i < src.len() {
+        match src[i] {
+            b'"' => return Some(i),
+            b'\\' => i += 2,
+            _ => i += 1
+        }
+    }
+    None
+}
+
+// An unexpected character 'got' where 'expected' should be.
+fn unexpected(expected: &str, got: u8) -> Error {
+    Error::Expected(expected.to_string(), (got as char).to_string())
+}
+
+// Applies the sign before the range is checked, so that e.g. i64::MIN fits.
+fn signed(num: u64, is_negative: bool) -> Option<i64> {
+    if is_negative {
+        0i64.checked_sub_unsigned(num)
+    } else {
+        i64::try_from(num).ok()
     }
 }
 
@@ -519,7 +704,10 @@ impl<'p> JsonParser<'p> {
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::store::bufreader::read_buffer;   
+    use crate::store::bufreader::{read_buffer, read_buffer_format};
+    use crate::store::resourcewriter::{ResourceHeader, FORMAT_U16};
+    use crate::store::header::Head;
+    use crate::error::JsonPosition;
     use std::{fs::read_to_string, fs::File, io::Read};
 
     fn assert_data(expects: Vec<u8>, result: Vec<u8>) {
@@ -551,147 +739,430 @@ mod test {
     fn json_parse_key_value() {
         let data = br#"{"resourceType": "patient"}"#;
 
-        // HEADER (skip for now) -> // ResourceID [16]  | ResourceType [2] 
-        // Total Length [2] 
-        // BODY form byte 6 on
-        // Length [2] | KeyId [2] | Length (7) [2] | ID (STRING) [2]  | DATA 
-        let expects: Vec<u8> = vec![0, 15, 0, 2, 16, 0, 0, 9, 0, 1, 112, 97, 116, 105, 101, 110, 116];
-        let read: Vec<u16> = vec![15, 2, 4096, 9, 1];
-        let result = from_json(data);
+        // HEADER is not part of the buffer
+        // BODY, every length and id is a varint, e.g. 4096 -> [128, 32]
+        // Length | KeyId | Length (8) | ID (STRING) | DATA 
+        let expects: Vec<u8> = vec![11, 128, 32, 8, 1, 112, 97, 116, 105, 101, 110, 116];
+        let read: Vec<usize> = vec![11, 4096, 8, 1];
+        let result = from_json(data).unwrap();
         assert_eq!(result, expects);
-        assert_eq!(read_buffer(&result), read);
+        assert_eq!(read_buffer(&result).unwrap(), read);
     }
 
     #[test]
     fn json_parse_several_key_values() {
         let data = br#"{"resourceType": "patient", "active": true}"#;
-        let expects: Vec<u8> = vec![0, 24, 0, 2, 16, 0, 0, 9, 0, 1, 112, 97, 116, 105, 101, 110, 116, 0, 2, 16, 1, 0, 3, 0, 2, 1];
-        let read: Vec<u16> = vec![24, 2, 4096, 9, 1, 2, 4097, 3, 2];
-        let result = from_json(data);
+        let expects: Vec<u8> = vec![16, 128, 32, 8, 1, 112, 97, 116, 105, 101, 110, 116, 129, 32, 2, 2, 1];
+        let read: Vec<usize> = vec![16, 4096, 8, 1, 4097, 2, 2];
+        let result = from_json(data).unwrap();
         assert_eq!(result, expects);
-        assert_eq!(read_buffer(&result), read);
+        assert_eq!(read_buffer(&result).unwrap(), read);
     }
     #[test]
     fn json_parse_obj_as_value() {
         let data = br#"{"text": {"status": "done", "div": "<div xmlns=\"http://www.w3.org/1999/xhtml\">"}}"#;
-                                        //    text          NARRATIVE     status
-                                        //ilen gpid  olen  ilen gpid      key  
-        let expects: Vec<u8> = vec![0,72, 0,2, 16,2, 0,66, 0,2, 2,0, 0,2, 16,3, 0,6, 0,3, 100, 111, 110, 101, 0,2, 16,4, 0, 44, 0,1,  60, 100, 105, 118, 32, 120, 109, 108, 110, 115, 61, 34, 104, 116, 116, 112, 58, 47, 47, 119, 119, 119, 46, 119, 51, 46, 111, 114, 103, 47, 49, 57, 57, 57, 47, 120, 104, 116, 109, 108, 34, 62];
-        let read: Vec<u16> = vec![72, 2, 4098, 66, 2, 512, 2, 4099, 6,3, 2, 4100, 44,1];
-        let result = from_json(data);
+                                    //    text    olen NARRATIVE status                        div         XHTML
+                                    //len key          gpid      key     len id                key     len id
+        let expects: Vec<u8> = vec![60, 130,32, 57, 128,4, 131,32, 5, 3, 100, 111, 110, 101, 132,32, 44, 128,2, 60, 100, 105, 118, 32, 120, 109, 108, 110, 115, 61, 34, 104, 116, 116, 112, 58, 47, 47, 119, 119, 119, 46, 119, 51, 46, 111, 114, 103, 47, 49, 57, 57, 57, 47, 120, 104, 116, 109, 108, 34, 62];
+        let read: Vec<usize> = vec![60, 4098, 57, 512, 4099, 5, 3, 4100, 44, 256];
+        let result = from_json(data).unwrap();
         assert_data(expects.clone(), result.clone());
         assert_eq!(result, expects);
-        assert_eq!(read_buffer(&result), read);
+        assert_eq!(read_buffer(&result).unwrap(), read);
     }
 
     #[test]
     fn json_parse_list_of_primitives() {
-        let data = br#"{"given": ["Rainer", "Maria"]}"#;
-        //                          len  id_l key   len  type len data                          len data                     
-        let expects: Vec<u8> = vec![0,23, 0,2, 16,7, 0,17, 0,22, 0,6, 82, 97, 105, 110, 101, 114, 0,5, 77, 97, 114, 105, 97];
-        let read: Vec<u16> =vec![23, 2, 4103, 17, 22, 6,5];
-        let result = from_json(data);
+        let data = br#"{"name": [{"given": ["Rainer", "Maria"]}]}"#;
+        //                          len key     len LHUMANNAME len  key     len type len data                          len data
+        let expects: Vec<u8> = vec![23, 133,32, 20, 128,16,    17,  135,32, 14, 22,  6, 82, 97, 105, 110, 101, 114, 5, 77, 97, 114, 105, 97];
+        let read: Vec<usize> = vec![23, 4101, 20, 2048, 17, 4103, 14, 22, 6, 5];
+        let result = from_json(data).unwrap();
         assert_eq!(result, expects);
-        assert_eq!(read_buffer(&result), read);
+        assert_eq!(read_buffer(&result).unwrap(), read);
     }
 
     #[test]
     fn json_parse_list_of_obj() {
         let data = br#"{"resourceType": "patient", "name": [{"use" : "official", "family" : "Chalmers", "given" : ["Peter", "James"]}, {"use" : "usual", "given": ["Jim"]}]}"#;
-        //                        t_len  len    id    len    kid   data                               
-        let expects: Vec<u8> = vec![0,107, 0,2, 16,0, 0, 9,  0, 1, 112, 97, 116, 105, 101, 110, 116, //16 
-        //status          LHUMANNAME 
-        //len kid   keylen             
-        0,2,  16,5,  0, 86, 8,0, //8       
+        //                        t_len key     len id  data                               
+        let expects: Vec<u8> = vec![75, 128,32, 8, 1, 112, 97, 116, 105, 101, 110, 116, //12 
+        //status       LHUMANNAME 
+        //kid    len   id             
+        133,32,  61,  128,16, //5       
         //len -> data [HUMANNAME]
-        0,54,  //2  
-        //len, key, len,  id   data
-        0,2,  16, 6,  0,10, 0,3, 111, 102, 102, 105, 99, 105, 97, 108, //16
-        //len key  len  id   data  //16
+        40,  //1  
+        //key    len id data
+        134,32,  9,  3, 111, 102, 102, 105, 99, 105, 97, 108, //12
+        //key    len id data  //12
+        136,32,  9,  1, 67, 104, 97, 108, 109, 101, 114, 115,   
+        //kid    len  id  len data                   len data    //16
+        135,32,  13,  22, 5, 80, 101, 116, 101, 114, 5, 74, 97, 109, 101, 115, 
+        //len -> data [HUMANNAME] 
+        17, //1
+        //kid    len id data   /9
+        134,32,  6,  3, 117, 115, 117, 97, 108, 
+        //kid    len id  len data  //8
+        135,32,  5,  22, 3, 74, 105, 109, 
+        ];
+
+        let read: Vec<usize> = vec![75, 4096, 8, 1, 4101, 61, 2048, 
+        40, 
+        4102, 9, 3,
+        4104, 9, 1,
+        4103, 13, 22, 5, 5,
+        17,
+        4102, 6, 3,
+        4103, 5, 22, 3,
+        ];
+        let result = from_json(data).unwrap();
+        assert_data(expects.clone(), result.clone());
+        assert_eq!(result, expects);
+        assert_eq!(read_buffer(&result).unwrap(), read);
+    }
+
+    #[test]
+    fn json_parse_old_format() {
+        // the same resource as in json_parse_list_of_obj, written with u16 lengths and ids
+        let old: Vec<u8> = vec![0,107, 0,2, 16,0, 0, 9,  0, 1, 112, 97, 116, 105, 101, 110, 116,
+        0,2,  16,5,  0, 86, 8,0,
+        0,54,
+        0,2,  16, 6,  0,10, 0,3, 111, 102, 102, 105, 99, 105, 97, 108,
         0,2,  16,8, 0,10, 0,1, 67, 104, 97, 108, 109, 101, 114, 115,   
-        //len kid  len   id    len  data                   len data    //22
         0,2,  16,7, 0,16, 0,22, 0,5, 80, 101, 116, 101, 114, 0,5, 74, 97, 109, 101, 115, 
-        //len -> data [HUMANNAME] 
-        0,26, //2
-        //len kid  len  id   data   /13
+        0,26,
         0,2,  16,6, 0,7, 0,3, 117, 115, 117, 97, 108, 
-        //len kid  len   id   len  data  //13
         0,2,  16,7, 0,7, 0,22, 0,3, 74, 105, 109, 
         ];
-
-        let read: Vec<u16> =vec![107, 2, 4096, 9, 1, 2, 4101, 86, 2048, 
+        let read: Vec<usize> = vec![107, 2, 4096, 9, 1, 2, 4101, 86, 2048, 
         54, 
         2, 4102, 10, 3,
         2, 4104, 10, 1,
         2, 4103, 16, 22, 5, 5,
         26,
-        //LEFT OF HERE
         2, 4102, 7, 3,
         2, 4103, 7, 22, 3,
         ];
-        let result = from_json(data);
-        assert_data(expects.clone(), result.clone());
-        assert_eq!(result, expects);
-        assert_eq!(read_buffer(&result), read);
+        assert_eq!(read_buffer_format(&old, FORMAT_U16).unwrap(), read);
 
+        let data = br#"{"text": {"status": "done"}}"#;
+        let old: Vec<u8> = vec![0,22, 0,2, 16,2, 0,16, 0,2, 2,0, 0,2, 16,3, 0,6, 0,3, 100, 111, 110, 101];
+        assert_eq!(read_buffer_format(&old, FORMAT_U16).unwrap(), vec![22, 2, 4098, 16, 2, 512, 2, 4099, 6, 3]);
+        assert_eq!(read_buffer(&from_json(data).unwrap()).unwrap(), vec![13, 4098, 10, 512, 4099, 5, 3]);
     }
 
     #[test]
     fn json_parse_numerics() {
         //positive int
-        let data = br#"{"rank": 123456}"#;
-        let expects: Vec<u8> = vec![0, 12, 0, 2, 16, 25, 0, 6, 0, 7, 0, 1, 226, 64];
-        let read: Vec<u16> = vec![12, 2, 4121, 6, 7];
-        let result = from_json(data);
+        let data = br#"{"telecom": [{"rank": 123456}]}"#;
+        let expects: Vec<u8> = vec![14, 152, 32, 11, 131, 16, 8, 153, 32, 5, 7, 0, 1, 226, 64];
+        let read: Vec<usize> = vec![14, 4120, 11, 2051, 8, 4121, 5, 7];
+        let result = from_json(data).unwrap();
         assert_eq!(result, expects);
-        assert_eq!(read_buffer(&result), read);
+        assert_eq!(read_buffer(&result).unwrap(), read);
 
-        let data = br#"{"attachment": [{"duration": 6.23456, "size": -1234}]}"#;
+        let data = br#"{"photo": [{"duration": 6.23456, "size": -1234}]}"#;
         let expects: Vec<u8> = vec![
             //total length
-            0,42, 
-            // len + id   
-            0, 2, 16, 38, 
+            29, 
+            //key
+            167, 32, 
             //list len
-            0,36, 
+            26, 
             //id ATT
-            8,5, 
+            133, 16, 
             //item length
-            0,32, 
+            23, 
             //key duration
-            0,2, 16,51, 
+            179, 32, 
             //obj l+id 
-            0,10, 0, 11, 
-            //data
-            64, 24, 240, 48, 127, 35, 204, 142, 
+            8, 11, 
+            //data, as written
+            54, 46, 50, 51, 52, 53, 54, 
             //key size
-            0,2,16,44,
+            172, 32,
             //obj l+id
-            0,10, 0, 10,
+            9, 10,
             //data
             255, 255, 255, 255, 255, 255, 251, 46
         ];
-        let result = from_json(data);
+        let result = from_json(data).unwrap();
         assert_eq!(result, expects);
     }
 
 
     #[test]
     fn json_parse_multiple() {
-        let data_is_boolean = br#"{"deceased": true}"#;
-        let data_is_date_time = br#"{"deceased": "2015-02-07T13:28:17-05:00"}"#;
-        let data_is_integer = br#"{"multipleBirth": 1}"#;
-        let expects_boolean: Vec<u8> = vec![0, 9, 0, 2, 16, 28, 0, 3, 0, 2, 1];
-        let result_boolean = from_json(data_is_boolean);
+        let data_is_boolean = br#"{"deceasedBoolean": true}"#;
+        let data_is_date_time = br#"{"deceasedDateTime": "2015-02-07T13:28:17-05:00"}"#;
+        let data_is_integer = br#"{"multipleBirthInteger": 1}"#;
+        let expects_boolean: Vec<u8> = vec![5, 156, 32, 2, 2, 1];
+        let result_boolean = from_json(data_is_boolean).unwrap();
         assert_eq!(result_boolean, expects_boolean);
 
-        let expects_date_time: Vec<u8> = vec![0, 16, 0, 2, 16, 28, 0, 10, 0, 6, 0, 0, 1, 75, 101, 76, 165, 232];
-        let result_date_time = from_json(data_is_date_time);
+        let expects_date_time: Vec<u8> = vec![12, 156, 32, 9, 6, 0, 0, 1, 75, 101, 76, 165, 232];
+        let result_date_time = from_json(data_is_date_time).unwrap();
         assert_eq!(result_date_time, expects_date_time);
-        let expects_integer: Vec<u8>   = vec![0, 12, 0, 2, 16, 29, 0, 6, 0, 9, 0, 0, 0, 1];
-        let result_integer = from_json(data_is_integer);
+        let expects_integer: Vec<u8>   = vec![8, 157, 32, 5, 9, 0, 0, 0, 1];
+        let result_integer = from_json(data_is_integer).unwrap();
         assert_eq!(result_integer, expects_integer);
 
+        // the chosen type is the id of the value, a general purpose one for complex types
+        let data = br#"{"extension": [{"url": "http://example.org/d", "valueDosage": {"doseAndRate": [{"doseQuantity": {"value": 2}, "rateRange": {"low": {"value": 1}}}]}}]}"#;
+        let read = read_buffer(&from_json(data).unwrap()).unwrap();
+        let dose = read.iter().position(|id| *id == ID::Dose as usize).unwrap();
+        assert_eq!(read[dose + 2], ID::SIMPLEQUANTITY as usize);
+        let rate = read.iter().position(|id| *id == ID::Rate as usize).unwrap();
+        assert_eq!(read[rate + 2], ID::RANGE as usize);
+
+        let (pos, err) = position(from_json(br#"{"deceased": true}"#));
+        assert_eq!(pos.path, "Resource.deceased");
+        assert!(matches!(err, Error::Expected(_, got) if got == "deceased"));
+        assert!(matches!(position(from_json(br#"{"deceasedInteger": 1}"#)).1, Error::UnknownKeyInJson(_)));
+        assert!(matches!(position(from_json(br#"{"deceasedBoolean": "2015"}"#)).1, Error::Expected(_, _)));
+        // choices depend on the object they are part of
+        assert!(from_json(br#"{"resourceType": "Observation", "effectiveTiming": {"repeat": {"boundsPeriod": {"start": "2015"}}}}"#).is_ok());
+        assert!(position(from_json(br#"{"resourceType": "Observation", "effectiveTiming": {"boundsPeriod": {"start": "2015"}}}"#)).0.path.ends_with("boundsPeriod"));
+        assert!(from_json(br#"{"extension": [{"url": "http://example.org/d", "valueDosage": {"doseAndRate": [{"doseRatio": {}}]}}]}"#).is_err());
+    }
+
+    #[test]
+    fn json_parse_long_values() {
+        // lengths are no longer limited to u16
+        let long = "x".repeat(70_000);
+        let data = format!(r#"{{"text": {{"div": "{long}"}}}}"#);
+        let result = from_json(data.as_bytes()).unwrap();
+        assert_eq!(read_buffer(&result).unwrap(), vec![70_014, 4098, 70_009, 512, 4100, 70_002, 256]);
+    }
+
+    fn position(result: Result<Vec<u8>>) -> (JsonPosition, Error) {
+        match result {
+            Err(Error::Json(position, err)) => (position, *err),
+            other => panic!("expected a json error, got {:?}", other)
+        }
+    }
+
+    #[test]
+    fn json_parse_errors() {
+        let data = br#"{"resourceType": "Patient", "name": [{"given": ["Peter"]}, {"given": ["Jim", 5]}]}"#;
+        let (pos, err) = position(from_json(data));
+        assert_eq!(pos.path, "Patient.name[1].given[1]");
+        assert_eq!((pos.offset, pos.line, pos.column), (77, 1, 78));
+        assert!(matches!(err, Error::Expected(_, got) if got == "5"));
+
+        let (pos, err) = position(from_json(b"{\"resourceType\": \"Patient\",\n  \"foo\": 1}"));
+        assert_eq!((pos.line, pos.column, pos.path.as_str()), (2, 7, "Patient.foo"));
+        assert!(matches!(err, Error::UnknownKeyInJson(key) if key == "foo"));
+
+        let (pos, err) = position(from_json(br#"{"text": {"status": "generated""#));
+        assert_eq!(pos.path, "Resource.text.status");
+        assert!(matches!(err, Error::EOF));
+
+        assert!(matches!(position(from_json(b"")).1, Error::EOF));
+        assert!(matches!(position(from_json(br#"{"active": true"#)).1, Error::EOF));
+        assert!(matches!(position(from_json(br#"{"active": tru}"#)).1, Error::Expected(_, _)));
+        assert!(matches!(position(from_json(br#"{"active": "yes"}"#)).1, Error::Expected(_, _)));
+        assert!(matches!(position(from_json(br#"{"active": true}}"#)).1, Error::Expected(_, _)));
+        assert!(matches!(position(from_json(br#"{"active": true]"#)).1, Error::Expected(_, _)));
+        assert!(matches!(position(from_json(br#"[{"active": true}]"#)).1, Error::Expected(_, _)));
+        assert!(matches!(position(from_json(br#"{"active": [true]}"#)).1, Error::Expected(_, _)));
+        assert!(matches!(position(from_json(br#"{"multipleBirthInteger": 12a}"#)).1, Error::Expected(_, _)));
+        assert!(matches!(position(from_json(br#"{"multipleBirthInteger": 99999999999999999999999}"#)).1, Error::Conversion(_, _)));
+        assert!(matches!(position(from_json(br#"{"gender": 5}"#)).1, Error::Expected(_, _)));
+        assert!(matches!(position(from_json(br#"{"photo": [{"duration": 1.}]}"#)).1, Error::Conversion(_, _)));
+        assert!(matches!(position(from_json(br#"{"photo": [{"duration": 1e400}]}"#)).1, Error::Conversion(_, _)));
+        assert!(from_json(br#"{"multipleBirthInteger": -2147483648}"#).is_ok());
+        assert!(matches!(position(from_json(br#"{"multipleBirthInteger": -2147483649}"#)).1, Error::Conversion(_, _)));
+        assert!(matches!(position(from_json(br#"{"multipleBirthInteger": 2147483648}"#)).1, Error::Conversion(_, _)));
+        let (pos, err) = position(from_json(br#"{"telecom": [{"rank": 0}]}"#));
+        assert_eq!(pos.path, "Resource.telecom[0].rank");
+        assert!(matches!(err, Error::Expected(_, got) if got == "0"));
+        assert!(from_json(br#"{"telecom": [{"rank": 1}]}"#).is_ok());
+        assert!(position(from_json(br#"{"birthDate": "not a date"}"#)).0.path.ends_with(".birthDate"));
+        assert_eq!(from_json(b" {} ").unwrap(), vec![0]);
+
+        // no prefix of a valid resource may panic
+        let data = br#"{"resourceType": "patient", "active": true, "name": [{"use": "official", "given": ["Peter", "James"]}], "text": {"status": "done"}, "multipleBirthInteger": 2, "deceasedDateTime": "2015-02-07T13:28:17-05:00"}"#;
+        assert!(from_json(data).is_ok());
+        for i in 0..data.len() {
+            assert!(from_json(&data[..i]).is_err(), "prefix of length {i} parsed");
+        }
+    }
+
+    #[test]
+    fn json_parse_escapes() {
+        let data = r#"{"text": {"div": "a\"b\\c\/d\b\f\n\r\t é 😀"}}"#.as_bytes();
+        let result = from_json(data).unwrap();
+        let div = "a\"b\\c/d\u{8}\u{c}\n\r\t \u{e9} \u{1f600}";
+        assert!(result.ends_with(div.as_bytes()));
+        assert_eq!(result[0] as usize, result.len() - 1);
+        // raw UTF-8 is kept as is
+        assert_eq!(from_json("{\"text\": {\"div\": \"\u{e9} \u{1f600}\"}}".as_bytes()).unwrap().len(), 18);
+
+        assert!(matches!(position(from_json(br#"{"text": {"div": "\x"}}"#)).1, Error::Expected(_, got) if got == "x"));
+        assert!(matches!(position(from_json(br#"{"text": {"div": "\u00g1"}}"#)).1, Error::Expected(_, _)));
+        assert!(matches!(position(from_json(br#"{"text": {"div": "\ud83d"}}"#)).1, Error::Expected(_, _)));
+        assert!(matches!(position(from_json(br#"{"text": {"div": "\ud83dA"}}"#)).1, Error::Expected(_, _)));
+        assert!(matches!(position(from_json(br#"{"text": {"div": "\ude00"}}"#)).1, Error::Expected(_, _)));
+        assert!(matches!(position(from_json(br#"{"text": {"div": "\u00"#)).1, Error::EOF));
+        assert!(matches!(position(from_json(b"{\"text\": {\"div\": \"a\nb\"}}")).1, Error::Expected(_, _)));
+        let (pos, err) = position(from_json(b"{\"text\": {\"div\": \"a\xffb\"}}"));
+        assert_eq!(pos.path, "Resource.text.div");
+        assert!(matches!(err, Error::Conversion(_, _)));
+    }
+
+    #[test]
+    fn json_parse_complex_types() {
+        // a SimpleQuantity has no comparator
+        let (pos, err) = position(from_json(br#"{"extension": [{"url": "http://example.org/d", "valueDosage": {"maxDosePerPeriod": [{"denominator": {"value": 1, "comparator": "<"}}]}}]}"#));
+        assert_eq!(pos.path, "Resource.extension[0].valueDosage.maxDosePerPeriod[0].denominator.comparator");
+        assert!(matches!(err, Error::UnknownKeyInJson(key) if key == "comparator"));
+        // items of primitive lists are checked like single values
+        let (pos, err) = position(from_json(br#"{"resourceType": "Observation", "effectiveTiming": {"repeat": {"timeOfDay": ["08:00:00", "8 pm"]}}}"#));
+        assert_eq!(pos.path, "Observation.effectiveTiming.repeat.timeOfDay[1]");
+        assert!(matches!(err, Error::Conversion(_, _)));
+        assert!(from_json(br#"{"resourceType": "Observation", "effectiveTiming": {"repeat": {"offset": -1}}}"#).is_err());
+        assert!(from_json(br#"{"meta": {"lastUpdated": "2020-01-01"}}"#).is_err());
+
+        let data = br#"{"meta": {"versionId": "1", "lastUpdated": "2020-01-01T00:00:00Z", "profile": ["http://example.org/p"]}}"#;
+        let result = from_json(data).unwrap();
+        let lengths = read_buffer(&result).unwrap();
+        assert_eq!(&lengths[1..4], &[ID::Meta as usize, lengths[2], ID::META as usize]);
+        assert!(lengths.contains(&(ID::LCANONICAL as usize)));
+    }
+
+    #[test]
+    fn json_parse_extensions() {
+        let data = br#"{"extension": [{"url": "http://hl7.org/fhir/us/core/StructureDefinition/us-core-race", "extension": [{"url": "ombCategory", "valueCoding": {"system": "urn:oid:2.16.840.1.113883.6.238", "code": "2106-3"}}, {"url": "text", "valueString": "White"}]}]}"#;
+        let read = read_buffer(&from_json(data).unwrap()).unwrap();
+        assert_eq!(&read[1..4], &[ID::Extension as usize, read[2], ID::LEXTENSION as usize]);
+        let value = read.iter().position(|id| *id == ID::Value as usize).unwrap();
+        assert_eq!(read[value + 2], ID::CODING as usize);
+
+        // on elements, with their id, and as modifier on the resource and backbone elements
+        assert!(from_json(br#"{"name": [{"id": "n1", "extension": [{"url": "http://example.org/a", "valueBoolean": true}], "family": "Chalmers"}]}"#).is_ok());
+        assert!(from_json(br#"{"modifierExtension": [{"url": "http://example.org/m", "valueCode": "x"}]}"#).is_ok());
+        assert!(from_json(br#"{"contact": [{"modifierExtension": [{"url": "http://example.org/m", "valueInteger": 1}]}]}"#).is_ok());
+        let (pos, err) = position(from_json(br#"{"maritalStatus": {"modifierExtension": []}}"#));
+        assert_eq!(pos.path, "Resource.maritalStatus.modifierExtension");
+        assert!(matches!(err, Error::UnknownKeyInJson(_)));
+        assert!(matches!(position(from_json(br#"{"extension": [{"valueFoo": 1}]}"#)).1, Error::UnknownKeyInJson(_)));
+        assert!(from_json(br#"{"extension": [{"valueInteger": "1"}]}"#).is_err());
+    }
+
+    #[test]
+    fn json_parse_primitive_elements() {
+        let data = br#"{"birthDate": "1974-12-25", "_birthDate": {"extension": [{"url": "http://hl7.org/fhir/StructureDefinition/patient-birthTime", "valueDateTime": "1974-12-25T14:35:45-05:00"}]}}"#;
+        let read = read_buffer(&from_json(data).unwrap()).unwrap();
+        let keys: Vec<(usize, usize)> = read.iter().enumerate()
+            .filter(|(_, id)| **id == ID::BirthDate as usize)
+            .map(|(i, _)| (read[i + 1], read[i + 2]))
+            .collect();
+        assert_eq!(keys.len(), 2);
+        assert_eq!(keys[0].1, ID::DATE as usize);
+        assert_eq!(keys[1].1, ID::ELEMENT as usize);
+
+        // null slots keep the items of a primitive list and their elements aligned
+        let data = br#"{"name": [{"given": ["Peter", null, "James"], "_given": [null, {"id": "g2"}, null]}]}"#;
+        let read = read_buffer(&from_json(data).unwrap()).unwrap();
+        assert!(read.windows(5).any(|w| w == [22, 5, 0, 5, ID::Given as usize]));
+        assert!(read.contains(&(ID::LELEMENT as usize)));
+        let (pos, err) = position(from_json(br#"{"name": [{"given": ["Peter", "James"], "_given": [null, {}]}]}"#));
+        assert_eq!(pos.path, "Resource.name[0]._given[1]");
+        assert!(matches!(err, Error::Expected(_, got) if got == "{}"));
+
+        // the sibling of a choice element keeps the chosen type in front of its content
+        let data = br#"{"deceasedDateTime": "2015-02-07", "_deceasedDateTime": {"id": "d"}}"#;
+        let read = read_buffer(&from_json(data).unwrap()).unwrap();
+        assert!(read.windows(3).any(|w| w == [ID::ELEMENT as usize, ID::DATETIME as usize, ID::Id as usize]));
+
+        let (pos, err) = position(from_json(br#"{"_name": {"id": "1"}}"#));
+        assert_eq!(pos.path, "Resource._name");
+        assert!(matches!(err, Error::UnknownKeyInJson(key) if key == "_name"));
+        assert!(matches!(position(from_json(br#"{"extension": [{"_valueQuantity": {}}]}"#)).1, Error::UnknownKeyInJson(_)));
+        assert!(matches!(position(from_json(br#"{"_birthDate": {"valueString": "x"}}"#)).1, Error::UnknownKeyInJson(_)));
+        assert!(from_json(br#"{"_given": {"id": "1"}}"#).is_err());
+        assert!(from_json(br#"{"birthDate": null}"#).is_err());
+        assert!(from_json(br#"{"name": [null]}"#).is_err());
+        assert!(from_json(br#"{"name": [{"given": [nul]}]}"#).is_err());
+        assert!(matches!(position(from_json(br#"{"name": [{"given": [""]}]}"#)).1, Error::Expected(_, _)));
+    }
+
+    #[test]
+    fn json_parse_resource_types() {
+        let data = br#"{"resourceType": "Observation", "status": "final", "code": {"coding": [{"system": "http://loinc.org", "code": "8867-4"}]}, "subject": {"reference": "Patient/example"}, "effectiveDateTime": "2024-05-01T09:30:00Z", "valueQuantity": {"value": 72, "unit": "beats/minute"}, "component": [{"code": {"text": "note"}, "valueString": "resting"}]}"#;
+        let (_, stored) = resource_from_json(data).unwrap();
+        assert_eq!(ResourceHeader::from_store(&stored).typ(), ResourceId::Observation);
+        let read = read_buffer(&from_json(data).unwrap()).unwrap();
+        let value = read.iter().position(|id| *id == ID::Value as usize).unwrap();
+        assert_eq!(read[value + 2], ID::QUANTITY as usize);
+
+        assert!(from_json(br#"{"resourceType": "Condition", "clinicalStatus": {"text": "active"}, "subject": {"reference": "Patient/1"}, "onsetAge": {"value": 40, "unit": "a"}, "evidence": [{"concept": {"text": "x"}}]}"#).is_ok());
+        assert!(from_json(br#"{"resourceType": "Encounter", "status": "completed", "class": [{"text": "ambulatory"}], "reason": [{"value": [{"reference": {"reference": "Condition/1"}}]}], "admission": {"admitSource": {"text": "gp"}}}"#).is_ok());
+        assert!(from_json(br#"{"resourceType": "Procedure", "status": "completed", "subject": {"reference": "Patient/1"}, "occurrencePeriod": {"start": "2024-01-01"}, "reportedBoolean": false, "instantiatesUri": ["http://example.org/p"]}"#).is_ok());
+        assert!(from_json(br#"{"resourceType": "AllergyIntolerance", "category": ["food"], "patient": {"reference": "Patient/1"}, "reaction": [{"manifestation": [{"concept": {"text": "hives"}}], "onset": "2020-02-02"}]}"#).is_ok());
+
+        // each resource only knows its own elements
+        let (pos, err) = position(from_json(br#"{"resourceType": "Observation", "birthDate": "1974-12-25"}"#));
+        assert_eq!(pos.path, "Observation.birthDate");
+        assert!(matches!(err, Error::UnknownKeyInJson(_)));
+        assert!(matches!(position(from_json(br#"{"resourceType": "Condition", "deceasedBoolean": true}"#)).1, Error::UnknownKeyInJson(_)));
+        assert!(matches!(position(from_json(br#"{"resourceType": "Basic"}"#)).1, Error::UnknownResourceStr(_)));
+        // Patient only knows its own elements, too
+        for data in [&br#"{"resourceType": "Patient", "status": "final"}"#[..], br#"{"resourceType": "Patient", "clinicalStatus": {"text": "active"}}"#,
+            br#"{"resourceType": "Patient", "subject": {"reference": "Patient/1"}}"#, br#"{"resourceType": "Patient", "comparator": "<"}"#, br#"{"given": ["Jim"]}"#] {
+            assert!(matches!(position(from_json(data)).1, Error::UnknownKeyInJson(_)));
+        }
+
+        // the resourceType need not be the first key
+        let (_, stored) = resource_from_json(br#"{"status": "final", "code": {"text": "pulse"}, "resourceType": "Observation", "valueString": "x"}"#).unwrap();
+        assert_eq!(ResourceHeader::from_store(&stored).typ(), ResourceId::Observation);
+        assert!(from_json(br#"{"gender": "male", "resourceType": "Patient"}"#).is_ok());
+        assert!(from_json(br#"{"text": {"div": "\"resourceType\": \"Observation\""}, "gender": "male"}"#).is_ok());
+        let (pos, err) = position(from_json(br#"{"status": "final", "resourceType": "Basic"}"#));
+        assert_eq!(pos.path, "Resource.status");
+        assert!(matches!(err, Error::UnknownKeyInJson(_)));
+    }
+
+    #[test]
+    fn json_parse_administrative_resources() {
+        let data = br#"{"resourceType": "Practitioner", "identifier": [{"system": "http://hl7.org/fhir/sid/us-npi", "value": "0123456789"}], "active": true, "name": [{"family": "Careful", "given": ["Adam"], "prefix": ["Dr"]}], "deceasedBoolean": false, "qualification": [{"code": {"text": "MD"}, "issuer": {"reference": "Organization/1"}}]}"#;
+        let (_, stored) = resource_from_json(data).unwrap();
+        assert_eq!(ResourceHeader::from_store(&stored).typ(), ResourceId::Practitioner);
+
+        assert!(from_json(br#"{"resourceType": "PractitionerRole", "practitioner": {"reference": "Practitioner/1"}, "specialty": [{"text": "cardiology"}], "availability": [{"availableTime": [{"daysOfWeek": ["mon", "tue"], "availableStartTime": "09:00:00", "availableEndTime": "16:30:00"}], "notAvailableTime": [{"description": "holidays", "during": {"start": "2024-12-24"}}]}]}"#).is_ok());
+        assert!(from_json(br#"{"resourceType": "Organization", "name": "Burgers University Medical Center", "alias": ["BUMC"], "contact": [{"purpose": {"text": "billing"}, "telecom": [{"system": "phone", "value": "022-655 2300"}]}], "qualification": [{"code": {"text": "accredited"}}]}"#).is_ok());
+        assert!(from_json(br#"{"resourceType": "Location", "status": "active", "operationalStatus": {"code": "O"}, "address": {"city": "Den Burg"}, "position": {"longitude": -83.6945691, "latitude": 42.25475478}, "virtualService": [{"addressUrl": "https://meet.example.org/room", "additionalInfo": ["https://example.org/help"], "maxParticipants": 10}]}"#).is_ok());
+        assert!(from_json(br#"{"resourceType": "HealthcareService", "providedBy": {"reference": "Organization/1"}, "name": "Consulting psychologists", "eligibility": [{"code": {"text": "DVA"}, "comment": "only **veterans**"}], "appointmentRequired": false}"#).is_ok());
+        assert!(from_json(br#"{"resourceType": "Endpoint", "status": "active", "connectionType": [{"coding": [{"code": "hl7-fhir-rest"}]}], "contact": [{"system": "email", "value": "endpoint@example.org"}], "payload": [{"mimeType": ["application/fhir+json"]}], "address": "http://fhir3.healthintersections.com.au/open/CarePlan", "header": ["bearer-code BASGS534s4"]}"#).is_ok());
+
+        assert!(matches!(position(from_json(br#"{"resourceType": "Endpoint", "address": {"city": "Den Burg"}}"#)).1, Error::Expected(..)));
+        assert!(matches!(position(from_json(br#"{"resourceType": "Location", "virtualService": [{"address": "x"}]}"#)).1, Error::Expected(..)));
+        assert!(matches!(position(from_json(br#"{"resourceType": "Organization", "gender": "other"}"#)).1, Error::UnknownKeyInJson(_)));
+    }
+
+    #[test]
+    fn json_parse_large_and_deep() {
+        // the stack does not grow with the number of tokens
+        let names = vec![r#"{"use": "usual", "family": "Chalmers", "given": ["Peter", "James"]}"#; 20_000].join(",");
+        let data = format!(r#"{{"resourceType": "patient", "name": [{names}], "active": true}}"#);
+        let result = from_json(data.as_bytes()).unwrap();
+        let read = read_buffer(&result).unwrap();
+        assert_eq!(read.iter().filter(|n| **n == ID::Family as usize).count(), 20_000);
+
+        // resource, name list and name are three levels
+        let data = br#"{"name": [{"given": ["Jim"]}]}"#;
+        assert!(from_json_with_max_depth(data, 4).is_ok());
+        assert!(from_json_with_max_depth(data, 3).is_err());
+        let (pos, err) = position(from_json_with_max_depth(data, 2));
+        assert!(matches!(err, Error::MaxDepth(2)));
+        assert_eq!(pos.path, "Resource.name[0]");
+
+        assert!(matches!(position(from_json(br#"{"active" true}"#)).1, Error::Expected(exp, _) if exp == ":"));
+        assert!(matches!(position(from_json(br#"{"active": true "rank": 1}"#)).1, Error::Expected(exp, _) if exp == ", or }"));
+        assert!(matches!(position(from_json(br#"{"name": [{"given": []}, ]}"#)).1, Error::Expected(exp, _) if exp == "{"));
+        assert!(matches!(position(from_json(br#"{"active": {"rank": 1}}"#)).1, Error::Expected(_, got) if got == "object"));
+        assert_eq!(from_json(br#"{"name": [], "identifier": []}"#).unwrap(), from_json(br#"{"name":[],"identifier":[]}"#).unwrap());
     }
 
     #[test]
@@ -700,7 +1171,7 @@ mod test {
         let mut data = Vec::<u8>::new();
         fd.read_to_end(&mut data).unwrap();
         //let expects = parse_byte_file("test_data/general_person_example_bytes.txt");
-        let result = from_json(&data);
+        let result = from_json(&data).unwrap();
 
     }
 }
@@ -1,3 +1,7 @@
 pub mod json;
+pub mod serializer;
+pub mod element;
+pub mod reader;
 mod stacks;
 pub mod datetime;
+pub mod primitive;
@@ -0,0 +1,286 @@
+use crate::datatypes::id::ID;
+use crate::datatypes::varint;
+use crate::error::{Error, Result};
+use crate::store::resourcewriter::FORMAT_DECIMAL;
+use super::datetime::Fhir_DateTime;
+use uuid::Uuid;
+
+//From https://build.fhir.org/datatypes.html#primitive
+//instant       2015-02-07T13:28:17.239+02:00       stored as i64 timestamp in milliseconds
+//time          13:28:17.239                        stored as u32 milliseconds since midnight
+//unsignedInt   0 to 2147483647                     stored as varint
+//decimal       1.50, 1e3                           stored as written, to keep the precision
+//oid           urn:oid:1.2.3.4.5                   stored without 'urn:oid:'
+//uuid          urn:uuid:c757873d-ec9a-4326-...     stored as 16 bytes
+
+const OID_PREFIX: &str = "urn:oid:";
+const UUID_PREFIX: &str = "urn:uuid:";
+const DAY_SECONDS: u32 = 86_400;
+
+
+/// Validates a primitive which is written as string in json and returns the bytes
+/// it is stored as.
+pub fn encode_string(id: ID, s: &str) -> Result<Vec<u8>> {
+    match id {
+        ID::STRING | ID::CODE | ID::ID | ID::URI | ID::URL | ID::BASE64BINARY | ID::MARKDOWN | ID::XHTML => {
+            Ok(s.as_bytes().to_vec())
+        },
+        ID::CANONICAL => {
+            if s.is_empty() || s.contains(char::is_whitespace) {
+                return Err(invalid(id, s))
+            }
+            Ok(s.as_bytes().to_vec())
+        },
+        ID::DATE | ID::DATETIME => Ok(Fhir_DateTime::from_string(s)?.timestamp_millis_bytes().to_vec()),
+        ID::INSTANT => Ok(Fhir_DateTime::from_instant_string(s)?.timestamp_millis_bytes().to_vec()),
+        ID::TIME => {
+            let millis = parse_time(s).ok_or_else(|| invalid(id, s))?;
+            Ok(millis.to_be_bytes().to_vec())
+        },
+        ID::OID => {
+            match s.strip_prefix(OID_PREFIX) {
+                Some(arcs) if is_oid(arcs) => Ok(arcs.as_bytes().to_vec()),
+                _ => Err(invalid(id, s))
+            }
+        },
+        ID::UUID => {
+            let hex = s.strip_prefix(UUID_PREFIX).ok_or_else(|| invalid(id, s))?;
+            match Uuid::parse_str(hex) {
+                // only the lower case, hyphenated form is allowed
+                Ok(uuid) if uuid.hyphenated().to_string() == hex => Ok(uuid.as_bytes().to_vec()),
+                _ => Err(invalid(id, s))
+            }
+        },
+        id => Err(Error::Expected(format!("{:?}", id), "string".to_string()))
+    }
+}
+
+/// Returns a primitive stored by [`encode_string`] as it is written in json, without quotes.
+pub fn decode_string(id: ID, data: &[u8]) -> Result<String> {
+    Ok(match id {
+        ID::STRING | ID::CODE | ID::ID | ID::URI | ID::URL | ID::BASE64BINARY | ID::MARKDOWN | ID::XHTML
+            | ID::CANONICAL => utf8(data)?.to_string(),
+        ID::DATE => Fhir_DateTime::from_timestamp_bytes(data)?.to_date_string(),
+        ID::DATETIME | ID::INSTANT => Fhir_DateTime::from_timestamp_bytes(data)?.to_datetime_string(),
+        ID::TIME => {
+            let bytes: [u8; 4] = data.try_into()
+                .map_err(|_| Error::Conversion("bytes".to_string(), "TIME".to_string()))?;
+            time_string(u32::from_be_bytes(bytes))?
+        },
+        ID::OID => format!("{}{}", OID_PREFIX, utf8(data)?),
+        ID::UUID => {
+            let uuid = Uuid::from_slice(data)
+                .map_err(|_| Error::Conversion("bytes".to_string(), "UUID".to_string()))?;
+            format!("{}{}", UUID_PREFIX, uuid.hyphenated())
+        },
+        _ => return Err(Error::UnknownStoreId(id as u16))
+    })
+}
+
+/// Checks that 'num' is a valid unsignedInt and returns it as stored.
+pub fn encode_unsigned(num: u64) -> Result<Vec<u8>> {
+    if num > i32::MAX as u64 {
+        return Err(Error::Conversion("u64".to_string(), "UNSIGNEDINT".to_string()))
+    }
+    Ok(varint::encode(num))
+}
+
+pub fn decode_unsigned(data: &[u8]) -> Result<u32> {
+    match varint::read(data)? {
+        (num, len) if len == data.len() && num <= i32::MAX as u64 => Ok(num as u32),
+        _ => Err(Error::Conversion("bytes".to_string(), "UNSIGNEDINT".to_string()))
+    }
+}
+
+/// Checks that 's' is a finite json number and returns it as stored, as it was written.
+pub fn encode_decimal(s: &str) -> Result<Vec<u8>> {
+    if !is_decimal(s) {
+        return Err(invalid(ID::DECIMAL, s))
+    }
+    Ok(s.as_bytes().to_vec())
+}
+
+/// Returns a decimal stored by [`encode_decimal`] as it is written in json. Formats
+/// before [`FORMAT_DECIMAL`] stored it as big-endian [`f64`].
+pub fn decode_decimal(data: &[u8], format: u8) -> Result<String> {
+    if format < FORMAT_DECIMAL {
+        let bytes: [u8; 8] = data.try_into()
+            .map_err(|_| Error::Conversion("bytes".to_string(), "f64".to_string()))?;
+        let dec = f64::from_be_bytes(bytes);
+        if !dec.is_finite() {
+            return Err(Error::Conversion("f64".to_string(), "DECIMAL".to_string()))
+        }
+        return Ok(dec.to_string())
+    }
+    match utf8(data)? {
+        s if is_decimal(s) => Ok(s.to_string()),
+        _ => Err(Error::Conversion("bytes".to_string(), "DECIMAL".to_string()))
+    }
+}
+
+
+fn invalid(id: ID, s: &str) -> Error {
+    Error::Conversion(s.to_string(), format!("{:?}", id))
+}
+
+fn utf8(data: &[u8]) -> Result<&str> {
+    std::str::from_utf8(data).map_err(|_| Error::Conversion("bytes".to_string(), "utf8".to_string()))
+}
+
+// hh:mm:ss(.s+)?, digits after the milliseconds are cut off. A leap second is only
+// allowed as 23:59:60, so that it can not be mistaken for the next minute.
+fn parse_time(s: &str) -> Option<u32> {
+    let bytes = s.as_bytes();
+    if bytes.len() < 8 || bytes[2] != b':' || bytes[5] != b':' {
+        return None
+    }
+    let (hours, minutes, seconds) = (two_digits(&bytes[0..2])?, two_digits(&bytes[3..5])?, two_digits(&bytes[6..8])?);
+    if hours > 23 || minutes > 59 || seconds > 60 || (seconds == 60 && (hours, minutes) != (23, 59)) {
+        return None
+    }
+    let mut millis = 0;
+    if bytes.len() > 8 {
+        let fraction = &bytes[9..];
+        if bytes[8] != b'.' || fraction.is_empty() || !fraction.iter().all(u8::is_ascii_digit) {
+            return None
+        }
+        for (i, digit) in fraction.iter().take(3).enumerate() {
+            millis += (digit - b'0') as u32 * 10u32.pow(2 - i as u32);
+        }
+    }
+    Some(((hours * 60 + minutes) * 60 + seconds) * 1000 + millis)
+}
+
+// -?(0|[1-9][0-9]*)(.[0-9]+)?([eE][+-]?[0-9]+)? as defined by RFC 8259, which
+// is also not too large for an f64.
+fn is_decimal(s: &str) -> bool {
+    let bytes = s.strip_prefix('-').unwrap_or(s).as_bytes();
+    let int = digits(bytes);
+    if int == 0 || (int > 1 && bytes[0] == b'0') {
+        return false
+    }
+    let mut rest = &bytes[int..];
+    if let Some(fraction) = rest.strip_prefix(b".") {
+        match digits(fraction) {
+            0 => return false,
+            len => rest = &fraction[len..]
+        }
+    }
+    if let Some(exp) = rest.strip_prefix(b"e").or_else(|| rest.strip_prefix(b"E")) {
+        let exp = exp.strip_prefix(b"+").or_else(|| exp.strip_prefix(b"-")).unwrap_or(exp);
+        match digits(exp) {
+            0 => return false,
+            len => rest = &exp[len..]
+        }
+    }
+    rest.is_empty() && matches!(fast_float::parse::<f64, _>(s), Ok(dec) if dec.is_finite())
+}
+
+fn digits(bytes: &[u8]) -> usize {
+    bytes.iter().take_while(|b| b.is_ascii_digit()).count()
+}
+
+fn two_digits(bytes: &[u8]) -> Option<u32> {
+    match bytes {
+        [a, b] if a.is_ascii_digit() && b.is_ascii_digit() => Some(((a - b'0') * 10 + (b - b'0')) as u32),
+        _ => None
+    }
+}
+
+fn time_string(millis: u32) -> Result<String> {
+    let (seconds, fraction) = (millis / 1000, millis % 1000);
+    let (hours, minutes, seconds) = match seconds {
+        s if s < DAY_SECONDS => (s / 3600, s / 60 % 60, s % 60),
+        DAY_SECONDS => (23, 59, 60),
+        _ => return Err(Error::Conversion("u32".to_string(), "TIME".to_string()))
+    };
+    if fraction > 0 {
+        Ok(format!("{:02}:{:02}:{:02}.{:03}", hours, minutes, seconds, fraction))
+    } else {
+        Ok(format!("{:02}:{:02}:{:02}", hours, minutes, seconds))
+    }
+}
+
+// [0-2](\.(0|[1-9][0-9]*))+
+fn is_oid(arcs: &str) -> bool {
+    let mut parts = arcs.split('.');
+    if !matches!(parts.next(), Some("0" | "1" | "2")) {
+        return false
+    }
+    let rest: Vec<&str> = parts.collect();
+    !rest.is_empty() && rest.iter().all(|arc| {
+        !arc.is_empty() && arc.bytes().all(|b| b.is_ascii_digit()) && (*arc == "0" || !arc.starts_with('0'))
+    })
+}
+
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn round_trip(id: ID, s: &str) -> String {
+        decode_string(id, &encode_string(id, s).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn primitive_round_trip() {
+        assert_eq!(round_trip(ID::INSTANT, "2015-02-07T13:28:17.239+02:00"), "2015-02-07T11:28:17.239Z");
+        assert_eq!(round_trip(ID::TIME, "13:28:17"), "13:28:17");
+        assert_eq!(round_trip(ID::TIME, "00:00:00.5"), "00:00:00.500");
+        assert_eq!(round_trip(ID::TIME, "23:59:60.123456"), "23:59:60.123");
+        assert_eq!(round_trip(ID::CANONICAL, "http://hl7.org/fhir/ValueSet/my-valueset|0.8"), "http://hl7.org/fhir/ValueSet/my-valueset|0.8");
+        assert_eq!(round_trip(ID::OID, "urn:oid:1.2.840.113619.19.2"), "urn:oid:1.2.840.113619.19.2");
+        assert_eq!(round_trip(ID::UUID, "urn:uuid:c757873d-ec9a-4326-a141-556f43239520"), "urn:uuid:c757873d-ec9a-4326-a141-556f43239520");
+        assert_eq!(round_trip(ID::MARKDOWN, "# Title\n*text*"), "# Title\n*text*");
+        assert_eq!(round_trip(ID::XHTML, "<div xmlns=\"http://www.w3.org/1999/xhtml\">text</div>"), "<div xmlns=\"http://www.w3.org/1999/xhtml\">text</div>");
+
+        assert_eq!(encode_string(ID::TIME, "00:00:01.5").unwrap(), 1500u32.to_be_bytes());
+        assert_eq!(encode_string(ID::OID, "urn:oid:2.16").unwrap(), b"2.16");
+        assert_eq!(encode_string(ID::UUID, "urn:uuid:c757873d-ec9a-4326-a141-556f43239520").unwrap().len(), 16);
+        assert_eq!(decode_unsigned(&encode_unsigned(0).unwrap()).unwrap(), 0);
+        assert_eq!(encode_unsigned(300).unwrap(), vec![172, 2]);
+        assert_eq!(decode_unsigned(&encode_unsigned(i32::MAX as u64).unwrap()).unwrap(), i32::MAX as u32);
+        for s in ["1.50", "2.0", "0", "-0.001", "1e3", "1.5E-10", "-2e+2"] {
+            assert_eq!(decode_decimal(&encode_decimal(s).unwrap(), FORMAT_DECIMAL).unwrap(), s);
+        }
+        assert_eq!(decode_decimal(&1.5f64.to_be_bytes(), FORMAT_DECIMAL - 1).unwrap(), "1.5");
+    }
+
+    #[test]
+    fn primitive_invalid() {
+        for (id, s) in [
+            (ID::INSTANT, "2015-02-07"),
+            (ID::INSTANT, "2015-02-07T13:28:17"),
+            (ID::TIME, "24:00:00"),
+            (ID::TIME, "12:60:00"),
+            (ID::TIME, "12:00:60"),
+            (ID::TIME, "12:00"),
+            (ID::TIME, "12:00:00."),
+            (ID::TIME, "12:00:00Z"),
+            (ID::CANONICAL, ""),
+            (ID::CANONICAL, "http://example.org/a b"),
+            (ID::OID, "1.2.3"),
+            (ID::OID, "urn:oid:3.1"),
+            (ID::OID, "urn:oid:1"),
+            (ID::OID, "urn:oid:1.02"),
+            (ID::OID, "urn:oid:1..2"),
+            (ID::UUID, "c757873d-ec9a-4326-a141-556f43239520"),
+            (ID::UUID, "urn:uuid:C757873D-EC9A-4326-A141-556F43239520"),
+            (ID::UUID, "urn:uuid:c757873dec9a4326a141556f43239520"),
+            (ID::BOOLEAN, "true"),
+        ] {
+            assert!(encode_string(id, s).is_err(), "{:?} accepted '{}'", id, s);
+        }
+        assert!(encode_unsigned(i32::MAX as u64 + 1).is_err());
+        assert!(decode_unsigned(&[172, 2, 0]).is_err());
+        assert!(decode_string(ID::TIME, &86_401_000u32.to_be_bytes()).is_err());
+        assert!(decode_string(ID::UUID, &[1, 2, 3]).is_err());
+        assert!(decode_string(ID::INTEGER, &[0, 0, 0, 1]).is_err());
+        for s in ["", "-", ".5", "1.", "01", "1e", "1e+", "1.5.2", "1e3e3", "+1", "1e400", "NaN", "inf"] {
+            assert!(encode_decimal(s).is_err(), "DECIMAL accepted '{}'", s);
+        }
+        assert!(decode_decimal(b"1.2a", FORMAT_DECIMAL).is_err());
+        assert!(decode_decimal(&f64::NAN.to_be_bytes(), FORMAT_DECIMAL - 1).is_err());
+    }
+}
@@ -0,0 +1,103 @@
+use crate::datatypes::id::{ID, ID_LEN};
+use crate::datatypes::varint;
+use crate::error::{Result, Error};
+use crate::store::resourcewriter::{FORMAT_U16, FORMAT_VARINT, FORMAT_UTF8, FORMAT_DECIMAL};
+
+
+/// Reads the lengths and ids of a stored resource in either format, see
+/// [`crate::store::resourcewriter::FORMAT_VERSION`]. Shared by the serializer,
+/// the element decoder and [`crate::store::bufreader`].
+pub struct Reader<'r> {
+    pub src: &'r [u8],
+    pub cursor: usize,
+    format: u8
+}
+
+impl<'r> Reader<'r> {
+    pub fn new(src: &'r [u8], format: u8) -> Result<Self> {
+        match format {
+            FORMAT_U16 | FORMAT_VARINT | FORMAT_UTF8 | FORMAT_DECIMAL => Ok(Self { src, cursor: 0, format }),
+            _ => Err(Error::UnknownStoreId(format as u16))
+        }
+    }
+
+    pub fn format(&self) -> u8 {
+        self.format
+    }
+
+    pub fn read_slice(&mut self, len: usize) -> Result<&'r [u8]> {
+        if len > self.src.len() - self.cursor {
+            return Err(Error::BufferUnderflow)
+        }
+        let slice = &self.src[self.cursor..self.cursor+len];
+        self.cursor += len;
+        Ok(slice)
+    }
+
+    /// Reads a length or an id, a [`u16`] or a varint depending on the format.
+    pub fn read_num(&mut self) -> Result<usize> {
+        if self.format == FORMAT_U16 {
+            let bytes = self.read_slice(2)?;
+            return Ok(u16::from_be_bytes([bytes[0], bytes[1]]) as usize)
+        }
+        let (value, len) = varint::read(&self.src[self.cursor..])?;
+        self.cursor += len;
+        usize::try_from(value).map_err(|_| Error::Conversion("u64".to_string(), "usize".to_string()))
+    }
+
+    pub fn read_id(&mut self) -> Result<ID> {
+        let raw = self.read_num()?;
+        let raw = u16::try_from(raw).map_err(|_| Error::Conversion("usize".to_string(), "ID".to_string()))?;
+        ID::try_from(raw).map_err(|_| Error::UnknownStoreId(raw))
+    }
+
+    /// Reads a length and returns the cursor position at which the unit ends.
+    pub fn read_end(&mut self) -> Result<usize> {
+        let len = self.read_num()?;
+        if len > self.src.len() - self.cursor {
+            return Err(Error::BufferUnderflow)
+        }
+        Ok(self.cursor + len)
+    }
+
+    /// Reads the total length of a resource, which has to cover the rest of the buffer.
+    pub fn read_resource_end(&mut self) -> Result<usize> {
+        let end = self.read_end()?;
+        if end != self.src.len() {
+            return Err(Error::BufferOverflow)
+        }
+        Ok(end)
+    }
+
+    /// Reads the key of a key/value pair. In [`FORMAT_U16`] the key is preceded by [`ID_LEN`].
+    pub fn read_key(&mut self) -> Result<ID> {
+        if self.format == FORMAT_U16 {
+            let id_len = self.read_num()?;
+            if id_len != ID_LEN as usize {
+                return Err(Error::Expected(ID_LEN.to_string(), id_len.to_string()))
+            }
+        }
+        let key = self.read_id()?;
+        if !key.is_key() {
+            return Err(Error::Expected("key".to_string(), format!("{:?}", key)))
+        }
+        Ok(key)
+    }
+
+    /// Reads the id of a value ending at 'end'.
+    ///
+    /// In [`FORMAT_U16`] a general purpose unit starts with [`ID_LEN`] followed by the general
+    /// purpose id, which reads like an [`ID::BOOLEAN`]. A boolean however only ever holds a
+    /// single byte. In [`FORMAT_VARINT`] the general purpose id comes right after the length.
+    pub fn read_value_id(&mut self, end: usize) -> Result<ID> {
+        let id = self.read_id()?;
+        if self.format == FORMAT_U16 && id == ID::BOOLEAN && end - self.cursor > 1 {
+            let gp = self.read_id()?;
+            if !gp.is_general_purpose() {
+                return Err(Error::Expected("general purpose id".to_string(), format!("{:?}", gp)))
+            }
+            return Ok(gp)
+        }
+        Ok(id)
+    }
+}
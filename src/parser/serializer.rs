@@ -0,0 +1,528 @@
+use crate::datatypes::id::{ID, get_expects_in, get_key_name, get_list_item, get_type_suffix};
+use crate::error::{Result, Error};
+use crate::resourcetypes::ResourceId;
+use crate::store::resourcewriter::{ResourceHeader, RESOURCE_HEADER_LEN, FORMAT_VERSION, FORMAT_UTF8};
+use crate::store::header::Head;
+use super::datetime::Fhir_DateTime;
+use super::primitive;
+use super::reader::Reader;
+
+/// Turns a stored resource (as produced by [`super::json::from_json`]) back into json.
+/// The buffer is expected to start with the total length, the [`crate::store::resourcewriter::ResourceHeader`]
+/// is not part of it.
+pub fn to_json(src: &[u8]) -> Result<String> {
+    let mut serializer = JsonSerializer {
+        reader: Reader::new(src, FORMAT_VERSION)?,
+        out: String::new(),
+        resource: ResourceId::Patient
+    };
+    serializer.serialize()?;
+    Ok(serializer.out)
+}
+
+/// Same as [`to_json`], but for a resource as it is stored, i.e. starting with its [`ResourceHeader`].
+/// The header only decides the format, the json is the resource as it was given.
+pub fn stored_to_json(src: &[u8]) -> Result<String> {
+    if src.len() < RESOURCE_HEADER_LEN {
+        return Err(Error::BufferUnderflow)
+    }
+    let header = ResourceHeader::from_store(src);
+    let mut serializer = JsonSerializer {
+        reader: Reader::new(&src[RESOURCE_HEADER_LEN..], header.format())?,
+        out: String::new(),
+        resource: header.typ()
+    };
+    serializer.serialize()?;
+    Ok(serializer.out)
+}
+
+/// Turns a resource as it is stored, i.e. starting with its [`ResourceHeader`], into json.
+/// 'id' and 'meta' are taken from the header and written right after 'resourceType',
+/// an 'id' stored with the resource is left out. Of a stored 'meta' everything but
+/// 'versionId' and 'lastUpdated' is kept.
+pub fn resource_to_json(src: &[u8]) -> Result<String> {
+    if src.len() < RESOURCE_HEADER_LEN {
+        return Err(Error::BufferUnderflow)
+    }
+    let header = ResourceHeader::from_store(src);
+    let mut serializer = JsonSerializer {
+        reader: Reader::new(&src[RESOURCE_HEADER_LEN..], header.format())?,
+        out: String::new(),
+        resource: header.typ()
+    };
+    serializer.serialize_resource(&header)?;
+    Ok(serializer.out)
+}
+
+
+struct JsonSerializer<'s> {
+    reader: Reader<'s>,
+    out: String,
+    // Decides the element tables, see [`get_expects_in`].
+    resource: ResourceId
+}
+
+impl<'s> JsonSerializer<'s> {
+
+    fn serialize(&mut self) -> Result<()> {
+        let end = self.reader.read_resource_end()?;
+        if let Some(resource) = self.read_resource_type(end)? {
+            self.resource = resource;
+        }
+        self.serialize_object(end, None)
+    }
+
+    // Without a header the type is taken from the stored 'resourceType'.
+    fn read_resource_type(&mut self, end: usize) -> Result<Option<ResourceId>> {
+        let Some((start, value_end)) = self.find_value(ID::ResourceType, end)? else {
+            return Ok(None)
+        };
+        let cursor = self.reader.cursor;
+        self.reader.cursor = start;
+        let id = self.reader.read_value_id(value_end)?;
+        let data = self.reader.read_slice(value_end - self.reader.cursor)?;
+        self.reader.cursor = cursor;
+        let name = primitive::decode_string(id, data)?;
+        ResourceId::try_from(name.as_str()).map(Some)
+    }
+
+    fn serialize_resource(&mut self, header: &ResourceHeader) -> Result<()> {
+        let end = self.reader.read_resource_end()?;
+        let meta = self.find_value(ID::Meta, end)?;
+        self.out.push('{');
+        let mut first = true;
+        let mut meta_written = false;
+        while self.reader.cursor < end {
+            let key = self.peek_key()?;
+            if key == ID::Id || key == ID::Meta {
+                self.skip_key_value()?;
+                continue;
+            }
+            if !first {
+                self.out.push(',');
+            }
+            first = false;
+            if !meta_written && key != ID::ResourceType {
+                self.push_meta(header, meta)?;
+                self.out.push(',');
+                meta_written = true;
+            }
+            self.serialize_key_value(None)?;
+            if !meta_written {
+                self.out.push(',');
+                self.push_meta(header, meta)?;
+                meta_written = true;
+            }
+        }
+        if self.reader.cursor != end {
+            return Err(Error::BufferOverflow)
+        }
+        if !meta_written {
+            self.push_meta(header, meta)?;
+        }
+        self.out.push('}');
+        Ok(())
+    }
+
+    // 'meta' is the start and end of a stored meta value, see [`Self::find_value`].
+    fn push_meta(&mut self, header: &ResourceHeader, meta: Option<(usize, usize)>) -> Result<()> {
+        let last_updated = Fhir_DateTime::from_timestamp_millis(header.last_updated())?;
+        self.out.push_str(&format!(
+            r#""id":"{}","meta":{{"versionId":"{}","lastUpdated":"{}""#,
+            header.id(), header.version_id(), last_updated.to_datetime_string()
+        ));
+        if let Some((start, end)) = meta {
+            let cursor = self.reader.cursor;
+            self.reader.cursor = start;
+            let id = self.reader.read_value_id(end)?;
+            if id != ID::META {
+                return Err(Error::Expected(format!("{:?}", ID::META), format!("{:?}", id)))
+            }
+            while self.reader.cursor < end {
+                let key = self.peek_key()?;
+                if key == ID::VersionId || key == ID::LastUpdated {
+                    self.skip_key_value()?;
+                    continue;
+                }
+                self.out.push(',');
+                self.serialize_key_value(Some(ID::META))?;
+            }
+            self.reader.cursor = cursor;
+        }
+        self.out.push('}');
+        Ok(())
+    }
+
+    // Returns where the value of 'key' starts and ends, if the object up to 'end' has it.
+    // The cursor is not moved.
+    fn find_value(&mut self, key: ID, end: usize) -> Result<Option<(usize, usize)>> {
+        let cursor = self.reader.cursor;
+        let mut found = None;
+        while self.reader.cursor < end {
+            let next = self.reader.read_key()?;
+            let value_end = self.reader.read_end()?;
+            if next == key {
+                found = Some((self.reader.cursor, value_end));
+            }
+            self.reader.cursor = value_end;
+        }
+        self.reader.cursor = cursor;
+        Ok(found)
+    }
+
+    // Returns the key of the next key/value pair without moving the cursor.
+    fn peek_key(&mut self) -> Result<ID> {
+        let cursor = self.reader.cursor;
+        let key = self.reader.read_key();
+        self.reader.cursor = cursor;
+        key
+    }
+
+    fn skip_key_value(&mut self) -> Result<()> {
+        self.reader.read_key()?;
+        let end = self.reader.read_end()?;
+        self.reader.cursor = end;
+        Ok(())
+    }
+
+    // Writes all key/value pairs up to 'end' as json object. 'context' is the type of
+    // the object, None for the resource itself.
+    fn serialize_object(&mut self, end: usize, context: Option<ID>) -> Result<()> {
+        self.out.push('{');
+        let mut first = true;
+        while self.reader.cursor < end {
+            if !first {
+                self.out.push(',');
+            }
+            first = false;
+            self.serialize_key_value(context)?;
+        }
+        if self.reader.cursor != end {
+            return Err(Error::BufferOverflow)
+        }
+        self.out.push('}');
+        Ok(())
+    }
+
+    /// Layout (every field is a varint):
+    /// |Key Id|Unit Length |ID    |data    |
+    /// |------|------------|------|--------|
+    ///
+    /// The unit length covers ID and data. The ID of a choice element is the chosen
+    /// type, which is added to the key. An [`ID::ELEMENT`] or [`ID::LELEMENT`] is
+    /// the '_' sibling of the primitive with the same key, for a choice element the
+    /// ELEMENT's data starts with the chosen type.
+    fn serialize_key_value(&mut self, context: Option<ID>) -> Result<()> {
+        let key = self.reader.read_key()?;
+        let name = get_key_name(key).ok_or(Error::UnknownStoreId(key as u16))?;
+        let end = self.reader.read_end()?;
+        let id = self.reader.read_value_id(end)?;
+        self.out.push('"');
+        if id == ID::ELEMENT || id == ID::LELEMENT {
+            self.out.push('_');
+        }
+        self.out.push_str(name);
+        if get_expects_in(&self.resource, context, key) == Some(ID::MULTIPLETYPES) {
+            let chosen = match id {
+                ID::ELEMENT => self.reader.read_id()?,
+                _ => id
+            };
+            self.out.push_str(get_type_suffix(chosen).ok_or(Error::UnknownExpect)?);
+        }
+        self.out.push_str("\":");
+        self.serialize_value(id, end)
+    }
+
+    fn serialize_value(&mut self, id: ID, end: usize) -> Result<()> {
+        if id.is_general_purpose() {
+            return self.serialize_object(end, Some(id))
+        }
+        if id.is_primitive() {
+            let data = self.reader.read_slice(end - self.reader.cursor)?;
+            return self.serialize_primitive(id, data)
+        }
+        if id.is_primitive_list() {
+            let item = get_list_item(id).ok_or(Error::UnknownStoreId(id as u16))?;
+            self.out.push('[');
+            let mut first = true;
+            while self.reader.cursor < end {
+                if !first {
+                    self.out.push(',');
+                }
+                first = false;
+                let len = self.reader.read_num()?;
+                if len == 0 {
+                    self.out.push_str("null");
+                    continue;
+                }
+                let data = self.reader.read_slice(len)?;
+                self.serialize_primitive(item, data)?;
+            }
+            self.out.push(']');
+            return Ok(())
+        }
+        if id.is_gp_list() {
+            self.out.push('[');
+            let mut first = true;
+            while self.reader.cursor < end {
+                if !first {
+                    self.out.push(',');
+                }
+                first = false;
+                let item_end = self.reader.read_end()?;
+                if id == ID::LELEMENT && item_end == self.reader.cursor {
+                    self.out.push_str("null");
+                    continue;
+                }
+                self.serialize_object(item_end, Some(id))?;
+            }
+            self.out.push(']');
+            return Ok(())
+        }
+        Err(Error::UnknownStoreId(id as u16))
+    }
+
+    fn serialize_primitive(&mut self, id: ID, data: &[u8]) -> Result<()> {
+        match id {
+            ID::BOOLEAN => {
+                match data {
+                    [0] => self.out.push_str("false"),
+                    [1] => self.out.push_str("true"),
+                    _ => return Err(Error::Conversion("bytes".to_string(), "BOOLEAN".to_string()))
+                }
+            },
+            ID::POSITIVEINT | ID::INTEGER => {
+                let bytes: [u8; 4] = data.try_into()
+                    .map_err(|_| Error::Conversion("bytes".to_string(), "i32".to_string()))?;
+                self.out.push_str(&i32::from_be_bytes(bytes).to_string());
+            },
+            ID::UNSIGNEDINT => {
+                self.out.push_str(&primitive::decode_unsigned(data)?.to_string());
+            },
+            ID::INTEGER64 => {
+                let bytes: [u8; 8] = data.try_into()
+                    .map_err(|_| Error::Conversion("bytes".to_string(), "i64".to_string()))?;
+                self.out.push_str(&i64::from_be_bytes(bytes).to_string());
+            },
+            ID::DECIMAL => {
+                self.out.push_str(&primitive::decode_decimal(data, self.reader.format())?);
+            },
+            _ => {
+                let s = primitive::decode_string(id, data)?;
+                self.push_string(&s);
+            }
+        }
+        Ok(())
+    }
+
+    // Strings are stored decoded since FORMAT_UTF8. Earlier formats kept every escape
+    // sequence except '\"' as it was sent, so a backslash is written as is for them.
+    fn push_string(&mut self, s: &str) {
+        let decoded = self.reader.format() >= FORMAT_UTF8;
+        self.out.push('"');
+        for ch in s.chars() {
+            match ch {
+                '"' => self.out.push_str("\\\""),
+                '\\' if decoded => self.out.push_str("\\\\"),
+                '\n' => self.out.push_str("\\n"),
+                '\r' => self.out.push_str("\\r"),
+                '\t' => self.out.push_str("\\t"),
+                '\u{8}' if decoded => self.out.push_str("\\b"),
+                '\u{c}' if decoded => self.out.push_str("\\f"),
+                c if (c as u32) < 0x20 => self.out.push_str(&format!("\\u{:04x}", c as u32)),
+                c => self.out.push(c)
+            }
+        }
+        self.out.push('"');
+    }
+}
+
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::parser::json::{from_json, resource_from_json};
+    use crate::store::resourcewriter::{FORMAT_U16, FORMAT_VARINT};
+
+    fn round_trip(data: &[u8]) -> String {
+        to_json(&from_json(data).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn serialize_resource_with_meta() {
+        let (id, mut resource) = resource_from_json(br#"{"resourceType": "patient", "id": "ignored", "active": true}"#).unwrap();
+        ResourceHeader::stamp(&mut resource, 2, 1686391200000);
+        assert_eq!(
+            resource_to_json(&resource).unwrap(),
+            format!(r#"{{"resourceType":"patient","id":"{id}","meta":{{"versionId":"2","lastUpdated":"2023-06-10T10:00:00Z"}},"active":true}}"#)
+        );
+        let (id, mut resource) = resource_from_json(br#"{"active": false, "resourceType": "Patient"}"#).unwrap();
+        ResourceHeader::stamp(&mut resource, 1, 1686391200001);
+        assert_eq!(
+            resource_to_json(&resource).unwrap(),
+            format!(r#"{{"id":"{id}","meta":{{"versionId":"1","lastUpdated":"2023-06-10T10:00:00.001Z"}},"active":false,"resourceType":"Patient"}}"#)
+        );
+        assert!(resource_to_json(&resource[..10]).is_err());
+    }
+
+    #[test]
+    fn serialize_resource_with_stored_meta() {
+        let data = br#"{"resourceType": "patient", "meta": {"versionId": "7", "lastUpdated": "2020-01-01T00:00:00Z", "profile": ["http://example.org/fhir/StructureDefinition/my-patient|1.0"], "tag": [{"code": "test"}]}, "active": true}"#;
+        let (id, mut resource) = resource_from_json(data).unwrap();
+        ResourceHeader::stamp(&mut resource, 2, 1686391200000);
+        assert_eq!(
+            resource_to_json(&resource).unwrap(),
+            format!(r#"{{"resourceType":"patient","id":"{id}","meta":{{"versionId":"2","lastUpdated":"2023-06-10T10:00:00Z","profile":["http://example.org/fhir/StructureDefinition/my-patient|1.0"],"tag":[{{"code":"test"}}]}},"active":true}}"#)
+        );
+    }
+
+    #[test]
+    fn serialize_key_values() {
+        let result = round_trip(br#"{"resourceType": "patient", "active": true}"#);
+        assert_eq!(result, r#"{"resourceType":"patient","active":true}"#);
+    }
+
+    #[test]
+    fn serialize_obj_as_value() {
+        let result = round_trip(br#"{"text": {"status": "done", "div": "<div xmlns=\"http://www.w3.org/1999/xhtml\">"}}"#);
+        assert_eq!(result, r#"{"text":{"status":"done","div":"<div xmlns=\"http://www.w3.org/1999/xhtml\">"}}"#);
+    }
+
+    #[test]
+    fn serialize_escapes() {
+        let result = round_trip(r#"{"text": {"div": "a\"b\\c\/d\b\f\n\r\t\u0001 é 😀"}}"#.as_bytes());
+        assert_eq!(result, "{\"text\":{\"div\":\"a\\\"b\\\\c/d\\b\\f\\n\\r\\t\\u0001 \u{e9} \u{1f600}\"}}");
+        assert_eq!(round_trip(result.as_bytes()), result);
+
+        // earlier formats stored escape sequences as they were sent
+        let (_, mut resource) = resource_from_json(br#"{"resourceType": "Patient", "text": {"div": "a\\nb"}}"#).unwrap();
+        assert_eq!(stored_to_json(&resource).unwrap(), r#"{"resourceType":"Patient","text":{"div":"a\\nb"}}"#);
+        resource[18] = FORMAT_VARINT;
+        assert_eq!(stored_to_json(&resource).unwrap(), r#"{"resourceType":"Patient","text":{"div":"a\nb"}}"#);
+    }
+
+    #[test]
+    fn serialize_decimals() {
+        let data = br#"{"photo": [{"duration": 1.50}, {"duration": 2.0}, {"duration": 1e3}, {"duration": -2.5E-3}]}"#;
+        assert_eq!(round_trip(data), r#"{"photo":[{"duration":1.50},{"duration":2.0},{"duration":1e3},{"duration":-2.5E-3}]}"#);
+
+        // earlier formats stored decimals as f64
+        let (_, resource) = resource_from_json(br#"{"resourceType": "Patient"}"#).unwrap();
+        let mut old = resource[..RESOURCE_HEADER_LEN].to_vec();
+        old[18] = FORMAT_UTF8;
+        old.extend([18, 167, 32, 15, 133, 16, 12, 179, 32, 9, 11, 64, 24, 240, 48, 127, 35, 204, 142]);
+        assert_eq!(stored_to_json(&old).unwrap(), r#"{"photo":[{"duration":6.23456}]}"#);
+    }
+
+    #[test]
+    fn serialize_complex_types() {
+        let data = br#"{"resourceType": "Observation", "effectiveTiming": {"event": ["2015-02-07T13:28:17-05:00"], "repeat": {"frequency": 2, "period": 1.5, "periodUnit": "d", "dayOfWeek": ["mon", "fri"], "timeOfDay": ["08:00:00", "20:30:00.5"], "offset": 30}}, "extension": [{"url": "http://example.org/d", "valueDosage": {"maxDosePerPeriod": [{"numerator": {"value": 10, "comparator": "<", "unit": "mg", "system": "http://unitsofmeasure.org", "code": "mg"}, "denominator": {"value": 1, "unit": "d"}}]}}, {"url": "http://example.org/r", "valueRelatedArtifact": {"type": "documentation", "document": {"contentType": "application/pdf", "language": "en", "url": "http://example.org/doc.pdf"}}}]}"#;
+        let expects = r#"{"resourceType":"Observation","effectiveTiming":{"event":["2015-02-07T18:28:17Z"],"repeat":{"frequency":2,"period":1.5,"periodUnit":"d","dayOfWeek":["mon","fri"],"timeOfDay":["08:00:00","20:30:00.500"],"offset":30}},"extension":[{"url":"http://example.org/d","valueDosage":{"maxDosePerPeriod":[{"numerator":{"value":10,"comparator":"<","unit":"mg","system":"http://unitsofmeasure.org","code":"mg"},"denominator":{"value":1,"unit":"d"}}]}},{"url":"http://example.org/r","valueRelatedArtifact":{"type":"documentation","document":{"contentType":"application/pdf","language":"en","url":"http://example.org/doc.pdf"}}}]}"#;
+        assert_eq!(round_trip(data), expects);
+        assert_eq!(round_trip(expects.as_bytes()), expects);
+    }
+
+    #[test]
+    fn serialize_name_and_reference() {
+        let data = br#"{"resourceType": "Patient", "name": [{"use": "official", "text": "Dr. Jim Chalmers Jr.", "family": "Chalmers", "given": ["Jim"], "prefix": ["Dr."], "suffix": ["Jr."], "period": {"start": "2015-02-07T00:00:00Z"}}], "managingOrganization": {"type": "Organization", "identifier": {"system": "urn:oid:1.2.36", "value": "12345"}, "display": "ACME"}}"#;
+        let expects = r#"{"resourceType":"Patient","name":[{"use":"official","text":"Dr. Jim Chalmers Jr.","family":"Chalmers","given":["Jim"],"prefix":["Dr."],"suffix":["Jr."],"period":{"start":"2015-02-07T00:00:00Z"}}],"managingOrganization":{"type":"Organization","identifier":{"system":"urn:oid:1.2.36","value":"12345"},"display":"ACME"}}"#;
+        assert_eq!(round_trip(data), expects);
+    }
+
+    #[test]
+    fn serialize_list_of_obj() {
+        let data = br#"{"resourceType": "patient", "name": [{"use" : "official", "family" : "Chalmers", "given" : ["Peter", "James"]}, {"use" : "usual", "given": ["Jim"]}]}"#;
+        let expects = r#"{"resourceType":"patient","name":[{"use":"official","family":"Chalmers","given":["Peter","James"]},{"use":"usual","given":["Jim"]}]}"#;
+        assert_eq!(round_trip(data), expects);
+    }
+
+    #[test]
+    fn serialize_numerics() {
+        assert_eq!(round_trip(br#"{"telecom": [{"rank": 123456}]}"#), r#"{"telecom":[{"rank":123456}]}"#);
+        let data = br#"{"photo": [{"duration": 6.23456, "size": -1234}]}"#;
+        assert_eq!(round_trip(data), r#"{"photo":[{"duration":6.23456,"size":-1234}]}"#);
+    }
+
+    #[test]
+    fn serialize_dates() {
+        let data = br#"{"birthDate": "1974-12-25", "name": [{"period": {"start": "2015-02-07T13:28:17-05:00", "end": "2017-01-01T00:00:00.123Z"}}]}"#;
+        let expects = r#"{"birthDate":"1974-12-25","name":[{"period":{"start":"2015-02-07T18:28:17Z","end":"2017-01-01T00:00:00.123Z"}}]}"#;
+        assert_eq!(round_trip(data), expects);
+    }
+
+    #[test]
+    fn serialize_multiple() {
+        assert_eq!(round_trip(br#"{"deceasedBoolean": true}"#), r#"{"deceasedBoolean":true}"#);
+        assert_eq!(round_trip(br#"{"deceasedDateTime": "2015-02-07T13:28:17-05:00"}"#), r#"{"deceasedDateTime":"2015-02-07T18:28:17Z"}"#);
+        assert_eq!(round_trip(br#"{"multipleBirthInteger": 1}"#), r#"{"multipleBirthInteger":1}"#);
+        assert_eq!(round_trip(br#"{"multipleBirthInteger": -2147483648}"#), r#"{"multipleBirthInteger":-2147483648}"#);
+        assert_eq!(round_trip(br#"{"photo": [{"size": -9223372036854775808}]}"#), r#"{"photo":[{"size":-9223372036854775808}]}"#);
+        // choices of complex types and in lists of objects
+        let data = r#"{"extension":[{"url":"http://example.org/d","valueDosage":{"doseAndRate":[{"type":{"text":"ordered"},"doseRange":{"low":{"value":1,"unit":"tbl"},"high":{"value":2,"unit":"tbl"}},"rateQuantity":{"value":3,"unit":"h"}},{"doseQuantity":{"value":1}}],"timing":{"repeat":{"boundsDuration":{"value":5,"comparator":"<","unit":"d"}}}}}]}"#;
+        assert_eq!(round_trip(data.as_bytes()), data);
+    }
+
+    #[test]
+    fn serialize_extensions() {
+        let data = r#"{"resourceType":"patient","extension":[{"url":"http://hl7.org/fhir/us/core/StructureDefinition/us-core-race","extension":[{"url":"ombCategory","valueCoding":{"system":"urn:oid:2.16.840.1.113883.6.238","code":"2106-3","display":"White"}},{"url":"text","valueString":"White"}]},{"url":"http://hl7.org/fhir/us/core/StructureDefinition/us-core-birthsex","valueCode":"F"}],"modifierExtension":[{"url":"http://example.org/m","valueQuantity":{"value":1.5,"unit":"kg"}}],"name":[{"id":"n1","extension":[{"url":"http://example.org/a","valuePeriod":{"start":"2015-02-07T18:28:17Z"}}],"family":"Chalmers"}],"contact":[{"modifierExtension":[{"url":"http://example.org/b","valueBoolean":false}],"gender":"male"}]}"#;
+        assert_eq!(round_trip(data.as_bytes()), data);
+    }
+
+    #[test]
+    fn serialize_primitive_elements() {
+        let data = r#"{"birthDate":"1974-12-25","_birthDate":{"id":"b","extension":[{"url":"http://hl7.org/fhir/StructureDefinition/patient-birthTime","valueDateTime":"1974-12-25T19:35:45Z"}]},"name":[{"_family":{"extension":[{"url":"http://hl7.org/fhir/StructureDefinition/data-absent-reason","valueCode":"unknown"}]},"given":["Peter",null,"James"],"_given":[null,{"extension":[{"url":"http://example.org/a","valueBoolean":true}]},null]}]}"#;
+        assert_eq!(round_trip(data.as_bytes()), data);
+
+        let data = r#"{"deceasedDateTime":"2015-02-07T00:00:00Z","_deceasedDateTime":{"id":"d"},"extension":[{"url":"http://example.org/a","_valueString":{"extension":[{"url":"http://example.org/b","valueCode":"x"}]}}]}"#;
+        assert_eq!(round_trip(data.as_bytes()), data);
+
+        let data = std::fs::read("test_data/general_person_example_no_extension.json").unwrap();
+        let result = round_trip(&data);
+        assert!(result.contains(r#""_birthDate":{"extension":[{"url":"http://hl7.org/fhir/StructureDefinition/patient-birthTime","valueDateTime":"#));
+        assert_eq!(round_trip(result.as_bytes()), result);
+    }
+
+    #[test]
+    fn serialize_resource_types() {
+        let data = r#"{"resourceType":"Observation","status":"final","code":{"text":"heart rate"},"effectiveDateTime":"2024-05-01T09:30:00Z","valueQuantity":{"value":72,"unit":"beats/minute"},"component":[{"code":{"text":"position"},"valueString":"sitting"}]}"#;
+        assert_eq!(round_trip(data.as_bytes()), data);
+
+        let (id, mut resource) = resource_from_json(br#"{"resourceType": "Condition", "subject": {"reference": "Patient/1"}, "onsetAge": {"value": 40, "unit": "a"}}"#).unwrap();
+        ResourceHeader::stamp(&mut resource, 1, 1686391200000);
+        assert_eq!(
+            resource_to_json(&resource).unwrap(),
+            format!(r#"{{"resourceType":"Condition","id":"{id}","meta":{{"versionId":"1","lastUpdated":"2023-06-10T10:00:00Z"}},"subject":{{"reference":"Patient/1"}},"onsetAge":{{"value":40,"unit":"a"}}}}"#)
+        );
+    }
+
+    #[test]
+    fn serialize_old_format() {
+        // {"resourceType": "patient", "text": {"status": "done"}} with u16 lengths and ids
+        let body = [0,37, 0,2, 16,0, 0,9, 0,1, 112, 97, 116, 105, 101, 110, 116,
+            0,2, 16,2, 0,16, 0,2, 2,0, 0,2, 16,3, 0,6, 0,3, 100, 111, 110, 101];
+        let (id, mut resource) = resource_from_json(br#"{"resourceType": "patient"}"#).unwrap();
+        resource.truncate(RESOURCE_HEADER_LEN);
+        resource[18] = FORMAT_U16;
+        resource.extend(body);
+        let len = resource.len() as u16;
+        resource[..2].copy_from_slice(&len.to_be_bytes());
+        ResourceHeader::stamp(&mut resource, 1, 1686391200000);
+        assert_eq!(stored_to_json(&resource).unwrap(), r#"{"resourceType":"patient","text":{"status":"done"}}"#);
+        assert_eq!(
+            resource_to_json(&resource).unwrap(),
+            format!(r#"{{"resourceType":"patient","id":"{id}","meta":{{"versionId":"1","lastUpdated":"2023-06-10T10:00:00Z"}},"text":{{"status":"done"}}}}"#)
+        );
+        assert!(to_json(&body).is_err());
+        resource[18] = 7;
+        assert!(stored_to_json(&resource).is_err());
+    }
+
+    #[test]
+    fn serialize_broken_buffer() {
+        let mut data = from_json(br#"{"resourceType": "patient", "active": true}"#).unwrap();
+        data.truncate(data.len() - 2);
+        assert!(to_json(&data).is_err());
+        assert!(to_json(&[4, 128, 32, 2, 2]).is_err());
+    }
+}
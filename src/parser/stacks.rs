@@ -1,56 +1,35 @@
 use crate::datatypes::id::ID;
-use std::collections::HashMap;
 
+/// Positions at which the length of an open object or list has to be inserted,
+/// once it is closed.
 #[derive(Default, Debug)]
 pub struct LengthStack {
-    offsets: Vec<u16>,
+    offsets: Vec<usize>,
 }
 
 impl LengthStack {
     pub fn push(&mut self, offset: usize) {
-        if let Ok(offs) = u16::try_from(offset) { 
-            self.offsets.push(offs)
-        }
+        self.offsets.push(offset)
     }
  
-    pub fn get_length(&mut self, offset: usize) -> Option<(usize, u16)> {
-        if let Some(last) = self.offsets.pop() {
-            match u16::try_from(offset) {
-                Ok(offs) => {
-                    let calc_offset = offs - last;
-                    Some((last as usize, calc_offset))},
-                Err(_) => None
-            }
-        } else {
-            match u16::try_from(offset) {
-                Ok(offs) => {
-                    Some((0, offs))},
-                Err(_) => None
-            }
-        }
+    /// Returns the position of the last open unit and its length up to 'offset'.
+    pub fn get_length(&mut self, offset: usize) -> Option<(usize, usize)> {
+        let last = self.offsets.pop()?;
+        offset.checked_sub(last).map(|length| (last, length))
     }
 }
 
 #[derive(Default, Debug, Clone)]
 pub struct KeyStack {
-    pub keys: Vec<ID>,
-    pub multiple: HashMap<u16, u16>  
+    pub keys: Vec<ID>
 }
 
 impl KeyStack {
 
-    pub fn len(&self) -> usize {
-        self.keys.len()
-    }
     pub fn push(&mut self, k: ID) {
         self.keys.push(k)
     }
-    pub fn push_multiples(&mut self, map: HashMap<u16, u16>) {
-        self.multiple = map;
-    }
-
     pub fn pop(&mut self) {
-        self.multiple = HashMap::new();
         if self.keys.len() > 0 {
             self.keys.pop();
         }  
@@ -86,12 +65,96 @@ impl KeyStack {
             false
         }
     }
+}
 
-    pub fn last_is_mulitple(&self) -> bool {
-        if let Some(last) = self.keys.last() {
-            last.is_multiple()
-        } else {
-            false
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Frame {
+    Object(Option<String>),
+    List(usize)
+}
+
+/// Path of the element currently parsed, e.g. 'Patient.name[1].given[0]'.
+/// Only used to tell where an error occured.
+#[derive(Default, Debug, Clone)]
+pub struct PathStack {
+    frames: Vec<Frame>
+}
+
+impl PathStack {
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// Returns the number of open objects and lists.
+    pub fn depth(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn push_object(&mut self) {
+        self.frames.push(Frame::Object(None))
+    }
+
+    pub fn push_list(&mut self) {
+        self.frames.push(Frame::List(0))
+    }
+
+    /// Sets the key of the innermost object.
+    pub fn set_key(&mut self, key: String) {
+        if let Some(Frame::Object(current)) = self.frames.last_mut() {
+            *current = Some(key)
+        }
+    }
+
+    /// Moves to the next item, if the innermost element is a list.
+    pub fn next_item(&mut self) {
+        if let Some(Frame::List(index)) = self.frames.last_mut() {
+            *index += 1
+        }
+    }
+
+    /// Closes the innermost object. Returns false, if it is no object.
+    pub fn pop_object(&mut self) -> bool {
+        match self.frames.last() {
+            Some(Frame::Object(_)) => self.frames.pop().is_some(),
+            _ => false
+        }
+    }
+
+    /// Closes the innermost list. Returns false, if it is no list.
+    pub fn pop_list(&mut self) -> bool {
+        match self.frames.last() {
+            Some(Frame::List(_)) => self.frames.pop().is_some(),
+            _ => false
+        }
+    }
+
+    /// Returns if the innermost element is an object.
+    pub fn in_object(&self) -> bool {
+        matches!(self.frames.last(), Some(Frame::Object(_)))
+    }
+
+    /// Returns what closes the innermost element.
+    pub fn closer(&self) -> &'static str {
+        match self.frames.last() {
+            Some(Frame::Object(_)) => "}",
+            Some(Frame::List(_)) => "]",
+            None => "end of input"
+        }
+    }
+
+    /// Returns the path starting at 'root', the name of the resource.
+    pub fn to_path(&self, root: &str) -> String {
+        let mut path = root.to_string();
+        for frame in self.frames.iter() {
+            match frame {
+                Frame::Object(Some(key)) => {
+                    path.push('.');
+                    path.push_str(key);
+                },
+                Frame::Object(None) => (),
+                Frame::List(index) => path.push_str(&format!("[{index}]"))
+            }
         }
+        path
     }
 }
@@ -0,0 +1,50 @@
+use crate::datatypes::id::ID;
+use crate::resourcetypes::ResourceId;
+
+
+/// Type of a search parameter, decides how values are compared.
+/// See http://hl7.org/fhir/search.html#ptypes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParamType {
+    String,
+    Token,
+    Date,
+    Reference
+}
+
+#[derive(Debug)]
+pub struct SearchParam {
+    pub name: &'static str,
+    pub typ: ParamType,
+    /// Paths (lists of keys) to the elements the parameter is matched against.
+    pub paths: &'static [&'static [ID]]
+}
+
+/// Search parameters of [Patient](http://hl7.org/fhir/patient.html#search).
+static PATIENT: [SearchParam; 11] = [
+    SearchParam { name: "name",         typ: ParamType::String, paths: &[&[ID::Name, ID::Family], &[ID::Name, ID::Given],
+        &[ID::Name, ID::Text], &[ID::Name, ID::Prefix], &[ID::Name, ID::Suffix]] },
+    SearchParam { name: "family",       typ: ParamType::String, paths: &[&[ID::Name, ID::Family]] },
+    SearchParam { name: "given",        typ: ParamType::String, paths: &[&[ID::Name, ID::Given]] },
+    SearchParam { name: "identifier",   typ: ParamType::Token,  paths: &[&[ID::Identifier]] },
+    SearchParam { name: "birthdate",    typ: ParamType::Date,   paths: &[&[ID::BirthDate]] },
+    SearchParam { name: "gender",       typ: ParamType::Token,  paths: &[&[ID::Gender]] },
+    SearchParam { name: "active",       typ: ParamType::Token,  paths: &[&[ID::Active]] },
+    SearchParam { name: "address-city", typ: ParamType::String, paths: &[&[ID::Address, ID::City]] },
+    SearchParam { name: "telecom",      typ: ParamType::Token,  paths: &[&[ID::Telecom]] },
+    SearchParam { name: "general-practitioner", typ: ParamType::Reference, paths: &[&[ID::GeneralPractitioner]] },
+    SearchParam { name: "organization", typ: ParamType::Reference, paths: &[&[ID::ManagingOrganization]] },
+];
+
+/// Returns all search parameters of resource type 'typ'.
+pub fn get_params(typ: &ResourceId) -> &'static [SearchParam] {
+    match typ {
+        ResourceId::Patient => &PATIENT,
+        _ => &[]
+    }
+}
+
+/// Returns the search parameter 'name' of resource type 'typ'.
+pub fn get_param(typ: &ResourceId, name: &str) -> Option<&'static SearchParam> {
+    get_params(typ).iter().find(|param| param.name == name)
+}
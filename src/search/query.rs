@@ -0,0 +1,474 @@
+use std::collections::BTreeSet;
+use chrono::{DateTime, Months, Utc};
+use uuid::Uuid;
+use crate::datatypes::id::ID;
+use crate::error::{Result, Error};
+use crate::parser::datetime::Fhir_DateTime;
+use crate::parser::element::{self, Element, Primitive};
+use crate::resourcetypes::ResourceId;
+use crate::store::history::read_at;
+use crate::store::index::{self, Pages};
+use crate::store::secondary;
+use crate::store::store::read_resource;
+use crate::store::resourcewriter::ResourceHeader;
+use crate::store::header::Head;
+use super::params::{get_param, ParamType, SearchParam};
+use super::terms;
+
+pub(super) const DAY_MILLIS: i64 = 24 * 60 * 60 * 1000;
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Modifier {
+    Exact,
+    Contains,
+    Missing
+}
+
+/// Comparison of a date parameter, see http://hl7.org/fhir/search.html#prefix
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Prefix {
+    Eq,
+    Ne,
+    Gt,
+    Lt,
+    Ge,
+    Le,
+    Sa,
+    Eb,
+    Ap
+}
+
+/// A single search parameter of a query, the resource has to match any of its values.
+#[derive(Debug)]
+pub struct Criterion {
+    pub param: &'static SearchParam,
+    pub modifier: Option<Modifier>,
+    pub values: Vec<String>
+}
+
+/// A parsed search query, a resource has to match all of its criteria.
+#[derive(Debug, Default)]
+pub struct Query {
+    pub criteria: Vec<Criterion>,
+    /// Maximum number of resources to return ('_count').
+    pub count: Option<usize>
+}
+
+impl Query {
+    /// Parses the query string of a search on resource type 'typ',
+    /// e.g. 'family:exact=Chalmers&birthdate=ge1970'.
+    pub fn parse(typ: &ResourceId, query: &str) -> Result<Self> {
+        let mut result = Query::default();
+        for pair in query.split('&').filter(|p| !p.is_empty()) {
+            let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+            let name = decode_component(name)?;
+            let value = decode_component(value)?;
+            if name == "_count" {
+                let count = value.parse::<usize>()
+                    .map_err(|_| Error::Search(format!("invalid _count '{value}'")))?;
+                result.count = Some(count);
+                continue;
+            }
+            let (name, modifier) = match name.split_once(':') {
+                Some((name, "exact")) => (name, Some(Modifier::Exact)),
+                Some((name, "contains")) => (name, Some(Modifier::Contains)),
+                Some((name, "missing")) => (name, Some(Modifier::Missing)),
+                Some((_, modifier)) => return Err(Error::Search(format!("unknown modifier '{modifier}'"))),
+                None => (name.as_str(), None)
+            };
+            let param = get_param(typ, name)
+                .ok_or_else(|| Error::Search(format!("unknown search parameter '{name}'")))?;
+            if matches!(modifier, Some(Modifier::Exact) | Some(Modifier::Contains)) && param.typ != ParamType::String {
+                return Err(Error::Search(format!("modifier not supported on '{name}'")))
+            }
+            let values = split_values(&value);
+            for value in values.iter() {
+                match modifier {
+                    Some(Modifier::Missing) if value != "true" && value != "false" => {
+                        return Err(Error::Search(format!("':missing' expects 'true' or 'false', got '{value}'")))
+                    },
+                    None if param.typ == ParamType::Date => { parse_date(value)?; },
+                    _ => ()
+                }
+            }
+            result.criteria.push(Criterion { param, modifier, values });
+        }
+        Ok(result)
+    }
+
+    /// Returns if the resource (as decoded by [`element::decode`]) matches all criteria.
+    pub fn matches(&self, resource: &Element) -> Result<bool> {
+        for criterion in self.criteria.iter() {
+            if !criterion.matches(resource)? {
+                return Ok(false)
+            }
+        }
+        Ok(true)
+    }
+}
+
+impl Criterion {
+    fn matches(&self, resource: &Element) -> Result<bool> {
+        let targets: Vec<&Element> = self.param.paths.iter()
+            .flat_map(|path| resource.select_elements(path))
+            .collect();
+        if let Some(Modifier::Missing) = self.modifier {
+            return Ok(self.values.iter().any(|v| (v == "true") == targets.is_empty()))
+        }
+        for value in self.values.iter() {
+            for target in targets.iter() {
+                let matched = match self.param.typ {
+                    ParamType::String => match_string(value, self.modifier, target)?,
+                    ParamType::Token => match_token(value, target)?,
+                    ParamType::Date => match_date(value, target)?,
+                    ParamType::Reference => match_reference(value, target)?
+                };
+                if matched {
+                    return Ok(true)
+                }
+            }
+        }
+        Ok(false)
+    }
+}
+
+
+/// Returns the number of resources of type 'typ' matching 'query' and the stored ones
+/// (including [`ResourceHeader`]) of them, at most `query.count`. The resources are looked
+/// up in the secondary index at 'search_root' and read through the primary index at
+/// 'index_root'. Only if no criterion can be answered by the secondary index (or there is
+/// none), all resources are scanned.
+pub fn search<P: Pages>(pages: &mut P, index_root: u16, search_root: u16, typ: &ResourceId, query: &Query) -> Result<(usize, Vec<Vec<u8>>)> {
+    let candidates = match search_root {
+        0 => None,
+        root => candidates(pages, root, typ, query)?
+    };
+    let candidates = match candidates {
+        Some(candidates) => candidates,
+        None => return scan(pages, index_root, typ, query)
+    };
+    let mut found = Found::new(query.count);
+    for id in candidates.iter() {
+        let stored = match read_resource(pages, index_root, id)? {
+            Some(stored) => stored,
+            None => continue
+        };
+        if &ResourceHeader::from_store(&stored).typ() != typ {
+            continue;
+        }
+        found.add(stored, query)?;
+    }
+    Ok((found.total, found.resources))
+}
+
+/// Like [`search`], by looking at every resource in the index at 'root'.
+pub fn scan<P: Pages>(pages: &mut P, root: u16, typ: &ResourceId, query: &Query) -> Result<(usize, Vec<Vec<u8>>)> {
+    let mut found = Found::new(query.count);
+    for (_, loc) in index::entries(pages, root)? {
+        let stored = read_at(pages, loc)?;
+        let header = ResourceHeader::from_store(&stored);
+        if header.is_deleted() || &header.typ() != typ {
+            continue;
+        }
+        found.add(stored, query)?;
+    }
+    Ok((found.total, found.resources))
+}
+
+// The matches of a search: all are counted, only the first 'limit' ones are kept.
+struct Found {
+    total: usize,
+    limit: Option<usize>,
+    resources: Vec<Vec<u8>>
+}
+
+impl Found {
+    fn new(limit: Option<usize>) -> Found {
+        Found { total: 0, limit, resources: Vec::new() }
+    }
+
+    fn add(&mut self, stored: Vec<u8>, query: &Query) -> Result<()> {
+        let resource = element::decode_stored(&stored)?;
+        if query.matches(&resource)? {
+            self.total += 1;
+            if self.limit.is_none_or(|limit| self.resources.len() < limit) {
+                self.resources.push(stored);
+            }
+        }
+        Ok(())
+    }
+}
+
+
+// Ids of the resources found in the secondary index for all criteria it can answer,
+// [`None`] if it can answer none of them.
+fn candidates<P: Pages>(pages: &mut P, root: u16, typ: &ResourceId, query: &Query) -> Result<Option<BTreeSet<Uuid>>> {
+    let mut result: Option<BTreeSet<Uuid>> = None;
+    for criterion in query.criteria.iter() {
+        let ranges = match terms::ranges(typ, criterion)? {
+            Some(ranges) => ranges,
+            None => continue
+        };
+        let mut found = BTreeSet::new();
+        for (from, to) in ranges.iter() {
+            found.extend(secondary::range(pages, root, from, to)?.iter().map(terms::key_id));
+        }
+        result = Some(match result {
+            Some(ids) => ids.intersection(&found).copied().collect(),
+            None => found
+        });
+    }
+    Ok(result)
+}
+
+
+// Strings match case insensitive at the start, ':exact' matches the whole
+// string exactly, ':contains' anywhere.
+fn match_string(value: &str, modifier: Option<Modifier>, target: &Element) -> Result<bool> {
+    let target = match target {
+        Element::Primitive(primitive) => primitive.to_json_string()?,
+        _ => return Ok(false)
+    };
+    Ok(match modifier {
+        Some(Modifier::Exact) => target == value,
+        Some(Modifier::Contains) => target.to_lowercase().contains(&value.to_lowercase()),
+        _ => target.to_lowercase().starts_with(&value.to_lowercase())
+    })
+}
+
+// Tokens are given as 'code', 'system|code', '|code' (no system) or 'system|'.
+// A target is either a primitive (code, boolean) or an element with system and value/code.
+fn match_token(value: &str, target: &Element) -> Result<bool> {
+    let (system, code) = match target {
+        Element::Primitive(primitive) => (None, Some(primitive.to_json_string()?)),
+        Element::Object(_) => {
+            let system = target.select(&[ID::System]).first().map(|p| p.to_json_string()).transpose()?;
+            let code = target.select(&[ID::Value]).first()
+                .or(target.select(&[ID::Code]).first())
+                .map(|p| p.to_json_string())
+                .transpose()?;
+            (system, code)
+        },
+        Element::List(_) => return Ok(false)
+    };
+    Ok(match value.split_once('|') {
+        Some(("", want)) => system.is_none() && code.as_deref() == Some(want),
+        Some((want_system, "")) => system.as_deref() == Some(want_system),
+        Some((want_system, want)) => system.as_deref() == Some(want_system) && code.as_deref() == Some(want),
+        None => code.as_deref() == Some(value)
+    })
+}
+
+// References match on the whole reference or, if the value is only an id or
+// 'type/id', on the end of the reference.
+fn match_reference(value: &str, target: &Element) -> Result<bool> {
+    for reference in target.select(&[ID::Reference]) {
+        let reference = reference.to_json_string()?;
+        if reference == value || reference.ends_with(&format!("/{value}")) {
+            return Ok(true)
+        }
+    }
+    Ok(false)
+}
+
+fn match_date(value: &str, target: &Element) -> Result<bool> {
+    let (target_start, target_end) = match target {
+        Element::Primitive(Primitive::DateTime(ID::DATE, ts)) => (*ts, ts + DAY_MILLIS),
+        Element::Primitive(Primitive::DateTime(_, ts)) => (*ts, ts + 1),
+        _ => return Ok(false)
+    };
+    let (prefix, start, end) = parse_date(value)?;
+    let eq = start <= target_start && target_end <= end;
+    Ok(match prefix {
+        Prefix::Eq => eq,
+        Prefix::Ne => !eq,
+        Prefix::Gt => target_end > end,
+        Prefix::Lt => target_start < start,
+        Prefix::Ge => eq || target_end > end,
+        Prefix::Le => eq || target_start < start,
+        Prefix::Sa => target_start >= end,
+        Prefix::Eb => target_end <= start,
+        Prefix::Ap => target_start < end && start < target_end
+    })
+}
+
+// Parses a date parameter value into its prefix and the range (start inclusive,
+// end exclusive, in milliseconds) given by the precision of the value.
+pub(super) fn parse_date(value: &str) -> Result<(Prefix, i64, i64)> {
+    let invalid = || Error::Search(format!("invalid date '{value}'"));
+    let (prefix, date) = match value.get(..2) {
+        Some("eq") => (Prefix::Eq, &value[2..]),
+        Some("ne") => (Prefix::Ne, &value[2..]),
+        Some("gt") => (Prefix::Gt, &value[2..]),
+        Some("lt") => (Prefix::Lt, &value[2..]),
+        Some("ge") => (Prefix::Ge, &value[2..]),
+        Some("le") => (Prefix::Le, &value[2..]),
+        Some("sa") => (Prefix::Sa, &value[2..]),
+        Some("eb") => (Prefix::Eb, &value[2..]),
+        Some("ap") => (Prefix::Ap, &value[2..]),
+        _ => (Prefix::Eq, value)
+    };
+    let start = Fhir_DateTime::from_string(date).map_err(|_| invalid())?.timestamp_millis();
+    let date_time = DateTime::<Utc>::from_timestamp_millis(start).ok_or_else(invalid)?;
+    let end = match date.len() {
+        4 => date_time.checked_add_months(Months::new(12)).ok_or_else(invalid)?.timestamp_millis(),
+        7 => date_time.checked_add_months(Months::new(1)).ok_or_else(invalid)?.timestamp_millis(),
+        10 => start + DAY_MILLIS,
+        _ if date.contains('.') => start + 1,
+        _ => start + 1000
+    };
+    Ok((prefix, start, end))
+}
+
+// Splits a parameter value at unescaped commas.
+fn split_values(value: &str) -> Vec<String> {
+    let mut values = Vec::new();
+    let mut current = String::new();
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some(next) => current.push(next),
+                None => current.push(c)
+            },
+            ',' => values.push(std::mem::take(&mut current)),
+            c => current.push(c)
+        }
+    }
+    values.push(current);
+    values
+}
+
+// Decodes percent encoding and '+' of a url query component.
+fn decode_component(s: &str) -> Result<String> {
+    let bytes = s.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'%' => {
+                let hex = bytes.get(i+1..i+3)
+                    .and_then(|h| std::str::from_utf8(h).ok())
+                    .and_then(|h| u8::from_str_radix(h, 16).ok())
+                    .ok_or_else(|| Error::Search(format!("invalid percent encoding in '{s}'")))?;
+                decoded.push(hex);
+                i += 3;
+            },
+            b'+' => {
+                decoded.push(b' ');
+                i += 1;
+            },
+            b => {
+                decoded.push(b);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8(decoded).map_err(|_| Error::Conversion("bytes".to_string(), "utf8".to_string()))
+}
+
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::parser::json::from_json;
+
+    fn patient() -> Element {
+        let data = br#"{"resourceType": "patient", "active": true, "gender": "male", "birthDate": "1974-12-25",
+            "name": [{"family": "Chalmers", "given": ["Peter", "James"]}, {"text": "Jim Chalmers", "prefix": ["Mr"], "suffix": ["MSc"]}],
+            "identifier": [{"system": "urn:oid:1.2.36.146.595.217.0.1", "value": "12345"}],
+            "telecom": [{"system": "phone", "value": "(03) 5555 6473"}],
+            "address": [{"city": "PleasantVille"}]}"#;
+        element::decode(&from_json(data).unwrap()).unwrap()
+    }
+
+    fn matches(query: &str) -> bool {
+        Query::parse(&ResourceId::Patient, query).unwrap().matches(&patient()).unwrap()
+    }
+
+    #[test]
+    fn query_strings() {
+        assert!(matches("name=chal"));
+        assert!(matches("name=peter"));
+        assert!(matches("family=Chalmers&given=Jam"));
+        assert!(!matches("family=halmers"));
+        assert!(matches("family:contains=halm"));
+        assert!(matches("family:exact=Chalmers"));
+        assert!(!matches("family:exact=chalmers"));
+        assert!(matches("address-city=pleasant"));
+        assert!(matches("given=Jim,James"));
+        assert!(matches("name=x,Pet"));
+        assert!(matches("name=jim") && !matches("given=jim"));
+        assert!(matches("name=mr") && matches("name=msc"));
+        assert!(!matches("given=Peter+James"));
+    }
+
+    #[test]
+    fn query_tokens() {
+        assert!(matches("gender=male"));
+        assert!(!matches("gender=female"));
+        assert!(matches("active=true"));
+        assert!(matches("identifier=12345"));
+        assert!(matches("identifier=urn:oid:1.2.36.146.595.217.0.1|12345"));
+        assert!(matches("identifier=urn%3Aoid%3A1.2.36.146.595.217.0.1%7C12345"));
+        assert!(matches("identifier=urn:oid:1.2.36.146.595.217.0.1|"));
+        assert!(!matches("identifier=|12345"));
+        assert!(!matches("identifier=other|12345"));
+        assert!(matches("telecom=(03) 5555 6473"));
+    }
+
+    #[test]
+    fn query_dates() {
+        assert!(matches("birthdate=1974-12-25"));
+        assert!(matches("birthdate=1974"));
+        assert!(matches("birthdate=eq1974-12"));
+        assert!(!matches("birthdate=1974-12-24"));
+        assert!(matches("birthdate=ne1974-12-24"));
+        assert!(matches("birthdate=ge1974-12-25"));
+        assert!(matches("birthdate=gt1974-12-24"));
+        assert!(!matches("birthdate=gt1974-12-25"));
+        assert!(matches("birthdate=lt1975"));
+        assert!(!matches("birthdate=lt1974-12-25"));
+        assert!(matches("birthdate=le1974-12-25"));
+        assert!(matches("birthdate=sa1974-12-24"));
+        assert!(matches("birthdate=eb1975-01-01"));
+        assert!(matches("birthdate=ge1970&birthdate=lt1980"));
+    }
+
+    #[test]
+    fn query_references() {
+        let reference = |reference: &str| Element::Object(vec![
+            (ID::Reference, Element::Primitive(Primitive::String(reference.to_string())))
+        ]);
+        let patient = Element::Object(vec![
+            (ID::GeneralPractitioner, Element::List(vec![reference("Practitioner/123")])),
+            (ID::ManagingOrganization, reference("http://example.org/fhir/Organization/1"))
+        ]);
+        let matches = |query: &str| Query::parse(&ResourceId::Patient, query).unwrap().matches(&patient).unwrap();
+        assert!(matches("general-practitioner=Practitioner/123"));
+        assert!(matches("general-practitioner=123"));
+        assert!(!matches("general-practitioner=23"));
+        assert!(matches("organization=Organization/1"));
+        assert!(matches("organization=http://example.org/fhir/Organization/1"));
+        assert!(!matches("organization=Organization/2"));
+    }
+
+    #[test]
+    fn query_missing_and_errors() {
+        assert!(matches("gender:missing=false"));
+        assert!(!matches("gender:missing=true"));
+        assert!(matches("address-city:missing=false&telecom:missing=false"));
+        let empty = element::decode(&from_json(br#"{"resourceType": "patient"}"#).unwrap()).unwrap();
+        assert!(Query::parse(&ResourceId::Patient, "birthdate:missing=true").unwrap().matches(&empty).unwrap());
+
+        assert_eq!(Query::parse(&ResourceId::Patient, "_count=10").unwrap().count, Some(10));
+        assert!(Query::parse(&ResourceId::Patient, "unknown=1").is_err());
+        assert!(Query::parse(&ResourceId::Patient, "family:below=x").is_err());
+        assert!(Query::parse(&ResourceId::Patient, "gender:exact=male").is_err());
+        assert!(Query::parse(&ResourceId::Patient, "gender:missing=maybe").is_err());
+        assert!(Query::parse(&ResourceId::Patient, "birthdate=ge19a4").is_err());
+        assert!(Query::parse(&ResourceId::Patient, "_count=x").is_err());
+    }
+}
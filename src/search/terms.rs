@@ -0,0 +1,224 @@
+use uuid::Uuid;
+use crate::datatypes::id::ID;
+use crate::error::Result;
+use crate::parser::element::{self, Element, Primitive};
+use crate::resourcetypes::ResourceId;
+use crate::store::header::Head;
+use crate::store::resourcewriter::ResourceHeader;
+use crate::store::secondary::{Key, KEY_LEN};
+use super::params::{get_params, ParamType};
+use super::query::{parse_date, Criterion, Modifier, Prefix, DAY_MILLIS};
+
+const VALUE_OFFSET: usize = 4;
+const VALUE_LEN: usize = 28;
+const ID_OFFSET: usize = VALUE_OFFSET + VALUE_LEN;
+
+/// Kinds of values a search parameter is indexed by.
+const VALUE: u8 = 0;
+const SYSTEM: u8 = 1;
+const DATE: u8 = 2;
+
+
+/// Returns the keys of the secondary index for a stored resource (including its
+/// [`ResourceHeader`]). Deleted resources and resources without search parameters
+/// have no keys.
+///
+/// Layout of a key:
+///
+/// |Num Bytes |2             |1           |1    |28    |16          |
+/// |----------|--------------|------------|-----|------|------------|
+/// |          |resource type |param index |kind |value |resource id |
+///
+/// Strings are lowercased, all values are cut after 28 bytes. The index only
+/// narrows down the resources, found ones still have to be matched against the query.
+/// Dates are indexed by the start of the period they cover.
+pub fn keys(stored: &[u8]) -> Result<Vec<Key>> {
+    let header = ResourceHeader::from_store(stored);
+    let typ = header.typ();
+    let params = get_params(&typ);
+    if header.is_deleted() || params.is_empty() {
+        return Ok(Vec::new())
+    }
+    let id = header.id();
+    let resource = element::decode_stored(stored)?;
+    let mut keys = Vec::new();
+    for (index, param) in params.iter().enumerate() {
+        let targets = param.paths.iter().flat_map(|path| resource.select_elements(path));
+        for target in targets {
+            match (param.typ, target) {
+                (ParamType::String, Element::Primitive(primitive)) => {
+                    let value = primitive.to_json_string()?.to_lowercase();
+                    keys.push(key(&typ, index, VALUE, value.as_bytes(), &id));
+                },
+                (ParamType::Token, Element::Primitive(primitive)) => {
+                    keys.push(key(&typ, index, VALUE, primitive.to_json_string()?.as_bytes(), &id));
+                },
+                (ParamType::Token, Element::Object(_)) => {
+                    for code in target.select(&[ID::Value]).into_iter().chain(target.select(&[ID::Code])) {
+                        keys.push(key(&typ, index, VALUE, code.to_json_string()?.as_bytes(), &id));
+                    }
+                    for system in target.select(&[ID::System]) {
+                        keys.push(key(&typ, index, SYSTEM, system.to_json_string()?.as_bytes(), &id));
+                    }
+                },
+                (ParamType::Date, Element::Primitive(Primitive::DateTime(_, ts))) => {
+                    keys.push(key(&typ, index, DATE, &date_bytes(*ts), &id));
+                },
+                (ParamType::Reference, Element::Object(_)) => {
+                    for reference in target.select(&[ID::Reference]) {
+                        let reference = reference.to_json_string()?;
+                        keys.push(key(&typ, index, VALUE, reference_id(&reference).as_bytes(), &id));
+                    }
+                },
+                _ => ()
+            }
+        }
+    }
+    keys.sort();
+    keys.dedup();
+    Ok(keys)
+}
+
+/// Returns the ranges of keys (start inclusive, end exclusive) containing all resources
+/// that can match 'criterion', or [`None`] if the criterion can not be answered by the index.
+pub fn ranges(typ: &ResourceId, criterion: &Criterion) -> Result<Option<Vec<(Key, Key)>>> {
+    let index = match get_params(typ).iter().position(|param| std::ptr::eq(param, criterion.param)) {
+        Some(index) => index,
+        None => return Ok(None)
+    };
+    let mut ranges = Vec::with_capacity(criterion.values.len());
+    for value in criterion.values.iter() {
+        let range = match (criterion.param.typ, criterion.modifier) {
+            (_, Some(Modifier::Missing)) | (_, Some(Modifier::Contains)) => None,
+            (ParamType::String, _) => {
+                let value = value.to_lowercase();
+                Some((key_range(typ, index, VALUE, value.as_bytes(), 0), key_range(typ, index, VALUE, value.as_bytes(), 0xFF)))
+            },
+            (ParamType::Token, _) => match value.split_once('|') {
+                Some((system, "")) => Some(exact_range(typ, index, SYSTEM, system.as_bytes())),
+                Some((_, code)) => Some(exact_range(typ, index, VALUE, code.as_bytes())),
+                None => Some(exact_range(typ, index, VALUE, value.as_bytes()))
+            },
+            (ParamType::Reference, _) => Some(exact_range(typ, index, VALUE, reference_id(value).as_bytes())),
+            (ParamType::Date, _) => {
+                let (prefix, start, end) = parse_date(value)?;
+                // a date covers a whole day, so it may start up to a day before the period
+                let period = match prefix {
+                    Prefix::Ne => None,
+                    Prefix::Eq => Some((start, end)),
+                    Prefix::Ap => Some((start - DAY_MILLIS, end)),
+                    Prefix::Ge => Some((start - DAY_MILLIS, i64::MAX)),
+                    Prefix::Gt => Some((end - DAY_MILLIS, i64::MAX)),
+                    Prefix::Sa => Some((end, i64::MAX)),
+                    Prefix::Le => Some((i64::MIN, end)),
+                    Prefix::Lt | Prefix::Eb => Some((i64::MIN, start))
+                };
+                period.map(|(from, to)| (
+                    key_range(typ, index, DATE, &date_bytes(from), 0),
+                    key_range(typ, index, DATE, &date_bytes(to), 0)
+                ))
+            }
+        };
+        match range {
+            Some(range) => ranges.push(range),
+            None => return Ok(None)
+        }
+    }
+    Ok(Some(ranges))
+}
+
+/// Returns the id of the resource a key points to.
+pub fn key_id(key: &Key) -> Uuid {
+    let mut id = [0u8; 16];
+    id.copy_from_slice(&key[ID_OFFSET..]);
+    Uuid::from_bytes(id)
+}
+
+
+fn key(typ: &ResourceId, index: usize, kind: u8, value: &[u8], id: &Uuid) -> Key {
+    let mut key = [0u8; KEY_LEN];
+    key[..2].copy_from_slice(&(typ.clone() as u16).to_be_bytes());
+    key[2] = index as u8;
+    key[3] = kind;
+    let len = value.len().min(VALUE_LEN);
+    key[VALUE_OFFSET..VALUE_OFFSET+len].copy_from_slice(&value[..len]);
+    key[ID_OFFSET..].copy_from_slice(id.as_bytes());
+    key
+}
+
+// Bound of a range over all values starting with 'value' (and padded with 'fill').
+fn key_range(typ: &ResourceId, index: usize, kind: u8, value: &[u8], fill: u8) -> Key {
+    let mut key = key(typ, index, kind, value, &Uuid::nil());
+    let len = value.len().min(VALUE_LEN);
+    key[VALUE_OFFSET+len..].fill(fill);
+    key
+}
+
+// Range over all resources with exactly 'value' (as far as it fits into the key).
+fn exact_range(typ: &ResourceId, index: usize, kind: u8, value: &[u8]) -> (Key, Key) {
+    let from = key(typ, index, kind, value, &Uuid::nil());
+    let mut to = from;
+    to[ID_OFFSET..].fill(0xFF);
+    (from, to)
+}
+
+// Timestamps are stored with the sign bit flipped, so that they sort like the bytes.
+fn date_bytes(ts: i64) -> [u8; 8] {
+    ((ts as u64) ^ (1 << 63)).to_be_bytes()
+}
+
+// The last segment of a reference, e.g. '123' of 'Practitioner/123'.
+fn reference_id(reference: &str) -> &str {
+    reference.rsplit('/').next().unwrap_or(reference)
+}
+
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::parser::json::resource_from_json_with_id;
+    use crate::search::query::Query;
+
+    fn stored(id: &Uuid) -> Vec<u8> {
+        let data = br#"{"resourceType": "patient", "gender": "male", "birthDate": "1974-12-25",
+            "name": [{"family": "Chalmers", "given": ["Peter"]}],
+            "identifier": [{"system": "urn:oid:1.2.36", "value": "12345"}]}"#;
+        resource_from_json_with_id(&data[..], id).unwrap()
+    }
+
+    fn found(keys: &[Key], query: &str) -> bool {
+        let query = Query::parse(&ResourceId::Patient, query).unwrap();
+        query.criteria.iter().all(|criterion| {
+            ranges(&ResourceId::Patient, criterion).unwrap().unwrap().iter()
+                .any(|(from, to)| keys.iter().any(|key| key >= from && key < to))
+        })
+    }
+
+    #[test]
+    fn terms_keys_ranges() {
+        let id = Uuid::new_v4();
+        let keys = keys(&stored(&id)).unwrap();
+        // name (family, given), family, given, identifier (value, system), birthdate, gender
+        assert_eq!(keys.len(), 8);
+        assert!(keys.iter().all(|key| key_id(key) == id));
+
+        assert!(found(&keys, "family=chal"));
+        assert!(found(&keys, "name=PET"));
+        assert!(!found(&keys, "family=peter"));
+        assert!(found(&keys, "identifier=12345"));
+        assert!(found(&keys, "identifier=urn:oid:1.2.36|"));
+        assert!(!found(&keys, "identifier=1234"));
+        assert!(found(&keys, "gender=female,male"));
+        assert!(found(&keys, "birthdate=1974"));
+        assert!(found(&keys, "birthdate=gt1974-12-25"));
+        assert!(found(&keys, "birthdate=lt1975"));
+        assert!(!found(&keys, "birthdate=1975"));
+        assert!(!found(&keys, "birthdate=lt1974"));
+
+        let query = Query::parse(&ResourceId::Patient, "family:contains=alm").unwrap();
+        assert!(ranges(&ResourceId::Patient, &query.criteria[0]).unwrap().is_none());
+        let query = Query::parse(&ResourceId::Patient, "birthdate=ne1974").unwrap();
+        assert!(ranges(&ResourceId::Patient, &query.criteria[0]).unwrap().is_none());
+    }
+}
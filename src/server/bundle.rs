@@ -0,0 +1,53 @@
+use crate::error::Result;
+use crate::parser::datetime::Fhir_DateTime;
+use crate::store::history::Version;
+use crate::store::resourcewriter::ResourceHeader;
+use super::rest::resource_name;
+
+
+/// Builds a Bundle of type 'history' from versions (latest first), as returned
+/// by the history interactions.
+pub fn history(base: &str, versions: &[Version]) -> Result<String> {
+    let mut entries = Vec::with_capacity(versions.len());
+    for version in versions.iter() {
+        let header = &version.header;
+        let typ = resource_name(&header.typ()).unwrap_or_default();
+        let id = header.id();
+        let (method, url, status) = match (&version.json, header.version_id()) {
+            (None, _) => ("DELETE", format!("{typ}/{id}"), "204"),
+            (Some(_), 1) => ("POST", typ.to_string(), "201"),
+            (Some(_), _) => ("PUT", format!("{typ}/{id}"), "200")
+        };
+        let last_modified = Fhir_DateTime::from_timestamp_millis(header.last_updated())?.to_datetime_string();
+        let mut entry = format!(r#"{{"fullUrl":"{base}/{typ}/{id}""#);
+        if let Some(json) = &version.json {
+            entry.push_str(&format!(r#","resource":{json}"#));
+        }
+        entry.push_str(&format!(
+            r#","request":{{"method":"{method}","url":"{url}"}},"response":{{"status":"{status}","etag":"W/\"{}\"","lastModified":"{last_modified}"}}}}"#,
+            header.version_id()
+        ));
+        entries.push(entry);
+    }
+    Ok(bundle("history", entries.len(), &entries))
+}
+
+/// Builds a Bundle of type 'searchset' from the resources found by a search. 'total'
+/// is the number of all matches, which may be more than the resources given.
+pub fn searchset(base: &str, resources: &[(ResourceHeader, String)], total: usize) -> String {
+    let entries: Vec<String> = resources.iter()
+        .map(|(header, json)| format!(
+            r#"{{"fullUrl":"{base}/{}/{}","resource":{json},"search":{{"mode":"match"}}}}"#,
+            resource_name(&header.typ()).unwrap_or_default(), header.id()
+        ))
+        .collect();
+    bundle("searchset", total, &entries)
+}
+
+// Wraps the entries in a Bundle of type 'typ'.
+fn bundle(typ: &str, total: usize, entries: &[String]) -> String {
+    format!(
+        r#"{{"resourceType":"Bundle","type":"{typ}","total":{total},"entry":[{}]}}"#,
+        entries.join(",")
+    )
+}
@@ -0,0 +1,3 @@
+pub mod rest;
+pub mod bundle;
+pub mod outcome;
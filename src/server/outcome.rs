@@ -0,0 +1,158 @@
+use crate::error::Error;
+
+
+/// Severity of an [`Issue`], as in the FHIR value set 'issue-severity'.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Fatal,
+    Error,
+    Warning,
+    Information
+}
+
+impl Severity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Severity::Fatal => "fatal",
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Information => "information"
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Issue {
+    pub severity: Severity,
+    /// Code of the FHIR value set 'issue-type', e.g. 'structure' or 'not-found'.
+    pub code: &'static str,
+    pub diagnostics: String,
+    /// FHIRPath of the element the issue is about, e.g. 'Patient.name[1].given[0]'.
+    pub expression: Option<String>
+}
+
+/// An OperationOutcome resource, together with the HTTP status it is sent with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OperationOutcome {
+    pub status: u16,
+    pub issues: Vec<Issue>
+}
+
+impl OperationOutcome {
+    /// An outcome with a single issue, which is fatal for server errors (status 500 and above).
+    pub fn new(status: u16, code: &'static str, diagnostics: &str) -> Self {
+        let severity = if status >= 500 { Severity::Fatal } else { Severity::Error };
+        Self {
+            status,
+            issues: vec![Issue { severity, code, diagnostics: diagnostics.to_string(), expression: None }]
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        let issues: Vec<String> = self.issues.iter()
+            .map(|issue| {
+                let mut json = format!(
+                    r#"{{"severity":"{}","code":"{}","diagnostics":"{}""#,
+                    issue.severity.as_str(), issue.code, escape(&issue.diagnostics)
+                );
+                if let Some(expression) = &issue.expression {
+                    json.push_str(&format!(r#","expression":["{}"]"#, escape(expression)));
+                }
+                json.push('}');
+                json
+            })
+            .collect();
+        format!(r#"{{"resourceType":"OperationOutcome","issue":[{}]}}"#, issues.join(","))
+    }
+}
+
+impl From<&Error> for OperationOutcome {
+    /// Maps the categories of [`Error`] to issue codes and HTTP status:
+    ///
+    /// |Error                          |Code          |Status |
+    /// |-------------------------------|--------------|-------|
+    /// |PARSING, unknown keys          |structure     |400    |
+    /// |CONVERSION, FHIR_DATETIME      |value         |400    |
+    /// |SEARCH                         |invalid       |400    |
+    /// |unknown resource types         |not-supported |400    |
+    /// |resource too large             |too-costly    |413    |
+    /// |no free frame in the pager     |transient     |503    |
+    /// |no free page in the store      |exception     |507    |
+    /// |MEMORY, IO, broken stored data |exception     |500    |
+    ///
+    /// Errors of the json parser carry the path of the element as expression.
+    fn from(err: &Error) -> Self {
+        let (code, status) = match err {
+            Error::Json(position, inner) => {
+                let mut outcome = OperationOutcome::from(inner.as_ref());
+                for issue in outcome.issues.iter_mut() {
+                    issue.diagnostics = err.to_string();
+                    issue.expression = Some(position.path.clone());
+                }
+                return outcome
+            },
+            Error::Expected(_, _) | Error::UnknownSyntaxToken(_) | Error::UnknownKeyInJson(_)
+                | Error::UnknownExpect | Error::EOF | Error::MaxDepth(_) => ("structure", 400),
+            Error::Conversion(_, _) | Error::IdMaxLen | Error::TimeStampOverflow
+                | Error::TimeStampParsingError => ("value", 400),
+            Error::Search(_) => ("invalid", 400),
+            Error::UnknownResourceStr(_) | Error::UnknownResourceId(_) => ("not-supported", 400),
+            Error::Custom(_) => ("processing", 400),
+            Error::StoreUnitMaxLen => ("too-costly", 413),
+            Error::NoFreeFrame => ("transient", 503),
+            Error::StoreFull => ("exception", 507),
+            Error::UnknownStoreId(_) | Error::MemoryAllocation | Error::LayoutSetting | Error::BufferOverflow
+                | Error::BufferUnderflow | Error::SegmentationFault | Error::Io(_) => ("exception", 500)
+        };
+        OperationOutcome::new(status, code, &err.to_string())
+    }
+}
+
+fn escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c)
+        }
+    }
+    escaped
+}
+
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::parser::json::from_json;
+
+    #[test]
+    fn outcome_from_error() {
+        let err = from_json(br#"{"resourceType": "Patient", "name": [{"given": ["Jim", 5]}]}"#).unwrap_err();
+        let outcome = OperationOutcome::from(&err);
+        assert_eq!(outcome.status, 400);
+        assert_eq!(outcome.issues.len(), 1);
+        assert_eq!(outcome.issues[0].severity, Severity::Error);
+        assert_eq!(outcome.issues[0].code, "structure");
+        assert_eq!(outcome.issues[0].expression.as_deref(), Some("Patient.name[0].given[1]"));
+        assert_eq!(
+            outcome.to_json(),
+            format!(
+                r#"{{"resourceType":"OperationOutcome","issue":[{{"severity":"error","code":"structure","diagnostics":"{}","expression":["Patient.name[0].given[1]"]}}]}}"#,
+                escape(&err.to_string())
+            )
+        );
+
+        let outcome = OperationOutcome::from(&Error::Conversion("u64".to_string(), "i32".to_string()));
+        assert_eq!((outcome.status, outcome.issues[0].code), (400, "value"));
+        assert_eq!(outcome.issues[0].expression, None);
+        let outcome = OperationOutcome::from(&Error::Io("disk full".to_string()));
+        assert_eq!((outcome.status, outcome.issues[0].code, outcome.issues[0].severity), (500, "exception", Severity::Fatal));
+        assert_eq!(OperationOutcome::from(&Error::StoreUnitMaxLen).status, 413);
+        assert_eq!(OperationOutcome::from(&Error::NoFreeFrame).status, 503);
+        assert_eq!(OperationOutcome::from(&Error::StoreFull).status, 507);
+        assert_eq!(OperationOutcome::from(&Error::Search("bad".to_string())).issues[0].code, "invalid");
+    }
+}
@@ -0,0 +1,514 @@
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::Arc;
+use std::thread;
+use chrono::{DateTime, Utc};
+use tiny_http::{Header, Method, Response, Server};
+use crate::error::{Result, Error};
+use crate::resourcetypes::ResourceId;
+use crate::search::query::Query;
+use crate::store::resourcewriter::ResourceHeader;
+use crate::store::shared::SharedStore;
+use super::bundle;
+use super::outcome::OperationOutcome;
+
+
+pub const FHIR_JSON: &str = "application/fhir+json; charset=utf-8";
+
+/// Resource types served, with the name used in urls.
+const RESOURCE_TYPES: [(&str, ResourceId); 12] = [
+    ("Patient", ResourceId::Patient),
+    ("Observation", ResourceId::Observation),
+    ("Condition", ResourceId::Condition),
+    ("Encounter", ResourceId::Encounter),
+    ("Procedure", ResourceId::Procedure),
+    ("AllergyIntolerance", ResourceId::AllergyIntolerance),
+    ("Practitioner", ResourceId::Practitioner),
+    ("PractitionerRole", ResourceId::PractitionerRole),
+    ("Organization", ResourceId::Organization),
+    ("Location", ResourceId::Location),
+    ("HealthcareService", ResourceId::HealthcareService),
+    ("Endpoint", ResourceId::Endpoint)
+];
+
+/// Returns the name of a served resource type, as used in urls.
+pub fn resource_name(typ: &ResourceId) -> Option<&'static str> {
+    RESOURCE_TYPES.iter().find(|(_, t)| t == typ).map(|(name, _)| *name)
+}
+
+
+/// Response to a request. Kept apart from [`tiny_http::Response`], so the handling
+/// of requests can be tested without a socket.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reply {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: String
+}
+
+impl Reply {
+    fn new(status: u16) -> Self {
+        Self {
+            status,
+            headers: Vec::new(),
+            body: String::new()
+        }
+    }
+
+    // A resource with its version headers.
+    fn resource(status: u16, header: &ResourceHeader, json: String) -> Self {
+        let mut reply = Reply::new(status);
+        reply.headers.push(("Content-Type".to_string(), FHIR_JSON.to_string()));
+        reply.headers.push(("ETag".to_string(), format!("W/\"{}\"", header.version_id())));
+        reply.headers.push(("Last-Modified".to_string(), http_date(header.last_updated())));
+        reply.body = json;
+        reply
+    }
+
+    /// An OperationOutcome with a single issue.
+    pub fn outcome(status: u16, code: &'static str, diagnostics: &str) -> Self {
+        Reply::from(OperationOutcome::new(status, code, diagnostics))
+    }
+
+    /// Returns the value of the header 'name'.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.iter()
+            .find(|(field, _)| field.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn bundle(json: String) -> Self {
+        let mut reply = Reply::new(200);
+        reply.headers.push(("Content-Type".to_string(), FHIR_JSON.to_string()));
+        reply.body = json;
+        reply
+    }
+
+    fn into_response(self) -> Response<std::io::Cursor<Vec<u8>>> {
+        let mut response = Response::from_data(self.body).with_status_code(self.status);
+        for (field, value) in self.headers.iter() {
+            if let Ok(header) = Header::from_bytes(field.as_bytes(), value.as_bytes()) {
+                response = response.with_header(header);
+            }
+        }
+        response
+    }
+}
+
+
+impl From<OperationOutcome> for Reply {
+    fn from(outcome: OperationOutcome) -> Self {
+        let mut reply = Reply::new(outcome.status);
+        reply.headers.push(("Content-Type".to_string(), FHIR_JSON.to_string()));
+        reply.body = outcome.to_json();
+        reply
+    }
+}
+
+
+/// The FHIR RESTful API on top of a [`SharedStore`], serving
+/// `POST [base]/[type]`, `GET`, `PUT` and `DELETE [base]/[type]/[id]`, 
+/// `GET [base]/[type]/[id]/_history/[vid]` as well as the history of an instance
+/// (`[base]/[type]/[id]/_history`), a type (`[base]/[type]/_history`) and the
+/// whole system (`[base]/_history`).
+#[derive(Debug, Clone)]
+pub struct FhirServer {
+    store: SharedStore,
+    base: String
+}
+
+impl FhirServer {
+    /// 'base' is the url the server is reachable at, it is used for the `Location` header.
+    pub fn new(store: SharedStore, base: &str) -> Self {
+        Self {
+            store,
+            base: base.trim_end_matches('/').to_string()
+        }
+    }
+
+    /// Listens on 'addr' and handles the requests with 'threads' worker threads.
+    /// Only returns if the server can not be started.
+    pub fn run(self, addr: &str, threads: usize) -> Result<()> {
+        let server = Arc::new(Server::http(addr).map_err(|err| Error::Io(err.to_string()))?);
+        println!("INFO: Serving FHIR at '{}' on '{addr}'.", self.base);
+        let workers: Vec<_> = (0..threads.max(1)).map(|_| {
+            let server = server.clone();
+            let fhir = self.clone();
+            thread::spawn(move || fhir.serve(&server))
+        }).collect();
+        for worker in workers {
+            let _ = worker.join();
+        }
+        Ok(())
+    }
+
+    fn serve(&self, server: &Server) {
+        loop {
+            let mut request = match server.recv() {
+                Ok(request) => request,
+                Err(err) => {
+                    println!("ERROR: receiving request failed: {err}");
+                    continue;
+                }
+            };
+            let mut body = Vec::new();
+            let reply = match request.as_reader().read_to_end(&mut body) {
+                Ok(_) => self.handle(request.method(), request.url(), &body),
+                Err(err) => Reply::outcome(400, "invalid", &err.to_string())
+            };
+            if let Err(err) = request.respond(reply.into_response()) {
+                println!("ERROR: responding failed: {err}");
+            }
+        }
+    }
+
+    /// Handles a single request. 'url' is the path relative to the base, as sent by the client.
+    pub fn handle(&self, method: &Method, url: &str, body: &[u8]) -> Reply {
+        match catch_unwind(AssertUnwindSafe(|| self.route(method, url, body))) {
+            Ok(Ok(reply)) => reply,
+            Ok(Err(err)) => error_reply(&err),
+            Err(_) => Reply::outcome(500, "exception", "the request could not be processed")
+        }
+    }
+
+    fn route(&self, method: &Method, url: &str, body: &[u8]) -> Result<Reply> {
+        let path = url.split('?').next().unwrap_or_default();
+        let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+        if let (Method::Get, ["_history"]) = (method, &segments[..]) {
+            return Ok(Reply::bundle(bundle::history(&self.base, &self.store.system_history()?)?))
+        }
+        let (typ, resource_id) = match segments.first() {
+            Some(name) => match RESOURCE_TYPES.iter().find(|(n, _)| n == name) {
+                Some((name, resource_id)) => (*name, resource_id),
+                None => return Ok(Reply::outcome(404, "not-supported", &format!("unknown resource type '{name}'")))
+            },
+            None => return Ok(Reply::outcome(404, "not-supported", "no resource type given"))
+        };
+        match (method, &segments[1..]) {
+            (Method::Post, []) => self.create(typ, resource_id, body),
+            (Method::Get, []) => self.search(resource_id, url.split_once('?').map(|(_, q)| q).unwrap_or_default()),
+            (Method::Get, ["_history"]) => {
+                let versions = self.store.type_history(resource_id.clone())?;
+                Ok(Reply::bundle(bundle::history(&self.base, &versions)?))
+            },
+            (Method::Get, [id]) => self.read(resource_id, id),
+            (Method::Get, [id, "_history"]) => self.history(resource_id, id),
+            (Method::Get, [id, "_history", vid]) => self.vread(resource_id, id, vid),
+            (Method::Put, [id]) => self.update(typ, resource_id, id, body),
+            (Method::Delete, [id]) => self.delete(resource_id, id),
+            (_, []) | (_, [_]) => Ok(Reply::outcome(405, "not-supported", &format!("{method} is not supported on '{path}'"))),
+            _ => Ok(Reply::outcome(404, "not-found", &format!("unknown path '{path}'")))
+        }
+    }
+
+    fn create(&self, typ: &str, resource_id: &ResourceId, body: &[u8]) -> Result<Reply> {
+        let mut store = self.store.writer()?;
+        let mut tx = store.begin();
+        let id = tx.create(body)?.to_string();
+        if tx.get_resource_type(&id)?.as_ref() != Some(resource_id) {
+            return Ok(wrong_type(typ))
+        }
+        tx.commit()?;
+        let (header, json) = store.get_resource(&id)?.ok_or(Error::SegmentationFault)?;
+        let mut reply = Reply::resource(201, &header, json);
+        reply.headers.push(("Location".to_string(), format!("{}/{typ}/{id}/_history/{}", self.base, header.version_id())));
+        Ok(reply)
+    }
+
+    fn search(&self, typ: &ResourceId, query: &str) -> Result<Reply> {
+        let query = Query::parse(typ, query)?;
+        let (total, found) = self.store.search(typ.clone(), &query)?;
+        Ok(Reply::bundle(bundle::searchset(&self.base, &found, total)))
+    }
+
+    fn read(&self, typ: &ResourceId, id: &str) -> Result<Reply> {
+        match self.store.get_resource(id) {
+            Ok(Some((header, json))) if &header.typ() == typ => Ok(Reply::resource(200, &header, json)),
+            Ok(Some(_)) => Ok(not_found(id)),
+            Ok(None) => match self.store.history(id)?.first() {
+                Some(latest) if &latest.header.typ() == typ => Ok(gone(id, latest.header.version_id())),
+                Some(_) => Ok(not_found(id)),
+                None => Ok(not_found(id))
+            },
+            Err(Error::Conversion(_, _)) => Ok(not_found(id)),
+            Err(err) => Err(err)
+        }
+    }
+
+    fn vread(&self, typ: &ResourceId, id: &str, vid: &str) -> Result<Reply> {
+        let version = match vid.parse::<u32>() {
+            Ok(vid) => self.store.vread(id, vid),
+            Err(_) => return Ok(not_found(id))
+        };
+        match version {
+            Ok(Some(version)) if &version.header.typ() == typ => match version.json {
+                Some(json) => Ok(Reply::resource(200, &version.header, json)),
+                None => Ok(gone(id, version.header.version_id()))
+            },
+            Ok(_) | Err(Error::Conversion(_, _)) => Ok(not_found(id)),
+            Err(err) => Err(err)
+        }
+    }
+
+    fn history(&self, typ: &ResourceId, id: &str) -> Result<Reply> {
+        match self.store.history(id) {
+            Ok(versions) if versions.first().is_some_and(|latest| &latest.header.typ() == typ) => Ok(Reply::bundle(bundle::history(&self.base, &versions)?)),
+            Ok(_) | Err(Error::Conversion(_, _)) => Ok(not_found(id)),
+            Err(err) => Err(err)
+        }
+    }
+
+    fn update(&self, name: &str, typ: &ResourceId, id: &str, body: &[u8]) -> Result<Reply> {
+        let mut store = self.store.writer()?;
+        let current = store.get_resource(id)?;
+        if current.as_ref().is_some_and(|(header, _)| &header.typ() != typ) {
+            return Ok(Reply::outcome(409, "conflict", &format!("resource '{id}' is not a {name}")))
+        }
+        let created = current.is_none();
+        let mut tx = store.begin();
+        tx.update(id, body)?;
+        if tx.get_resource_type(id)?.as_ref() != Some(typ) {
+            return Ok(wrong_type(name))
+        }
+        tx.commit()?;
+        let (header, json) = store.get_resource(id)?.ok_or(Error::SegmentationFault)?;
+        let status = if created { 201 } else { 200 };
+        Ok(Reply::resource(status, &header, json))
+    }
+
+    fn delete(&self, typ: &ResourceId, id: &str) -> Result<Reply> {
+        let mut store = self.store.writer()?;
+        match store.get_resource(id) {
+            Ok(Some((header, _))) if &header.typ() != typ => return Ok(not_found(id)),
+            Err(Error::Conversion(_, _)) => return Ok(not_found(id)),
+            Ok(_) => (),
+            Err(err) => return Err(err)
+        }
+        match store.delete_resource(id) {
+            Ok(true) => Ok(Reply::new(204)),
+            Ok(false) | Err(Error::Conversion(_, _)) => Ok(not_found(id)),
+            Err(err) => Err(err)
+        }
+    }
+}
+
+
+fn not_found(id: &str) -> Reply {
+    Reply::outcome(404, "not-found", &format!("resource '{id}' not found"))
+}
+
+fn gone(id: &str, version_id: u32) -> Reply {
+    let mut reply = Reply::outcome(410, "deleted", &format!("resource '{id}' was deleted"));
+    reply.headers.push(("ETag".to_string(), format!("W/\"{version_id}\"")));
+    reply
+}
+
+// The body of a create or update has to be of the type given in the url.
+fn wrong_type(typ: &str) -> Reply {
+    Reply::outcome(400, "invalid", &format!("resourceType of the body is not '{typ}'"))
+}
+
+fn error_reply(err: &Error) -> Reply {
+    Reply::from(OperationOutcome::from(err))
+}
+
+// Formats a timestamp in milliseconds as HTTP date, e.g. 'Sat, 10 Jun 2023 10:00:00 GMT'.
+fn http_date(millis: i64) -> String {
+    match DateTime::<Utc>::from_timestamp_millis(millis) {
+        Some(date) => date.format("%a, %d %b %Y %H:%M:%S GMT").to_string(),
+        None => String::new()
+    }
+}
+
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::store::store::Store;
+    use crate::store::test::TempStore;
+
+    fn temp_server(name: &str) -> (TempStore, FhirServer) {
+        let path = TempStore::new(name);
+        let store = Store::open_path(&path).unwrap().into_shared().unwrap();
+        (path, FhirServer::new(store, "http://localhost:8080/fhir/"))
+    }
+
+    #[test]
+    fn rest_create_read() {
+        let (_path, server) = temp_server("rest_create");
+        let created = server.handle(&Method::Post, "/Patient", br#"{"resourceType": "patient", "active": true}"#);
+        assert_eq!(created.status, 201);
+        assert_eq!(created.header("Content-Type"), Some(FHIR_JSON));
+        assert_eq!(created.header("ETag"), Some("W/\"1\""));
+        assert!(created.header("Last-Modified").unwrap().ends_with(" GMT"));
+        let location = created.header("Location").unwrap();
+        assert!(location.starts_with("http://localhost:8080/fhir/Patient/"));
+        assert!(location.ends_with("/_history/1"));
+        let id = location.split('/').nth(5).unwrap().to_string();
+        assert!(created.body.contains(&format!(r#""id":"{id}""#)));
+
+        let read = server.handle(&Method::Get, &format!("/Patient/{id}"), b"");
+        assert_eq!(read.status, 200);
+        assert_eq!(read.body, created.body);
+        assert_eq!(read.header("ETag"), Some("W/\"1\""));
+
+        assert_eq!(server.handle(&Method::Get, &format!("/Patient/{}", uuid::Uuid::new_v4()), b"").status, 404);
+        assert_eq!(server.handle(&Method::Get, "/Patient/not-a-uuid", b"").status, 404);
+        assert_eq!(server.handle(&Method::Get, &format!("/Observation/{id}"), b"").status, 404);
+        assert_eq!(server.handle(&Method::Patch, &format!("/Patient/{id}"), b"").status, 405);
+        // broken json must neither take down the server nor the store
+        let outcome = server.handle(&Method::Post, "/Patient", b"{\"active\": tru");
+        assert_eq!(outcome.status, 400);
+        assert!(outcome.body.starts_with(r#"{"resourceType":"OperationOutcome""#));
+        assert!(outcome.body.contains("line 1, column 12"));
+        assert!(outcome.body.contains(r#""code":"structure""#));
+        assert!(outcome.body.contains(r#""expression":["Resource.active"]"#));
+        let outcome = server.handle(&Method::Post, "/Patient", br#"{"active": true}"#);
+        assert_eq!(outcome.status, 400);
+        assert!(outcome.body.contains(r#""code":"structure""#));
+        assert!(outcome.body.contains("expected 'resourceType'"));
+        assert!(outcome.body.contains(r#""expression":["Resource"]"#));
+        drop(server);
+    }
+
+    #[test]
+    fn rest_resource_types() {
+        let (_path, server) = temp_server("rest_types");
+        let created = server.handle(&Method::Post, "/Observation", br#"{"resourceType": "Observation", "status": "final", "code": {"text": "heart rate"}, "valueQuantity": {"value": 72, "unit": "beats/minute"}}"#);
+        assert_eq!(created.status, 201);
+        assert!(created.header("Location").unwrap().starts_with("http://localhost:8080/fhir/Observation/"));
+        assert!(created.body.ends_with(r#""valueQuantity":{"value":72,"unit":"beats/minute"}}"#));
+        let id = created.body.split(r#""id":""#).nth(1).unwrap().split('"').next().unwrap().to_string();
+        assert_eq!(server.handle(&Method::Get, &format!("/Observation/{id}"), b"").status, 200);
+        assert_eq!(server.handle(&Method::Get, &format!("/Patient/{id}"), b"").status, 404);
+        assert_eq!(server.handle(&Method::Get, &format!("/Patient/{id}/_history"), b"").status, 404);
+        assert_eq!(server.handle(&Method::Delete, &format!("/Patient/{id}"), b"").status, 404);
+
+        // the body has to match the type of the url
+        let wrong = server.handle(&Method::Post, "/Condition", br#"{"resourceType": "Procedure", "status": "completed"}"#);
+        assert_eq!(wrong.status, 400);
+        assert_eq!(server.handle(&Method::Put, &format!("/Observation/{id}"), br#"{"resourceType": "Patient"}"#).status, 400);
+        let conflict = server.handle(&Method::Put, &format!("/Patient/{id}"), br#"{"resourceType": "Patient"}"#);
+        assert_eq!(conflict.status, 409);
+        assert!(conflict.body.contains(&format!("resource '{id}' is not a Patient")));
+        assert_eq!(server.handle(&Method::Get, "/Condition/_history", b"").body.matches("fullUrl").count(), 0);
+        assert_eq!(server.handle(&Method::Put, &format!("/Observation/{id}"), br#"{"resourceType": "Observation", "status": "amended"}"#).status, 200);
+        assert_eq!(server.handle(&Method::Delete, &format!("/Observation/{id}"), b"").status, 204);
+        assert_eq!(server.handle(&Method::Get, &format!("/Observation/{id}"), b"").status, 410);
+        drop(server);
+    }
+
+    #[test]
+    fn rest_referenced_resources() {
+        let (_path, server) = temp_server("rest_references");
+        let organization = server.handle(&Method::Post, "/Organization", br#"{"resourceType": "Organization", "name": "Health Level Seven International"}"#);
+        assert_eq!(organization.status, 201);
+        let location = organization.header("Location").unwrap();
+        let reference = location.split_once("fhir/").unwrap().1.split("/_history").next().unwrap().to_string();
+        let patient = format!(r#"{{"resourceType": "Patient", "managingOrganization": {{"reference": "{reference}"}}}}"#);
+        assert_eq!(server.handle(&Method::Post, "/Patient", patient.as_bytes()).status, 201);
+
+        let found = server.handle(&Method::Get, &format!("/Patient?organization={reference}"), b"");
+        assert!(found.body.contains(r#""total":1"#));
+        let read = server.handle(&Method::Get, &format!("/{reference}"), b"");
+        assert_eq!(read.status, 200);
+        assert!(read.body.ends_with(r#""name":"Health Level Seven International"}"#));
+        drop(server);
+    }
+
+    #[test]
+    fn rest_update_delete() {
+        let (_path, server) = temp_server("rest_update");
+        let id = uuid::Uuid::new_v4().to_string();
+        let created = server.handle(&Method::Put, &format!("/Patient/{id}"), br#"{"resourceType": "patient", "active": true}"#);
+        assert_eq!(created.status, 201);
+        let updated = server.handle(&Method::Put, &format!("/Patient/{id}"), br#"{"resourceType": "patient", "active": false}"#);
+        assert_eq!(updated.status, 200);
+        assert_eq!(updated.header("ETag"), Some("W/\"2\""));
+        assert!(updated.body.contains(r#""versionId":"2""#));
+        assert!(updated.body.ends_with(r#""active":false}"#));
+        // the id of the body has to be the one of the url
+        let same = format!(r#"{{"resourceType": "Patient", "id": "{id}", "active": true}}"#);
+        assert_eq!(server.handle(&Method::Put, &format!("/Patient/{id}"), same.as_bytes()).status, 200);
+        let other = format!(r#"{{"resourceType": "Patient", "id": "{}", "active": true}}"#, uuid::Uuid::new_v4());
+        assert_eq!(server.handle(&Method::Put, &format!("/Patient/{id}"), other.as_bytes()).status, 400);
+        assert!(server.handle(&Method::Get, &format!("/Patient/{id}"), b"").body.contains(r#""versionId":"3""#));
+
+        assert_eq!(server.handle(&Method::Delete, &format!("/Patient/{id}"), b"").status, 204);
+        assert_eq!(server.handle(&Method::Get, &format!("/Patient/{id}"), b"").status, 410);
+        assert_eq!(server.handle(&Method::Delete, &format!("/Patient/{id}"), b"").status, 404);
+        assert_eq!(server.handle(&Method::Delete, &format!("/Patient/{}", uuid::Uuid::new_v4()), b"").status, 404);
+        drop(server);
+    }
+
+    #[test]
+    fn rest_history() {
+        let (_path, server) = temp_server("rest_history");
+        let id = uuid::Uuid::new_v4().to_string();
+        server.handle(&Method::Put, &format!("/Patient/{id}"), br#"{"resourceType": "patient", "active": true}"#);
+        server.handle(&Method::Put, &format!("/Patient/{id}"), br#"{"resourceType": "patient", "active": false}"#);
+        server.handle(&Method::Delete, &format!("/Patient/{id}"), b"");
+        let other = server.handle(&Method::Post, "/Patient", br#"{"resourceType": "patient", "gender": "male"}"#);
+        assert_eq!(other.status, 201);
+
+        let first = server.handle(&Method::Get, &format!("/Patient/{id}/_history/1"), b"");
+        assert_eq!(first.status, 200);
+        assert_eq!(first.header("ETag"), Some("W/\"1\""));
+        assert!(first.body.ends_with(r#""active":true}"#));
+        assert_eq!(server.handle(&Method::Get, &format!("/Patient/{id}/_history/3"), b"").status, 410);
+        assert_eq!(server.handle(&Method::Get, &format!("/Patient/{id}/_history/4"), b"").status, 404);
+        assert_eq!(server.handle(&Method::Get, &format!("/Patient/{id}/_history/x"), b"").status, 404);
+
+        let history = server.handle(&Method::Get, &format!("/Patient/{id}/_history"), b"");
+        assert_eq!(history.status, 200);
+        assert!(history.body.starts_with(r#"{"resourceType":"Bundle","type":"history","total":3,"#));
+        let delete = history.body.find(r#""method":"DELETE""#).unwrap();
+        let put = history.body.find(r#""method":"PUT""#).unwrap();
+        let post = history.body.find(r#""method":"POST""#).unwrap();
+        assert!(delete < put && put < post);
+        assert_eq!(server.handle(&Method::Get, &format!("/Patient/{}/_history", uuid::Uuid::new_v4()), b"").status, 404);
+
+        let typ = server.handle(&Method::Get, "/Patient/_history", b"");
+        assert!(typ.body.contains(r#""total":4"#));
+        let system = server.handle(&Method::Get, "/_history", b"");
+        assert_eq!(system.status, 200);
+        assert!(system.body.contains(r#""total":4"#));
+
+        // the resource can be recreated, its versions continue
+        let recreated = server.handle(&Method::Put, &format!("/Patient/{id}"), br#"{"resourceType": "patient"}"#);
+        assert_eq!(recreated.status, 201);
+        assert_eq!(recreated.header("ETag"), Some("W/\"4\""));
+        drop(server);
+    }
+
+    #[test]
+    fn rest_search() {
+        let (_path, server) = temp_server("rest_search");
+        server.handle(&Method::Post, "/Patient", br#"{"resourceType": "patient", "gender": "male", "birthDate": "1974-12-25", "name": [{"family": "Chalmers", "given": ["Peter"]}]}"#);
+        server.handle(&Method::Post, "/Patient", br#"{"resourceType": "patient", "gender": "female", "birthDate": "1980-02-01", "name": [{"family": "Chalmers", "given": ["Mary"]}]}"#);
+        let deleted = server.handle(&Method::Post, "/Patient", br#"{"resourceType": "patient", "name": [{"family": "Chalmers"}]}"#);
+        let id = deleted.body.split(r#""id":""#).nth(1).unwrap().split('"').next().unwrap().to_string();
+        assert_eq!(server.handle(&Method::Delete, &format!("/Patient/{id}"), b"").status, 204);
+
+        let all = server.handle(&Method::Get, "/Patient?family=chalmers", b"");
+        assert_eq!(all.status, 200);
+        assert!(all.body.starts_with(r#"{"resourceType":"Bundle","type":"searchset","total":2,"#));
+        assert!(all.body.contains(r#""search":{"mode":"match"}"#));
+        let male = server.handle(&Method::Get, "/Patient?family=chalmers&gender=male&birthdate=lt1975", b"");
+        assert!(male.body.contains(r#""total":1"#) && male.body.contains("Peter"));
+        let none = server.handle(&Method::Get, "/Patient?given:exact=peter", b"");
+        assert!(none.body.contains(r#""total":0,"entry":[]"#));
+        let counted = server.handle(&Method::Get, "/Patient?_count=1", b"");
+        assert!(counted.body.contains(r#""total":2"#) && counted.body.matches("fullUrl").count() == 1);
+
+        // name also covers the text, prefix and suffix of a HumanName
+        server.handle(&Method::Post, "/Patient", br#"{"resourceType": "patient", "name": [{"text": "Dr. Jim Smith", "prefix": ["Dr."]}]}"#);
+        let text = server.handle(&Method::Get, "/Patient?name=dr. jim", b"");
+        assert!(text.body.contains(r#""total":1"#) && text.body.contains("Dr. Jim Smith"));
+
+        let invalid = server.handle(&Method::Get, "/Patient?unknown=1", b"");
+        assert_eq!(invalid.status, 400);
+        assert!(invalid.body.contains("unknown search parameter"));
+        drop(server);
+    }
+}
@@ -0,0 +1,383 @@
+use crate::error::{Result, Error};
+use super::index::Pages;
+use super::store::PAGE_SIZE;
+use std::marker::PhantomData;
+use uuid::Uuid;
+
+
+const LEAF: u8 = 1;
+const INTERNAL: u8 = 2;
+const NODE_HEADER_LEN: usize = 8;
+const CHILD_LEN: usize = 2;
+
+
+/// Fixed length encoding of the keys and values of a B+tree page.
+pub trait Codec: Sized {
+    const LEN: usize;
+    fn read(data: &[u8]) -> Self;
+    fn write(&self, data: &mut [u8]);
+}
+
+impl Codec for Uuid {
+    const LEN: usize = 16;
+    fn read(data: &[u8]) -> Self {
+        let mut key = [0u8; 16];
+        key.copy_from_slice(&data[..16]);
+        Uuid::from_bytes(key)
+    }
+    fn write(&self, data: &mut [u8]) {
+        data[..16].copy_from_slice(self.as_bytes());
+    }
+}
+
+impl<const N: usize> Codec for [u8; N] {
+    const LEN: usize = N;
+    fn read(data: &[u8]) -> Self {
+        let mut key = [0u8; N];
+        key.copy_from_slice(&data[..N]);
+        key
+    }
+    fn write(&self, data: &mut [u8]) {
+        data[..N].copy_from_slice(self);
+    }
+}
+
+/// For trees that only hold keys.
+impl Codec for () {
+    const LEN: usize = 0;
+    fn read(_: &[u8]) -> Self {}
+    fn write(&self, _: &mut [u8]) {}
+}
+
+#[derive(Debug)]
+enum Node<K, V> {
+    Leaf {
+        entries: Vec<(K, V)>,
+        next: u16
+    },
+    Internal {
+        keys: Vec<K>,
+        children: Vec<u16>
+    }
+}
+
+
+impl<K: Codec + Ord + Copy, V: Codec + PartialEq + Copy> Node<K, V> {
+    const LEAF_ENTRY_LEN: usize = K::LEN + V::LEN;
+    const INTERNAL_ENTRY_LEN: usize = K::LEN + CHILD_LEN;
+    const LEAF_CAP: usize = (PAGE_SIZE - NODE_HEADER_LEN) / Self::LEAF_ENTRY_LEN;
+    const INTERNAL_CAP: usize = (PAGE_SIZE - NODE_HEADER_LEN - CHILD_LEN) / Self::INTERNAL_ENTRY_LEN;
+
+    /// Layout Leaf:
+    /// |Num Bytes |1    |2        |2         |3        |entry len * num keys |
+    /// |----------|-----|---------|----------|---------|---------------------|
+    /// |          |LEAF |num keys |next leaf |reserved |key, value           |
+    ///
+    /// Layout Internal:
+    /// |Num Bytes |1        |2        |4        |2            |(key len + 2) * num keys |
+    /// |----------|---------|---------|---------|-------------|-------------------------|
+    /// |          |INTERNAL |num keys |reserved |first child  |key, child               |
+    fn from_page(page: &[u8]) -> Result<Self> {
+        let num = u16::from_be_bytes([page[1], page[2]]) as usize;
+        match page[0] {
+            LEAF => {
+                if num > Self::LEAF_CAP {
+                    return Err(Error::BufferOverflow)
+                }
+                let next = u16::from_be_bytes([page[3], page[4]]);
+                let mut entries = Vec::with_capacity(num + 1);
+                let mut offset = NODE_HEADER_LEN;
+                for _ in 0..num {
+                    entries.push((K::read(&page[offset..]), V::read(&page[offset+K::LEN..])));
+                    offset += Self::LEAF_ENTRY_LEN;
+                }
+                Ok(Node::Leaf { entries, next })
+            },
+            INTERNAL => {
+                if num > Self::INTERNAL_CAP {
+                    return Err(Error::BufferOverflow)
+                }
+                let mut keys = Vec::with_capacity(num + 1);
+                let mut children = Vec::with_capacity(num + 2);
+                let mut offset = NODE_HEADER_LEN;
+                children.push(u16::from_be_bytes([page[offset], page[offset+1]]));
+                offset += CHILD_LEN;
+                for _ in 0..num {
+                    keys.push(K::read(&page[offset..]));
+                    children.push(u16::from_be_bytes([page[offset+K::LEN], page[offset+K::LEN+1]]));
+                    offset += Self::INTERNAL_ENTRY_LEN;
+                }
+                Ok(Node::Internal { keys, children })
+            },
+            typ => Err(Error::UnknownStoreId(typ as u16))
+        }
+    }
+
+    fn to_page(&self) -> Vec<u8> {
+        let mut page = vec![0u8; PAGE_SIZE];
+        match self {
+            Node::Leaf { entries, next } => {
+                page[0] = LEAF;
+                page[1..3].copy_from_slice(&(entries.len() as u16).to_be_bytes());
+                page[3..5].copy_from_slice(&next.to_be_bytes());
+                let mut offset = NODE_HEADER_LEN;
+                for (key, value) in entries.iter() {
+                    key.write(&mut page[offset..]);
+                    value.write(&mut page[offset+K::LEN..]);
+                    offset += Self::LEAF_ENTRY_LEN;
+                }
+            },
+            Node::Internal { keys, children } => {
+                page[0] = INTERNAL;
+                page[1..3].copy_from_slice(&(keys.len() as u16).to_be_bytes());
+                let mut offset = NODE_HEADER_LEN;
+                page[offset..offset+CHILD_LEN].copy_from_slice(&children[0].to_be_bytes());
+                offset += CHILD_LEN;
+                for (key, child) in keys.iter().zip(children.iter().skip(1)) {
+                    key.write(&mut page[offset..]);
+                    page[offset+K::LEN..offset+K::LEN+CHILD_LEN].copy_from_slice(&child.to_be_bytes());
+                    offset += Self::INTERNAL_ENTRY_LEN;
+                }
+            }
+        }
+        page
+    }
+}
+
+// Index of the child that has to contain 'key'.
+fn child_index<K: Ord>(keys: &[K], key: &K) -> usize {
+    keys.partition_point(|k| k <= key)
+}
+
+fn read_node<P: Pages, K: Codec + Ord + Copy, V: Codec + PartialEq + Copy>(pages: &mut P, num: u16) -> Result<Node<K, V>> {
+    let page = pages.read_page(num)?;
+    Node::from_page(&page)
+}
+
+fn write_node<P: Pages, K: Codec + Ord + Copy, V: Codec + PartialEq + Copy>(pages: &mut P, num: u16, node: &Node<K, V>) -> Result<()> {
+    pages.write_page(num, &node.to_page())
+}
+
+
+/// A B+tree of fixed length keys and values in the pages of the store. The primary
+/// [`super::index`] maps ids to [`super::index::Location`]s, the [`super::secondary`]
+/// indexes only hold keys. Nodes are not merged on removal, so underfull leaves stay
+/// around until the tree is rebuilt.
+pub struct BTree<K, V> {
+    pub root: u16,
+    entry: PhantomData<(K, V)>
+}
+
+impl<K: Codec + Ord + Copy, V: Codec + PartialEq + Copy> BTree<K, V> {
+    /// The tree with its root at page 'root'.
+    pub fn at(root: u16) -> Self {
+        BTree { root, entry: PhantomData }
+    }
+
+    /// Creates an empty tree.
+    pub fn create<P: Pages>(pages: &mut P) -> Result<Self> {
+        let root = pages.allocate_page()?;
+        write_node::<P, K, V>(pages, root, &Node::Leaf { entries: Vec::new(), next: 0 })?;
+        Ok(Self::at(root))
+    }
+
+    // Returns the page of the leaf that has to contain 'key'.
+    fn find_leaf<P: Pages>(&self, pages: &mut P, key: &K) -> Result<u16> {
+        let mut num = self.root;
+        while let Node::Internal { keys, children } = read_node::<P, K, V>(pages, num)? {
+            num = children[child_index(&keys, key)];
+        }
+        Ok(num)
+    }
+
+    /// Returns the value of 'key'.
+    pub fn search<P: Pages>(&self, pages: &mut P, key: &K) -> Result<Option<V>> {
+        let num = self.find_leaf(pages, key)?;
+        match read_node::<P, K, V>(pages, num)? {
+            Node::Leaf { entries, .. } => Ok(entries.binary_search_by(|(k, _)| k.cmp(key)).ok().map(|i| entries[i].1)),
+            Node::Internal { .. } => Err(Error::Expected("LEAF".to_string(), "INTERNAL".to_string()))
+        }
+    }
+
+    /// Inserts (or replaces) 'key', the root changes if it was split.
+    pub fn insert<P: Pages>(&mut self, pages: &mut P, key: &K, value: V) -> Result<()> {
+        if let Some((separator, right)) = self.insert_into(pages, self.root, key, value)? {
+            let new_root = pages.allocate_page()?;
+            let node = Node::<K, V>::Internal { keys: vec![separator], children: vec![self.root, right] };
+            write_node(pages, new_root, &node)?;
+            self.root = new_root;
+        }
+        Ok(())
+    }
+
+    // Inserts into the subtree at 'num'. If the node had to be split, the separator key and
+    // the page of the new right sibling are returned.
+    fn insert_into<P: Pages>(&self, pages: &mut P, num: u16, key: &K, value: V) -> Result<Option<(K, u16)>> {
+        match read_node::<P, K, V>(pages, num)? {
+            Node::Leaf { mut entries, next } => {
+                match entries.binary_search_by(|(k, _)| k.cmp(key)) {
+                    Ok(i) if entries[i].1 == value => return Ok(None),
+                    Ok(i) => entries[i].1 = value,
+                    Err(i) => entries.insert(i, (*key, value))
+                }
+                if entries.len() <= Node::<K, V>::LEAF_CAP {
+                    write_node(pages, num, &Node::Leaf { entries, next })?;
+                    return Ok(None)
+                }
+                let right_entries = entries.split_off(entries.len() / 2);
+                let separator = right_entries[0].0;
+                let right = pages.allocate_page()?;
+                write_node(pages, right, &Node::Leaf { entries: right_entries, next })?;
+                write_node(pages, num, &Node::Leaf { entries, next: right })?;
+                Ok(Some((separator, right)))
+            },
+            Node::Internal { mut keys, mut children } => {
+                let i = child_index(&keys, key);
+                if let Some((separator, right)) = self.insert_into(pages, children[i], key, value)? {
+                    keys.insert(i, separator);
+                    children.insert(i + 1, right);
+                } else {
+                    return Ok(None)
+                }
+                if keys.len() <= Node::<K, V>::INTERNAL_CAP {
+                    write_node(pages, num, &Node::<K, V>::Internal { keys, children })?;
+                    return Ok(None)
+                }
+                let mid = keys.len() / 2;
+                let right_keys = keys.split_off(mid + 1);
+                let separator = keys.pop().unwrap();
+                let right_children = children.split_off(mid + 1);
+                let right = pages.allocate_page()?;
+                write_node(pages, right, &Node::<K, V>::Internal { keys: right_keys, children: right_children })?;
+                write_node(pages, num, &Node::<K, V>::Internal { keys, children })?;
+                Ok(Some((separator, right)))
+            }
+        }
+    }
+
+    /// Removes 'key' from its leaf. Returns if the key was present.
+    pub fn remove<P: Pages>(&self, pages: &mut P, key: &K) -> Result<bool> {
+        let num = self.find_leaf(pages, key)?;
+        match read_node::<P, K, V>(pages, num)? {
+            Node::Leaf { mut entries, next } => match entries.binary_search_by(|(k, _)| k.cmp(key)) {
+                Ok(i) => {
+                    entries.remove(i);
+                    write_node(pages, num, &Node::Leaf { entries, next })?;
+                    Ok(true)
+                },
+                Err(_) => Ok(false)
+            },
+            Node::Internal { .. } => Err(Error::Expected("LEAF".to_string(), "INTERNAL".to_string()))
+        }
+    }
+
+    /// Returns the entries from 'from' (inclusive) to 'to' (exclusive) in key order,
+    /// all of them if there are no bounds.
+    pub fn range<P: Pages>(&self, pages: &mut P, from: Option<&K>, to: Option<&K>) -> Result<Vec<(K, V)>> {
+        let mut num = match from {
+            Some(from) => self.find_leaf(pages, from)?,
+            None => {
+                let mut num = self.root;
+                while let Node::Internal { children, .. } = read_node::<P, K, V>(pages, num)? {
+                    num = children[0];
+                }
+                num
+            }
+        };
+        let mut result = Vec::new();
+        while num != 0 {
+            match read_node::<P, K, V>(pages, num)? {
+                Node::Leaf { entries, next } => {
+                    for entry in entries.into_iter().filter(|(key, _)| from.is_none_or(|from| key >= from)) {
+                        if to.is_some_and(|to| &entry.0 >= to) {
+                            return Ok(result)
+                        }
+                        result.push(entry);
+                    }
+                    num = next;
+                },
+                Node::Internal { .. } => return Err(Error::Expected("LEAF".to_string(), "INTERNAL".to_string()))
+            }
+        }
+        Ok(result)
+    }
+
+    /// Returns if the root is an internal node, i.e. the tree was split.
+    #[cfg(test)]
+    pub fn is_split<P: Pages>(&self, pages: &mut P) -> Result<bool> {
+        Ok(matches!(read_node::<P, K, V>(pages, self.root)?, Node::Internal { .. }))
+    }
+}
+
+
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+    use crate::store::index::Location;
+
+    /// [`Pages`] in memory, for the tests of the structures living in the pages.
+    #[derive(Default)]
+    pub struct MemPages {
+        pub pages: Vec<Vec<u8>>
+    }
+
+    impl Pages for MemPages {
+        fn read_page(&mut self, num: u16) -> Result<Vec<u8>> {
+            self.pages.get(num as usize).cloned().ok_or(Error::BufferUnderflow)
+        }
+        fn write_page(&mut self, num: u16, page: &[u8]) -> Result<()> {
+            self.pages[num as usize] = page.to_vec();
+            Ok(())
+        }
+        fn allocate_page(&mut self) -> Result<u16> {
+            // page 0 is never handed out, just like in the store
+            if self.pages.is_empty() {
+                self.pages.push(vec![0u8; PAGE_SIZE]);
+            }
+            self.pages.push(vec![0u8; PAGE_SIZE]);
+            Ok((self.pages.len() - 1) as u16)
+        }
+    }
+
+    #[test]
+    fn btree_node_page() {
+        let key = Uuid::new_v4();
+        let loc = Location { page: 3, offset: 72 };
+        let leaf = Node::Leaf { entries: vec![(key, loc)], next: 7 };
+        match Node::<Uuid, Location>::from_page(&leaf.to_page()).unwrap() {
+            Node::Leaf { entries, next } => {
+                assert_eq!(entries, vec![(key, loc)]);
+                assert_eq!(next, 7);
+            },
+            _ => panic!("expected leaf")
+        }
+        let internal = Node::<Uuid, Location>::Internal { keys: vec![key], children: vec![4, 5] };
+        match Node::<Uuid, Location>::from_page(&internal.to_page()).unwrap() {
+            Node::Internal { keys, children } => {
+                assert_eq!(keys, vec![key]);
+                assert_eq!(children, vec![4, 5]);
+            },
+            _ => panic!("expected internal")
+        }
+        assert!(Node::<Uuid, Location>::from_page(&[0u8; PAGE_SIZE]).is_err());
+
+        // a leaf of keys only is a plain list of keys
+        let page = Node::<[u8; 4], ()>::Leaf { entries: vec![([1, 2, 3, 4], ()), ([5, 6, 7, 8], ())], next: 0 }.to_page();
+        assert_eq!(&page[NODE_HEADER_LEN..NODE_HEADER_LEN + 9], &[1, 2, 3, 4, 5, 6, 7, 8, 0]);
+    }
+
+    #[test]
+    fn btree_range() {
+        let mut pages = MemPages::default();
+        let mut tree = BTree::<[u8; 2], ()>::create(&mut pages).unwrap();
+        for i in (0..5000u16).rev() {
+            tree.insert(&mut pages, &i.to_be_bytes(), ()).unwrap();
+        }
+        assert!(tree.is_split(&mut pages).unwrap());
+        let found = tree.range(&mut pages, Some(&100u16.to_be_bytes()), Some(&200u16.to_be_bytes())).unwrap();
+        assert_eq!(found, (100..200u16).map(|i| (i.to_be_bytes(), ())).collect::<Vec<_>>());
+        assert_eq!(tree.range(&mut pages, None, None).unwrap().len(), 5000);
+        assert!(tree.range(&mut pages, Some(&5000u16.to_be_bytes()), None).unwrap().is_empty());
+    }
+}
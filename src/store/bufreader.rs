@@ -1,93 +1,64 @@
-use crate::datatypes::id::{ID, get_expects};
-pub fn read_buffer(mut buf: &[u8]) -> Vec<u16> {
-    let len: [u8; 2] = buf[..2].try_into().unwrap();
-    buf = &buf[2..];
-    let mut result = Vec::<u16>::new();
-    result.push(u16::from_be_bytes(len));
-    let mut gp_list = 0;
-    let mut current = 0;
-    while buf.len() > 0 {
-        if gp_list > 0 && current == 0 {
-            let length: [u8; 2] = buf[..2].try_into().unwrap();
-            buf = &buf[2..];
-            let length = u16::from_be_bytes(length);
-            result.push(length);
-            //gp_list -= 2;
-            current = length;
-        }
+use crate::datatypes::id::ID;
+use crate::error::Result;
+use crate::parser::reader::Reader;
+use super::resourcewriter::{FORMAT_U16, FORMAT_VERSION};
 
+/// Returns all lengths and ids of a buffer (without [`super::resourcewriter::ResourceHeader`])
+/// in the order they are written, skipping the data. Meant for inspecting the binary format.
+pub fn read_buffer(buf: &[u8]) -> Result<Vec<usize>> {
+    read_buffer_format(buf, FORMAT_VERSION)
+}
 
+/// Same as [`read_buffer`] for a buffer written in 'format'. In [`FORMAT_U16`] the result
+/// also holds the [`crate::datatypes::id::ID_LEN`] in front of every key and general purpose id.
+pub fn read_buffer_format(buf: &[u8], format: u8) -> Result<Vec<usize>> {
+    let mut reader = Reader::new(buf, format)?;
+    let mut result = Vec::new();
+    let end = reader.read_resource_end()?;
+    result.push(end - reader.cursor);
+    read_object(&mut reader, format, end, &mut result)?;
+    Ok(result)
+}
 
-        let len: [u8; 2] = buf[..2].try_into().unwrap();
-        buf = &buf[2..];
-        if gp_list > 0 {
-            gp_list -= 2;
-        }
-        if current > 0 {
-            current -= 2;
-        }
-        let pos_id: [u8; 2] = buf[..2].try_into().unwrap();
-        let id = ID::try_from(u16::from_be_bytes(pos_id)).unwrap();
-        buf = &buf[2..];
-        if gp_list > 0 {
-            gp_list -= 2;
+fn read_object(reader: &mut Reader, format: u8, end: usize, result: &mut Vec<usize>) -> Result<()> {
+    while reader.cursor < end {
+        if format == FORMAT_U16 {
+            result.push(reader.read_num()?);
         }
-        if current > 0 {
-            current -= 2;
+        result.push(reader.read_id()? as usize);
+        let value_end = reader.read_end()?;
+        result.push(value_end - reader.cursor);
+        let mut id = reader.read_id()?;
+        result.push(id as usize);
+        if format == FORMAT_U16 && id == ID::BOOLEAN && value_end - reader.cursor > 1 {
+            id = reader.read_id()?;
+            result.push(id as usize);
         }
-        let l = u16::from_be_bytes(len);
-        result.push(l);
-        result.push(id as u16);
-        if let Some(expects) = get_expects(id.clone()) {
-            if expects.is_general_purpose() {
-                let gp_len: [u8; 2] = buf[..2].try_into().unwrap();
-                let le = u16::from_be_bytes(gp_len);
-                result.push(le);
-                buf = &buf[2..];
-                if gp_list > 0 {
-                    gp_list -= 2;
-                }
-                if current > 0 {
-                    current -= 2;
-                }
-                continue;
+        if id == ID::ELEMENT && value_end > reader.cursor {
+            // the chosen type of a choice element's ELEMENT, keys are no primitives
+            let cursor = reader.cursor;
+            match reader.read_id()? {
+                chosen if chosen.is_primitive() => result.push(chosen as usize),
+                _ => reader.cursor = cursor
             }
         }
-        if id.is_primitive_list() {
-            let mut temp_cur = 0;
-            let trgt = l-2-4;
-            if gp_list > 0 {
-                gp_list -= l-2;
-            }
-            if current > 0 {
-                current -= trgt;
-            }
-            while temp_cur < trgt {
-                let length: [u8; 2] = buf[..2].try_into().unwrap();
-                buf = &buf[2..];
-                let length = u16::from_be_bytes(length);
-                result.push(length);
-                buf = &buf[length as usize..];
-                temp_cur += 2 + length;
+        if id.is_general_purpose() {
+            read_object(reader, format, value_end, result)?;
+        } else if id.is_primitive_list() {
+            while reader.cursor < value_end {
+                let len = reader.read_num()?;
+                result.push(len);
+                reader.read_slice(len)?;
             }
         } else if id.is_gp_list() {
-            gp_list = u16::from_be_bytes(len);
-            let length: [u8; 2] = buf[..2].try_into().unwrap();
-            //gp_list -= 2;
-            buf = &buf[2..];
-            let length = u16::from_be_bytes(length);
-            result.push(length);
-            current = length;
-        } else if !id.is_key() {
-            buf = &buf[(l - 2 )as usize..];
-            if gp_list > 0 {
-                gp_list -= l;
-            }
-            if current > 0 {
-                current -= l;
+            while reader.cursor < value_end {
+                let item_end = reader.read_end()?;
+                result.push(item_end - reader.cursor);
+                read_object(reader, format, item_end, result)?;
             }
+        } else {
+            reader.cursor = value_end;
         }
-
     }
-    return result
+    Ok(())
 }
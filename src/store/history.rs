@@ -0,0 +1,111 @@
+use uuid::Uuid;
+use crate::error::{Result, Error};
+use crate::parser::serializer::resource_to_json;
+use crate::resourcetypes::ResourceId;
+use super::header::Head;
+use super::index::{self, Location, Pages};
+use super::overflow;
+use super::resourcewriter::{ResourceHeader, RESOURCE_HEADER_LEN};
+use super::store::PAGE_SIZE;
+
+
+/// A single version of a resource.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Version {
+    pub header: ResourceHeader,
+    /// The resource as json, [`None`] if the version marks its deletion.
+    pub json: Option<String>
+}
+
+impl Version {
+    fn from_stored(stored: &[u8]) -> Result<Self> {
+        let header = ResourceHeader::from_store(stored);
+        let json = match header.is_deleted() {
+            true => None,
+            false => Some(resource_to_json(stored)?)
+        };
+        Ok(Self { header, json })
+    }
+}
+
+
+/// Reads the stored resource at 'loc', including its [`ResourceHeader`]. Resources
+/// on overflow pages are read from there.
+pub fn read_at<P: Pages>(pages: &mut P, loc: Location) -> Result<Vec<u8>> {
+    let page = pages.read_page(loc.page)?;
+    let offset = loc.offset as usize;
+    if offset + RESOURCE_HEADER_LEN > PAGE_SIZE {
+        return Err(Error::SegmentationFault)
+    }
+    let header = ResourceHeader::from_store(&page[offset..offset+RESOURCE_HEADER_LEN]);
+    let len = header.resource_len() as usize;
+    if len < RESOURCE_HEADER_LEN || offset + len > PAGE_SIZE {
+        return Err(Error::SegmentationFault)
+    }
+    let stored = &page[offset..offset+len];
+    match overflow::referenced(stored) {
+        Some((first, len)) => overflow::read(pages, first, len),
+        None => Ok(stored.to_vec())
+    }
+}
+
+/// Returns all versions of the resource with the given id, the latest first.
+pub fn versions<P: Pages>(pages: &mut P, root: u16, id: &Uuid) -> Result<Vec<Version>> {
+    match index::search(pages, root, id)? {
+        Some(loc) => chain(pages, loc),
+        None => Ok(Vec::new())
+    }
+}
+
+/// Returns the version 'version_id' of the resource with the given id.
+pub fn version<P: Pages>(pages: &mut P, root: u16, id: &Uuid, version_id: u32) -> Result<Option<Version>> {
+    let mut next = index::search(pages, root, id)?;
+    while let Some(loc) = next {
+        let stored = read_at(pages, loc)?;
+        let header = ResourceHeader::from_store(&stored);
+        if header.version_id() == version_id {
+            return Ok(Some(Version::from_stored(&stored)?))
+        }
+        if header.version_id() < version_id {
+            break;
+        }
+        next = header.previous().map(|(page, offset)| Location { page, offset });
+    }
+    Ok(None)
+}
+
+/// Returns the versions of all resources of type 'typ' (or of all types for [`None`]),
+/// the latest first.
+pub fn all_versions<P: Pages>(pages: &mut P, root: u16, typ: Option<ResourceId>) -> Result<Vec<Version>> {
+    let mut result = Vec::new();
+    for (_, loc) in index::entries(pages, root)? {
+        let mut versions = chain(pages, loc)?;
+        if let (Some(typ), Some(latest)) = (&typ, versions.first()) {
+            if &latest.header.typ() != typ {
+                continue;
+            }
+        }
+        result.append(&mut versions);
+    }
+    result.sort_by_key(|v| std::cmp::Reverse(v.header.last_updated()));
+    Ok(result)
+}
+
+// Follows the links from the version at 'loc' to the first one.
+fn chain<P: Pages>(pages: &mut P, loc: Location) -> Result<Vec<Version>> {
+    let mut result: Vec<Version> = Vec::new();
+    let mut next = Some(loc);
+    while let Some(loc) = next {
+        let stored = read_at(pages, loc)?;
+        let version = Version::from_stored(&stored)?;
+        // versions only ever link to older ones, anything else would loop forever
+        if let Some(later) = result.last() {
+            if version.header.version_id() >= later.header.version_id() {
+                return Err(Error::SegmentationFault)
+            }
+        }
+        next = version.header.previous().map(|(page, offset)| Location { page, offset });
+        result.push(version);
+    }
+    Ok(result)
+}
@@ -0,0 +1,115 @@
+use crate::error::Result;
+use super::btree::{BTree, Codec};
+use uuid::Uuid;
+
+
+/// Access to the pages of the store, the index lives in the same file as the resources.
+pub trait Pages {
+    fn read_page(&mut self, num: u16) -> Result<Vec<u8>>;
+    fn write_page(&mut self, num: u16, page: &[u8]) -> Result<()>;
+    /// Returns the number of a new, zeroed page.
+    fn allocate_page(&mut self) -> Result<u16>;
+}
+
+/// Position of a stored resource (including its [`super::resourcewriter::ResourceHeader`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Location {
+    pub page: u16,
+    pub offset: u16
+}
+
+impl Codec for Location {
+    const LEN: usize = 4;
+    fn read(data: &[u8]) -> Self {
+        Location {
+            page: u16::from_be_bytes([data[0], data[1]]),
+            offset: u16::from_be_bytes([data[2], data[3]])
+        }
+    }
+    fn write(&self, data: &mut [u8]) {
+        data[0..2].copy_from_slice(&self.page.to_be_bytes());
+        data[2..4].copy_from_slice(&self.offset.to_be_bytes());
+    }
+}
+
+/// The index maps the id of a resource to the [`Location`] of its latest version.
+type Index = BTree<Uuid, Location>;
+
+
+/// Creates an empty index and returns the page of its root.
+pub fn create<P: Pages>(pages: &mut P) -> Result<u16> {
+    Ok(Index::create(pages)?.root)
+}
+
+/// Returns the [`Location`] of the resource with id 'key'.
+pub fn search<P: Pages>(pages: &mut P, root: u16, key: &Uuid) -> Result<Option<Location>> {
+    Index::at(root).search(pages, key)
+}
+
+/// Inserts (or replaces) 'key'. Returns the page of the root, which changes if the root was split.
+pub fn insert<P: Pages>(pages: &mut P, root: u16, key: &Uuid, loc: Location) -> Result<u16> {
+    let mut index = Index::at(root);
+    index.insert(pages, key, loc)?;
+    Ok(index.root)
+}
+
+/// Removes 'key' from its leaf. Nodes are not merged, so underfull leaves
+/// stay around until the index is rebuilt. Returns if the key was present.
+pub fn remove<P: Pages>(pages: &mut P, root: u16, key: &Uuid) -> Result<bool> {
+    Index::at(root).remove(pages, key)
+}
+
+/// Returns all entries in key order by walking the leaves.
+pub fn entries<P: Pages>(pages: &mut P, root: u16) -> Result<Vec<(Uuid, Location)>> {
+    Index::at(root).range(pages, None, None)
+}
+
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::store::btree::test::MemPages;
+
+    #[test]
+    fn index_location_codec() {
+        let loc = Location { page: 3, offset: 72 };
+        let mut data = [0u8; 4];
+        loc.write(&mut data);
+        assert_eq!(data, [0, 3, 0, 72]);
+        assert_eq!(Location::read(&data), loc);
+    }
+
+    #[test]
+    fn index_insert_search() {
+        let mut pages = MemPages::default();
+        let mut root = create(&mut pages).unwrap();
+        let keys: Vec<Uuid> = (0..20_000).map(|_| Uuid::new_v4()).collect();
+        for (i, key) in keys.iter().enumerate() {
+            root = insert(&mut pages, root, key, Location { page: i as u16, offset: 1 }).unwrap();
+        }
+        assert!(Index::at(root).is_split(&mut pages).unwrap());
+        for (i, key) in keys.iter().enumerate() {
+            assert_eq!(search(&mut pages, root, key).unwrap(), Some(Location { page: i as u16, offset: 1 }));
+        }
+        assert_eq!(search(&mut pages, root, &Uuid::new_v4()).unwrap(), None);
+
+        let all = entries(&mut pages, root).unwrap();
+        assert_eq!(all.len(), keys.len());
+        assert!(all.windows(2).all(|w| w[0].0 < w[1].0));
+    }
+
+    #[test]
+    fn index_replace_remove() {
+        let mut pages = MemPages::default();
+        let mut root = create(&mut pages).unwrap();
+        let key = Uuid::new_v4();
+        root = insert(&mut pages, root, &key, Location { page: 1, offset: 1 }).unwrap();
+        root = insert(&mut pages, root, &key, Location { page: 2, offset: 2 }).unwrap();
+        assert_eq!(search(&mut pages, root, &key).unwrap(), Some(Location { page: 2, offset: 2 }));
+        assert_eq!(entries(&mut pages, root).unwrap().len(), 1);
+        assert!(remove(&mut pages, root, &key).unwrap());
+        assert!(!remove(&mut pages, root, &key).unwrap());
+        assert_eq!(search(&mut pages, root, &key).unwrap(), None);
+    }
+}
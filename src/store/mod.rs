@@ -1,7 +1,63 @@
 pub mod resourcewriter;
 pub mod store;
 pub mod index;
+pub mod btree;
+pub mod secondary;
+pub mod overflow;
 pub mod header;
+pub mod page;
+pub mod pager;
+pub mod wal;
+pub mod transaction;
+pub mod shared;
+pub mod history;
 pub mod bufreader;
 
 
+
+
+
+#[cfg(test)]
+pub mod test {
+    use std::ops::Deref;
+    use std::path::{Path, PathBuf};
+    use uuid::Uuid;
+
+    /// Path of a store in the temp directory. The store and its write-ahead log are
+    /// removed on drop, also when the test fails.
+    pub struct TempStore {
+        path: PathBuf
+    }
+
+    impl TempStore {
+        pub fn new(name: &str) -> TempStore {
+            TempStore { path: std::env::temp_dir().join(format!("fhir_store_{name}_{}.db", Uuid::new_v4())) }
+        }
+
+        pub fn wal(&self) -> PathBuf {
+            let mut wal = self.path.clone().into_os_string();
+            wal.push("-wal");
+            PathBuf::from(wal)
+        }
+    }
+
+    impl Deref for TempStore {
+        type Target = Path;
+        fn deref(&self) -> &Path {
+            &self.path
+        }
+    }
+
+    impl AsRef<Path> for TempStore {
+        fn as_ref(&self) -> &Path {
+            &self.path
+        }
+    }
+
+    impl Drop for TempStore {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.path);
+            let _ = std::fs::remove_file(self.wal());
+        }
+    }
+}
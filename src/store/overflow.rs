@@ -0,0 +1,126 @@
+use crate::error::{Result, Error};
+use super::index::Pages;
+use super::resourcewriter::RESOURCE_HEADER_LEN;
+use super::store::PAGE_SIZE;
+
+
+const OVERFLOW_HEADER_LEN: usize = 4;
+/// Number of bytes of a resource an overflow page holds.
+const OVERFLOW_CAP: usize = PAGE_SIZE - OVERFLOW_HEADER_LEN;
+/// Length of the reference that is written to the data page in place of a resource
+/// stored on overflow pages.
+pub const REFERENCE_LEN: usize = RESOURCE_HEADER_LEN + 8;
+
+
+/// Resources that do not fit into a data page are written to a chain of overflow
+/// pages. Returns the first page of the chain.
+///
+/// Layout of an overflow page:
+/// |Num Bytes |2          |2          |PAGE_SIZE - 4  |
+/// |----------|-----------|-----------|---------------|
+/// |          |next page  |used bytes |data           |
+///
+/// The last page of the chain has 0 as next page.
+pub fn write<P: Pages>(pages: &mut P, resource: &[u8]) -> Result<u16> {
+    let chunks: Vec<&[u8]> = resource.chunks(OVERFLOW_CAP).collect();
+    let mut nums = Vec::with_capacity(chunks.len());
+    for _ in chunks.iter() {
+        nums.push(pages.allocate_page()?);
+    }
+    for (i, chunk) in chunks.iter().enumerate() {
+        let next = nums.get(i + 1).copied().unwrap_or(0);
+        let mut page = vec![0u8; PAGE_SIZE];
+        page[..2].copy_from_slice(&next.to_be_bytes());
+        page[2..4].copy_from_slice(&(chunk.len() as u16).to_be_bytes());
+        page[OVERFLOW_HEADER_LEN..OVERFLOW_HEADER_LEN+chunk.len()].copy_from_slice(chunk);
+        pages.write_page(nums[i], &page)?;
+    }
+    nums.first().copied().ok_or(Error::BufferUnderflow)
+}
+
+/// Reads the 'len' bytes of the resource stored on the chain starting at 'first'.
+pub fn read<P: Pages>(pages: &mut P, first: u16, len: usize) -> Result<Vec<u8>> {
+    let mut resource = Vec::with_capacity(len);
+    let mut num = first;
+    while resource.len() < len {
+        // the chain ended early or points back to the header page
+        if num == 0 {
+            return Err(Error::SegmentationFault)
+        }
+        let page = pages.read_page(num)?;
+        let used = u16::from_be_bytes([page[2], page[3]]) as usize;
+        if used == 0 || used > OVERFLOW_CAP || resource.len() + used > len {
+            return Err(Error::SegmentationFault)
+        }
+        resource.extend_from_slice(&page[OVERFLOW_HEADER_LEN..OVERFLOW_HEADER_LEN+used]);
+        num = u16::from_be_bytes([page[0], page[1]]);
+    }
+    Ok(resource)
+}
+
+/// Creates the reference to a resource written to the overflow pages starting at 'first'.
+/// It is the header of the resource followed by
+///
+/// |Num Bytes |2  |2          |4            |
+/// |----------|---|-----------|-------------|
+/// |          |0  |first page |resource len |
+///
+/// A stored resource always starts with the length of its keys, which covers the rest of
+/// the data after the header. Starting with 0 it can only be a single byte long, so it
+/// can not be mistaken for a reference.
+pub fn reference(resource: &[u8], first: u16) -> Result<Vec<u8>> {
+    let len = u32::try_from(resource.len()).map_err(|_| Error::StoreUnitMaxLen)?;
+    let mut stored = Vec::with_capacity(REFERENCE_LEN);
+    stored.extend_from_slice(&resource[..RESOURCE_HEADER_LEN]);
+    stored[..2].copy_from_slice(&(REFERENCE_LEN as u16).to_be_bytes());
+    stored.extend([0, 0]);
+    stored.extend(first.to_be_bytes());
+    stored.extend(len.to_be_bytes());
+    Ok(stored)
+}
+
+/// Returns the first overflow page and the length of the resource, if 'stored'
+/// is a reference created by [`reference`].
+pub fn referenced(stored: &[u8]) -> Option<(u16, usize)> {
+    if stored.len() != REFERENCE_LEN || stored[RESOURCE_HEADER_LEN..RESOURCE_HEADER_LEN+2] != [0, 0] {
+        return None
+    }
+    let data = &stored[RESOURCE_HEADER_LEN+2..];
+    let first = u16::from_be_bytes([data[0], data[1]]);
+    let len = u32::from_be_bytes([data[2], data[3], data[4], data[5]]);
+    Some((first, len as usize))
+}
+
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::resourcetypes::ResourceId;
+    use crate::store::header::Head;
+    use crate::store::resourcewriter::ResourceHeader;
+    use uuid::Uuid;
+    use crate::store::btree::test::MemPages;
+
+    #[test]
+    fn overflow_write_read() {
+        let mut pages = MemPages::default();
+        let mut resource = ResourceHeader::tombstone(ResourceId::Patient, Uuid::new_v4()).unwrap();
+        resource.extend((0..20_000u32).map(|i| (i % 251) as u8));
+        let first = write(&mut pages, &resource).unwrap();
+        assert_eq!(pages.pages.len(), 1 + resource.len().div_ceil(OVERFLOW_CAP));
+        assert_eq!(read(&mut pages, first, resource.len()).unwrap(), resource);
+        assert!(read(&mut pages, first, resource.len() + 1).is_err());
+
+        let stored = reference(&resource, first).unwrap();
+        assert_eq!(stored.len(), REFERENCE_LEN);
+        assert_eq!(referenced(&stored), Some((first, resource.len())));
+        let header = ResourceHeader::from_store(&stored);
+        assert_eq!(header.resource_len() as usize, REFERENCE_LEN);
+        assert!(!header.is_deleted());
+        // a resource with the same length is no reference
+        let mut other = stored.clone();
+        other[RESOURCE_HEADER_LEN..RESOURCE_HEADER_LEN+2].copy_from_slice(&6u16.to_be_bytes());
+        assert_eq!(referenced(&other), None);
+    }
+}
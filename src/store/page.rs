@@ -0,0 +1,70 @@
+use crate::error::Result;
+use super::header::{Head, PAGE_HEADER_LEN};
+
+
+/// Header of a page holding resources. The resources are written one after
+/// the other, starting right after the header.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PageHeader {
+    pub num_resources: u16,
+    pub free_offset: u16,
+}
+
+impl PageHeader {
+    pub fn new() -> Self {
+        Self {
+            num_resources: 0,
+            free_offset: PAGE_HEADER_LEN as u16
+        }
+    }
+
+    /// Returns the number of bytes still available in a page of 'page_size'.
+    pub fn free_space(&self, page_size: usize) -> usize {
+        page_size.saturating_sub(self.free_offset as usize)
+    }
+}
+
+impl Default for PageHeader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Head for PageHeader {
+    /// Layout:
+    /// |Num Bytes |2              |2           |PAGE_HEADER_LEN - 4 |
+    /// |----------|---------------|------------|--------------------|
+    /// |          |Num Resources  |Free Offset |reserved            |
+    fn to_store(&self) -> Result<Vec<u8>> {
+        let mut stored = vec![0u8; PAGE_HEADER_LEN];
+        stored[..2].copy_from_slice(&self.num_resources.to_be_bytes());
+        stored[2..4].copy_from_slice(&self.free_offset.to_be_bytes());
+        Ok(stored)
+    }
+
+    /// A zeroed (never written) page is read as an empty page.
+    fn from_store(data: &[u8]) -> Self {
+        let num_resources = u16::from_be_bytes([data[0], data[1]]);
+        let free_offset = u16::from_be_bytes([data[2], data[3]]);
+        Self {
+            num_resources,
+            free_offset: if free_offset == 0 { PAGE_HEADER_LEN as u16 } else { free_offset }
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn page_header_store() {
+        let header = PageHeader { num_resources: 3, free_offset: 1024 };
+        let stored = header.to_store().unwrap();
+        assert_eq!(stored.len(), PAGE_HEADER_LEN);
+        assert_eq!(PageHeader::from_store(&stored), header);
+        assert_eq!(PageHeader::from_store(&[0u8; PAGE_HEADER_LEN]), PageHeader::new());
+        assert_eq!(PageHeader::new().free_space(4096), 4096 - PAGE_HEADER_LEN);
+    }
+}
@@ -0,0 +1,377 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{Read, Write, Seek, SeekFrom};
+use crate::error::{Result, Error};
+use super::store::PAGE_SIZE;
+use super::wal::Wal;
+
+
+/// Handle of a pinned frame, only valid until it is unpinned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrameId(usize);
+
+#[derive(Debug)]
+struct Frame {
+    page: Option<u16>,
+    data: Vec<u8>,
+    pins: u32,
+    dirty: bool,
+    referenced: bool
+}
+
+/// Buffer pool between the store and its file. It holds a fixed number of frames,
+/// each caching one page. Frames are evicted by a clock, dirty frames never reach
+/// the main file before their pages went through the [`Wal`] (no-steal). They are
+/// written back by [`Pager::write_back_all`] or [`Pager::flush`] after [`Pager::commit`].
+/// If all unpinned frames are dirty, one of them is spilled: its page is appended to
+/// the [`Wal`] and read from there until the transaction ends.
+#[derive(Debug)]
+pub struct Pager {
+    file: File,
+    wal: Wal,
+    frames: Vec<Frame>,
+    table: HashMap<u16, usize>,
+    // pages spilled to the log, by the position of their image
+    spilled: HashMap<u16, u64>,
+    hand: usize
+}
+
+impl Pager {
+    pub fn new(file: File, wal: Wal, capacity: usize) -> Self {
+        assert!(capacity > 0, "buffer pool capacity cannot be '0'");
+        let frames = (0..capacity).map(|_| Frame {
+            page: None,
+            data: vec![0u8; PAGE_SIZE],
+            pins: 0,
+            dirty: false,
+            referenced: false
+        }).collect();
+        Self {
+            file,
+            wal,
+            frames,
+            table: HashMap::with_capacity(capacity),
+            spilled: HashMap::new(),
+            hand: 0
+        }
+    }
+
+    pub fn wal(&self) -> &Wal {
+        &self.wal
+    }
+
+    /// Returns the number of frames.
+    pub fn capacity(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Returns if page 'num' is currently held by a frame.
+    pub fn is_resident(&self, num: u16) -> bool {
+        self.table.contains_key(&num)
+    }
+
+    /// Pins the frame holding page 'num', reading the page from disk if it is not resident.
+    pub fn pin(&mut self, num: u16) -> Result<FrameId> {
+        self.fetch(num, true)
+    }
+
+    /// Releases a pin, the frame may be evicted once it is not pinned anymore.
+    pub fn unpin(&mut self, frame: FrameId) {
+        let frame = &mut self.frames[frame.0];
+        frame.pins = frame.pins.saturating_sub(1);
+    }
+
+    pub fn page(&self, frame: FrameId) -> &[u8] {
+        &self.frames[frame.0].data
+    }
+
+    /// Returns the page for writing, which marks it dirty.
+    pub fn page_mut(&mut self, frame: FrameId) -> &mut [u8] {
+        let frame = &mut self.frames[frame.0];
+        frame.dirty = true;
+        &mut frame.data
+    }
+
+    /// Convenience function that returns a copy of page 'num'.
+    pub fn read_page(&mut self, num: u16) -> Result<Vec<u8>> {
+        let frame = self.pin(num)?;
+        let page = self.page(frame).to_vec();
+        self.unpin(frame);
+        Ok(page)
+    }
+
+    /// Convenience function that replaces page 'num'. As the whole page is replaced,
+    /// it is not read from disk first.
+    pub fn write_page(&mut self, num: u16, data: &[u8]) -> Result<()> {
+        if data.len() != PAGE_SIZE {
+            return Err(Error::BufferOverflow)
+        }
+        let frame = self.fetch(num, false)?;
+        self.page_mut(frame).copy_from_slice(data);
+        self.unpin(frame);
+        Ok(())
+    }
+
+    /// Returns copies of all dirty pages, ordered by page number.
+    pub fn dirty_pages(&self) -> Vec<(u16, Vec<u8>)> {
+        let mut pages: Vec<(u16, Vec<u8>)> = self.frames.iter()
+            .filter(|f| f.dirty)
+            .filter_map(|f| f.page.map(|num| (num, f.data.clone())))
+            .collect();
+        pages.sort_by_key(|(num, _)| *num);
+        pages
+    }
+
+    /// Commits the dirty pages and the ones spilled before to the [`Wal`]. Returns copies
+    /// of all of them, ordered by page number. They still have to be written back.
+    pub fn commit(&mut self) -> Result<Vec<(u16, Vec<u8>)>> {
+        let mut pages = self.dirty_pages();
+        self.wal.commit(&pages)?;
+        for (num, pos) in self.spilled.iter() {
+            pages.push((*num, self.wal.read_page(*pos)?));
+        }
+        pages.sort_by_key(|(num, _)| *num);
+        Ok(pages)
+    }
+
+    /// Writes all dirty frames and spilled pages back, without syncing the file.
+    pub fn write_back_all(&mut self) -> Result<()> {
+        for i in 0..self.frames.len() {
+            self.write_back(i)?;
+        }
+        let spilled: Vec<(u16, u64)> = self.spilled.drain().collect();
+        for (num, pos) in spilled {
+            let data = self.wal.read_page(pos)?;
+            self.file.seek(SeekFrom::Start((num as usize * PAGE_SIZE) as u64))?;
+            self.file.write_all(&data)?;
+        }
+        Ok(())
+    }
+
+    /// Writes all dirty frames back and syncs the file.
+    pub fn flush(&mut self) -> Result<()> {
+        self.write_back_all()?;
+        self.file.sync_data()?;
+        Ok(())
+    }
+
+    /// Flushes the file and empties the [`Wal`].
+    pub fn checkpoint(&mut self) -> Result<()> {
+        self.flush()?;
+        self.wal.truncate()
+    }
+
+    /// Writes page images directly to the file, bypassing (and invalidating) the frames.
+    /// Used to replay the [`super::wal::Wal`].
+    pub fn write_through(&mut self, num: u16, data: &[u8]) -> Result<()> {
+        if let Some(i) = self.table.remove(&num) {
+            let frame = &mut self.frames[i];
+            frame.page = None;
+            frame.dirty = false;
+        }
+        self.spilled.remove(&num);
+        self.file.seek(SeekFrom::Start((num as usize * PAGE_SIZE) as u64))?;
+        self.file.write_all(data)?;
+        Ok(())
+    }
+
+    /// Drops all dirty frames and spilled pages, so the pages are read from the file again.
+    pub fn discard(&mut self) -> Result<()> {
+        self.spilled.clear();
+        self.wal.rollback()?;
+        for frame in self.frames.iter_mut() {
+            if !frame.dirty {
+                continue;
+            }
+            if let Some(num) = frame.page.take() {
+                self.table.remove(&num);
+            }
+            frame.dirty = false;
+            frame.pins = 0;
+            frame.referenced = false;
+        }
+        Ok(())
+    }
+
+    /// Returns another handle to the file, e.g. for reading without the pager.
+    pub fn try_clone_file(&self) -> Result<File> {
+        Ok(self.file.try_clone()?)
+    }
+
+    fn fetch(&mut self, num: u16, read: bool) -> Result<FrameId> {
+        if let Some(&i) = self.table.get(&num) {
+            let frame = &mut self.frames[i];
+            frame.pins += 1;
+            frame.referenced = true;
+            return Ok(FrameId(i))
+        }
+        let i = self.victim()?;
+        self.write_back(i)?;
+        if let Some(old) = self.frames[i].page.take() {
+            self.table.remove(&old);
+        }
+        // a spilled page is dirty again once it is back in a frame
+        let spilled = self.spilled.remove(&num);
+        match (read, spilled) {
+            (true, Some(pos)) => {
+                let data = self.wal.read_page(pos)?;
+                self.frames[i].data.copy_from_slice(&data);
+            },
+            (true, None) => {
+                self.file.seek(SeekFrom::Start((num as usize * PAGE_SIZE) as u64))?;
+                self.file.read_exact(&mut self.frames[i].data)?;
+            },
+            (false, _) => self.frames[i].data.fill(0)
+        }
+        let frame = &mut self.frames[i];
+        frame.page = Some(num);
+        frame.pins = 1;
+        frame.dirty = spilled.is_some();
+        frame.referenced = true;
+        self.table.insert(num, i);
+        Ok(FrameId(i))
+    }
+
+    // Clock: an empty frame is taken right away, otherwise the hand moves on
+    // until it finds an unpinned clean frame that has not been referenced since the
+    // last pass. Without one, the next unpinned dirty frame is spilled.
+    fn victim(&mut self) -> Result<usize> {
+        for _ in 0..2 * self.frames.len() {
+            let i = self.hand;
+            self.hand = (self.hand + 1) % self.frames.len();
+            let frame = &mut self.frames[i];
+            if frame.page.is_none() {
+                return Ok(i)
+            }
+            if frame.pins > 0 || frame.dirty {
+                continue;
+            }
+            if frame.referenced {
+                frame.referenced = false;
+                continue;
+            }
+            return Ok(i)
+        }
+        let len = self.frames.len();
+        let i = (0..len).map(|k| (self.hand + k) % len)
+            .find(|i| self.frames[*i].pins == 0)
+            .ok_or(Error::NoFreeFrame)?;
+        self.hand = (i + 1) % len;
+        self.spill(i)?;
+        Ok(i)
+    }
+
+    // Appends the page of dirty frame 'i' to the log, the frame is clean afterwards.
+    fn spill(&mut self, i: usize) -> Result<()> {
+        let frame = &mut self.frames[i];
+        if let (Some(num), true) = (frame.page, frame.dirty) {
+            let pos = self.wal.append(num, &frame.data)?;
+            self.spilled.insert(num, pos);
+            frame.dirty = false;
+        }
+        Ok(())
+    }
+
+    fn write_back(&mut self, i: usize) -> Result<()> {
+        let frame = &mut self.frames[i];
+        if let (Some(num), true) = (frame.page, frame.dirty) {
+            self.file.seek(SeekFrom::Start((num as usize * PAGE_SIZE) as u64))?;
+            self.file.write_all(&frame.data)?;
+            frame.dirty = false;
+        }
+        Ok(())
+    }
+}
+
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::fs::OpenOptions;
+    use crate::store::test::TempStore;
+
+    fn temp_pager(name: &str, pages: usize, capacity: usize) -> (TempStore, Pager) {
+        let path = TempStore::new(name);
+        let mut file = OpenOptions::new().read(true).write(true).create(true).truncate(true).open(&path).unwrap();
+        file.write_all(&vec![0u8; pages * PAGE_SIZE]).unwrap();
+        let wal = Wal::open(&path).unwrap();
+        (path, Pager::new(file, wal, capacity))
+    }
+
+    #[test]
+    fn pager_evicts_and_writes_back() {
+        let (path, mut pager) = temp_pager("pager_evict", 4, 2);
+        for num in 0..4u16 {
+            pager.write_page(num, &[num as u8 + 1; PAGE_SIZE]).unwrap();
+            pager.write_back_all().unwrap();
+        }
+        assert!(!pager.is_resident(0));
+        assert!(pager.is_resident(3));
+        for num in 0..4u16 {
+            assert_eq!(pager.read_page(num).unwrap(), vec![num as u8 + 1; PAGE_SIZE]);
+        }
+        pager.flush().unwrap();
+        let on_disk = std::fs::read(&path).unwrap();
+        assert_eq!(on_disk[3 * PAGE_SIZE], 4);
+    }
+
+    #[test]
+    fn pager_pinned_frames() {
+        let (_path, mut pager) = temp_pager("pager_pin", 3, 2);
+        let first = pager.pin(0).unwrap();
+        let second = pager.pin(1).unwrap();
+        assert!(matches!(pager.pin(2), Err(Error::NoFreeFrame)));
+        pager.page_mut(first)[0] = 42;
+        pager.unpin(first);
+        pager.write_back_all().unwrap();
+        let third = pager.pin(2).unwrap();
+        assert!(!pager.is_resident(0));
+        pager.unpin(second);
+        pager.unpin(third);
+        assert_eq!(pager.read_page(0).unwrap()[0], 42);
+        assert!(pager.read_page(7).is_err());
+    }
+
+    #[test]
+    fn pager_no_steal() {
+        let (_path, mut pager) = temp_pager("pager_steal", 3, 2);
+        pager.write_page(0, &[1u8; PAGE_SIZE]).unwrap();
+        pager.write_page(1, &[2u8; PAGE_SIZE]).unwrap();
+        assert_eq!(pager.dirty_pages().iter().map(|(num, _)| *num).collect::<Vec<u16>>(), vec![0, 1]);
+        pager.discard().unwrap();
+        assert!(pager.dirty_pages().is_empty());
+        assert_eq!(pager.read_page(0).unwrap(), vec![0u8; PAGE_SIZE]);
+        pager.write_through(2, &[3u8; PAGE_SIZE]).unwrap();
+        assert_eq!(pager.read_page(2).unwrap(), vec![3u8; PAGE_SIZE]);
+    }
+
+    #[test]
+    fn pager_spills_to_wal() {
+        let (path, mut pager) = temp_pager("pager_spill", 8, 2);
+        for num in 0..8u16 {
+            pager.write_page(num, &[num as u8 + 1; PAGE_SIZE]).unwrap();
+        }
+        // spilled pages are read back from the log, not the main file
+        assert_eq!(pager.dirty_pages().len(), 2);
+        assert_eq!(pager.wal().frames(), 0);
+        assert_eq!(pager.read_page(0).unwrap(), vec![1u8; PAGE_SIZE]);
+        assert!(std::fs::read(&path).unwrap().iter().all(|byte| *byte == 0));
+
+        // a rollback drops them
+        pager.discard().unwrap();
+        assert_eq!(pager.read_page(3).unwrap(), vec![0u8; PAGE_SIZE]);
+        assert_eq!(std::fs::metadata(pager.wal().path()).unwrap().len(), 0);
+
+        for num in 0..8u16 {
+            pager.write_page(num, &[num as u8 + 1; PAGE_SIZE]).unwrap();
+        }
+        let committed = pager.commit().unwrap();
+        assert_eq!(committed.iter().map(|(num, _)| *num).collect::<Vec<u16>>(), (0..8).collect::<Vec<u16>>());
+        assert!(committed.iter().all(|(num, data)| data == &vec![*num as u8 + 1; PAGE_SIZE]));
+        assert_eq!(pager.wal().frames(), 8);
+        pager.checkpoint().unwrap();
+        let on_disk = std::fs::read(&path).unwrap();
+        assert!((0..8).all(|num| on_disk[num * PAGE_SIZE] == num as u8 + 1));
+    }
+}
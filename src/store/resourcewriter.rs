@@ -1,126 +1,216 @@
-use crate::{resourcetypes::ResourceId, datatypes::id::ID};
+use crate::{resourcetypes::ResourceId, datatypes::varint};
 use crate::error::{Result, Error};
 
 use super::header::Head;
 
-use std::ptr::NonNull;
-use std::alloc::{Layout, alloc_zeroed};
-use std::mem::size_of;
 use uuid::Uuid;
 
 
+/// Initial capacity of the buffer of a [`ResourceWriter`].
 const RESOURCE_CAP: usize = 4096; 
-
+/// Length of the [`ResourceHeader`] as it is stored.
+pub const RESOURCE_HEADER_LEN: usize = 36;
+/// Offset of the [`ResourceId`] in the stored [`ResourceHeader`].
+const TYP_OFFSET: usize = 19;
+/// Format of resources written before lengths and ids became varints: every
+/// length and [`crate::datatypes::id::ID`] is a big-endian [`u16`] and keys are preceded by [`crate::datatypes::id::ID_LEN`].
+pub const FORMAT_U16: u8 = 0;
+/// Format with lengths and ids as LEB128 varints, see [`crate::datatypes::varint`].
+/// Strings keep the json escapes they were sent with, except for '\"'.
+pub const FORMAT_VARINT: u8 = 1;
+/// Same as [`FORMAT_VARINT`], but strings are stored decoded, as valid UTF-8.
+pub const FORMAT_UTF8: u8 = 2;
+/// Same as [`FORMAT_UTF8`], but decimals are stored as their json text instead of a
+/// big-endian [`f64`], which keeps their precision.
+pub const FORMAT_DECIMAL: u8 = 3;
+/// Format the [`ResourceWriter`] writes.
+pub const FORMAT_VERSION: u8 = FORMAT_DECIMAL;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ResourceHeader {
     typ: ResourceId,
+    format: u8,
     id: Uuid,  
     size: u16,
-    len: u16 
+    len: u16,
+    version_id: u32,
+    last_updated: i64,
+    previous: (u16, u16)
 }
 
 impl ResourceHeader {
     fn new_with_id(typ: ResourceId) -> Self {
         let id = Uuid::new_v4();
-        let size_len = 2;
-        let size = u16::try_from(size_of::<ResourceHeader>()).unwrap() - size_len;
         Self {
             typ,
+            format: FORMAT_VERSION,
             id,
-            size,
+            size: RESOURCE_HEADER_LEN as u16,
             len: 0,
+            version_id: 0,
+            last_updated: 0,
+            previous: (0, 0)
         }
     }
 
+    /// Creates the stored form of a deleted resource, a header without any data.
+    pub fn tombstone(typ: ResourceId, id: Uuid) -> Result<Vec<u8>> {
+        let mut header = ResourceHeader::new_with_id(typ);
+        header.id = id;
+        header.len = RESOURCE_HEADER_LEN as u16;
+        let mut stored = header.to_store()?;
+        stored[..2].copy_from_slice(&header.len.to_be_bytes());
+        Ok(stored)
+    }
+
+    /// Sets version and last update (a timestamp in milliseconds) in the stored
+    /// resource. This happens when the resource is written to the store.
+    pub fn stamp(resource: &mut [u8], version_id: u32, last_updated: i64) {
+        resource[20..24].copy_from_slice(&version_id.to_be_bytes());
+        resource[24..32].copy_from_slice(&last_updated.to_be_bytes());
+    }
+
+    /// Sets the page and offset of the previous version in the stored resource.
+    pub fn link(resource: &mut [u8], page: u16, offset: u16) {
+        resource[32..34].copy_from_slice(&page.to_be_bytes());
+        resource[34..36].copy_from_slice(&offset.to_be_bytes());
+    }
+
     fn set_len(&mut self, len: u16) {
         self.len = len;
     }
+
+    /// Returns the [`Uuid`] of the resource.
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    /// Returns the [`ResourceId`] of the resource.
+    pub fn typ(&self) -> ResourceId {
+        self.typ.clone()
+    }
+
+    /// Returns the format the data after the header is written in, [`FORMAT_U16`],
+    /// [`FORMAT_VARINT`], [`FORMAT_UTF8`] or [`FORMAT_DECIMAL`].
+    pub fn format(&self) -> u8 {
+        self.format
+    }
+
+    /// Returns the length of the stored resource in bytes, including the header.
+    pub fn resource_len(&self) -> u16 {
+        self.len
+    }
+
+    /// Returns the version of the resource, starting at 1.
+    pub fn version_id(&self) -> u32 {
+        self.version_id
+    }
+
+    /// Returns the time of the last update as timestamp in milliseconds.
+    pub fn last_updated(&self) -> i64 {
+        self.last_updated
+    }
+
+    /// Returns page and offset of the previous version, if there is one.
+    pub fn previous(&self) -> Option<(u16, u16)> {
+        match self.previous {
+            (0, _) => None,
+            previous => Some(previous)
+        }
+    }
+
+    /// Returns if this version marks the deletion of the resource.
+    pub fn is_deleted(&self) -> bool {
+        self.len as usize == RESOURCE_HEADER_LEN
+    }
 }
 
 impl Head for ResourceHeader {
     /// Layout:
-    /// |Num Bytes |2              |16            |2          |
-    /// |----------|---------------|--------------|-----------|
-    /// |          |Resource Length| Id / [`Uuid`]|ResourceId |
+    /// |Num Bytes |2              |16            |1       |1          |4          |8             |2              |2                |
+    /// |----------|---------------|--------------|--------|-----------|-----------|--------------|---------------|-----------------|
+    /// |          |Resource Length| Id / [`Uuid`]|Format  |ResourceId |Version Id |Last Updated  |Previous Page  |Previous Offset  |
+    ///
+    /// A deleted resource is stored as header only. Resources written before the format
+    /// byte existed had a [`u16`] ResourceId there, so they read as [`FORMAT_U16`].
     fn to_store(&self) -> Result<Vec<u8>> {
         let mut stored = Vec::<u8>::with_capacity(self.size.into());
         stored.extend([0, 0]);
         stored.extend(self.id.clone().into_bytes());
-        let typ: u16 = self.typ.clone().into();
-        stored.extend(typ.to_be_bytes());
+        stored.push(self.format);
+        stored.push(typ_to_store(&self.typ)?);
+        stored.extend(self.version_id.to_be_bytes());
+        stored.extend(self.last_updated.to_be_bytes());
+        stored.extend(self.previous.0.to_be_bytes());
+        stored.extend(self.previous.1.to_be_bytes());
         Ok(stored)
     }
 
     fn from_store(data: &[u8]) -> Self {
-        unimplemented!("from_store() for ResourceHeader")
+        let len = u16::from_be_bytes([data[0], data[1]]);
+        let mut id = [0u8; 16];
+        id.copy_from_slice(&data[2..18]);
+        let typ = data[TYP_OFFSET] as u16;
+        let mut version_id = [0u8; 4];
+        version_id.copy_from_slice(&data[20..24]);
+        let mut last_updated = [0u8; 8];
+        last_updated.copy_from_slice(&data[24..32]);
+        Self {
+            typ: ResourceId::try_from(typ).unwrap_or(ResourceId::Empty),
+            format: data[18],
+            id: Uuid::from_bytes(id),
+            size: RESOURCE_HEADER_LEN as u16,
+            len,
+            version_id: u32::from_be_bytes(version_id),
+            last_updated: i64::from_be_bytes(last_updated),
+            previous: (u16::from_be_bytes([data[32], data[33]]), u16::from_be_bytes([data[34], data[35]]))
+        }
     }
 }
 
+// The [`ResourceId`] as stored in the single byte of the [`ResourceHeader`].
+fn typ_to_store(typ: &ResourceId) -> Result<u8> {
+    let typ: u16 = typ.clone().into();
+    u8::try_from(typ).map_err(|_| Error::UnknownResourceId(typ))
+}
+
 
+/// Writes a resource into a buffer that grows as needed, starting with
+/// [`RESOURCE_CAP`] bytes.
 pub struct ResourceWriter {
     header: ResourceHeader,
-    cursor: usize,
-    buffer: NonNull<u8>
+    buffer: Vec<u8>
 }
 
 
 
 impl ResourceWriter {
     /// Creates new [`ResourceWriter`] instance. It also assigns 
-    /// an [`Uuid`] to the resource. It will always be set to 
-    /// have len [`PAGE_SIZE`].
+    /// an [`Uuid`] to the resource.
     pub fn new(typ: ResourceId) -> Result<Self>{
-        assert!(RESOURCE_CAP > 0, "RESOURCE_CAP cannot be '0'");
-        let layout = match Layout::array::<u8>(RESOURCE_CAP) {
-            Ok(layout) => layout,
-            Err(_) => panic!("{}", Error::LayoutSetting)
-        };
-        //SAFETY: The layout can not be null, as type is defined as u8 and MEM_CAP is 
-        //initialized at compile time.
-        let ptr = unsafe {
-            alloc_zeroed(layout)   
-        };
-        let buffer = match NonNull::new(ptr) {
-            Some(buffer) => buffer,
-            None => panic!("{}", Error::MemoryAllocation)
-        };
-        
         let header = ResourceHeader::new_with_id(typ);
-        let head = header.to_store()?;
-
-        //write header to buffer
-        unsafe {
-            let ptr = buffer.as_ptr();
-            ptr.copy_from(head.as_ptr(), head.len());
-        };
+        let mut buffer = Vec::with_capacity(RESOURCE_CAP);
+        buffer.extend(header.to_store()?);
         Ok(Self {
             buffer,
-            cursor: header.size.into(),
             header
         })
     }
 
-    //checks if the any operation will cause a segmentation fault or buffer overflow.
-    fn len_check(&self, adv: usize) -> Result<()> {
-        if self.cursor + adv > RESOURCE_CAP {
-            return Err(Error::SegmentationFault)
-        }
-        Ok(())
-    }
-
-    // Advances the cursor by 'i' bytes without writing to them.
+    // Advances the cursor by 'i' zeroed bytes.
     // It returns the cursor position at which the advance was issued.
     fn advance_by(&mut self, i: usize) -> Result<usize> {
-        self.len_check(i)?;
-        let pos = self.cursor;
-        self.cursor += i;   
+        let pos = self.buffer.len();
+        self.buffer.resize(pos + i, 0);
         Ok(pos)
     }
 
 
     // sets a single byte [`u8`] at the provided index 'i'
     fn set_at(&mut self, v: u8, i: usize) -> Result<()> {
-        unsafe {
-            self.buffer.as_ptr().add(i).write(v);
+        match self.buffer.get_mut(i) {
+            Some(byte) => *byte = v,
+            None => return Err(Error::SegmentationFault)
         }
         Ok(())
     }
@@ -132,23 +222,18 @@ impl ResourceWriter {
 
     /// Returns the current cursor position.
     pub fn len(&self) -> usize {
-        self.cursor
+        self.buffer.len()
     }
 
-    /// Given a [`ptr`], it writes the contents to the buffer.
-    pub fn set(&mut self, src: *mut u8, len: usize) -> Result<usize> {
-        self.len_check(len)?;
-        unsafe {
-            let ptr = self.buffer.as_ptr();
-            ptr.add(self.cursor).copy_from(src, len);
-        }
-        self.cursor += len;
-        Ok(len)
+    /// Writes 'data' to the buffer, returns the number of bytes written.
+    pub fn set(&mut self, data: &[u8]) -> Result<usize> {
+        self.buffer.extend_from_slice(data);
+        Ok(data.len())
     }
 
     /// Convenience function that writes a u16 to the buffer.
     pub fn set_u16<I: Into<u16>>(&mut self, v: I) -> Result<usize> {
-        self.set(v.into().to_be_bytes().as_mut_ptr(), 2)
+        self.set(&v.into().to_be_bytes())
     }
 
     
@@ -160,23 +245,66 @@ impl ResourceWriter {
         Ok(2)
     }
 
+    /// Writes 'v' as varint to the buffer, returns the number of bytes written.
+    pub fn set_varint(&mut self, v: usize) -> Result<usize> {
+        Ok(varint::write(v as u64, &mut self.buffer))
+    }
+
+    /// Inserts 'v' as varint at the given index, moving all bytes after it.
+    /// Returns the number of bytes inserted.
+    pub fn insert_varint_at(&mut self, v: usize, i: usize) -> Result<usize> {
+        if i > self.buffer.len() {
+            return Err(Error::SegmentationFault)
+        }
+        let bytes = varint::encode(v as u64);
+        let len = bytes.len();
+        self.buffer.splice(i..i, bytes);
+        Ok(len)
+    }
+
     /// Advances the cursor by two bytes without writing to them.
     /// It returns the cursor position at which the reserve was issued.
     pub fn reserve_two(&mut self) -> Result<usize> {
         self.advance_by(2)
     }
 
-    /// Returns a [`*mut u8`] pointer to the buffer.
-    pub fn get_mut_ptr(&self) -> *mut u8 {
-        self.buffer.as_ptr()
+    /// Returns the buffer written so far, including the header.
+    pub fn as_slice(&self) -> &[u8] {
+        &self.buffer
+    }
+
+    /// Returns the [`Uuid`] assigned to the resource.
+    pub fn id(&self) -> Uuid {
+        self.header.id
+    }
+
+    /// Replaces the [`ResourceId`] given on creation, once the type of the resource is known.
+    pub fn set_typ(&mut self, typ: ResourceId) -> Result<()> {
+        self.buffer[TYP_OFFSET] = typ_to_store(&typ)?;
+        self.header.typ = typ;
+        Ok(())
+    }
+
+    /// Replaces the [`Uuid`] assigned on creation, e.g. when a stored resource is updated.
+    pub fn set_id(&mut self, id: Uuid) {
+        self.header.id = id;
+        self.buffer[2..18].copy_from_slice(id.as_bytes());
+    }
+
+    /// Sets the resource length in the header and returns the buffer 
+    /// including the header as a [`Vec`]. Resources longer than [`u16::MAX`]
+    /// get [`u16::MAX`] as length, they only fit on overflow pages, which
+    /// keep the full length (see [`super::overflow`]).
+    pub fn finish(&mut self) -> Result<Vec<u8>> {
+        let len = u16::try_from(self.buffer.len()).unwrap_or(u16::MAX);
+        self.header.set_len(len);
+        self.set_u16_at(len, 0)?;
+        Ok(self.to_vec())
     }
 
     /// Returns the buffer as a [`Vec`].
     pub fn to_vec(&mut self) -> Vec<u8> {
-        let sliced = unsafe {
-            std::slice::from_raw_parts_mut(self.get_mut_ptr(), self.cursor)
-        };
-        sliced.to_vec()
+        self.buffer.clone()
     }
 }
 
@@ -192,14 +320,14 @@ mod test {
         let writer = ResourceWriter::new(ResourceId::Patient).unwrap();
         assert_eq!(writer.header.typ, ResourceId::Patient);
         assert_eq!(writer.header.len, 0);
-        assert_eq!(writer.cursor, writer.header.size.into());
+        assert_eq!(writer.len(), writer.get_header_len());
     }
 
     #[test]
     fn resourcewriter_write() {
         let mut writer = ResourceWriter::new(ResourceId::Patient).unwrap();
-        let start = writer.cursor;
-        assert_eq!(start, writer.header.size.into());
+        let start = writer.len();
+        assert_eq!(start, writer.get_header_len());
         let reserved_at = writer.reserve_two().unwrap();
         assert_eq!(writer.len(), start+2);
         assert_eq!(reserved_at, start);
@@ -212,16 +340,100 @@ mod test {
         assert_eq!(v[reserved_at+1], 2);
         writer.set_u16(514u16).unwrap();
         let v = writer.to_vec();
-        assert_eq!(v[writer.cursor-2], 2);
-        assert_eq!(v[writer.cursor-1], 2);
-        let start = writer.cursor;
-        let mut data = b"Hello, World".to_vec();
-        let written = writer.set(data.as_mut_ptr(), data.len()).unwrap();
+        assert_eq!(v[writer.len()-2], 2);
+        assert_eq!(v[writer.len()-1], 2);
+        let start = writer.len();
+        let data = b"Hello, World".to_vec();
+        let written = writer.set(&data).unwrap();
         assert_eq!(written, data.len());
         let mut vect = writer.to_vec();
         let drained:Vec<u8> = vect.drain(start..start+data.len()).collect();
         assert_eq!(String::from_utf8(drained).unwrap(), "Hello, World".to_string());
-        assert!(writer.len_check(4097).is_err());
+        assert!(writer.set_u16_at(1u16, writer.len()).is_err());
+    }
+
+    #[test]
+    fn resourcewriter_varint() {
+        let mut writer = ResourceWriter::new(ResourceId::Patient).unwrap();
+        let start = writer.len();
+        assert_eq!(writer.set_varint(4096).unwrap(), 2);
+        writer.set_varint(5).unwrap();
+        assert_eq!(writer.insert_varint_at(300, start).unwrap(), 2);
+        assert_eq!(writer.to_vec()[start..], [172, 2, 128, 32, 5]);
+        assert!(writer.insert_varint_at(1, writer.len() + 1).is_err());
+    }
+
+    #[test]
+    fn resourcewriter_grows() {
+        let mut writer = ResourceWriter::new(ResourceId::Patient).unwrap();
+        let data = vec![7u8; 1000];
+        for _ in 0..100 {
+            writer.set(&data).unwrap();
+        }
+        let v = writer.finish().unwrap();
+        assert_eq!(v.len(), RESOURCE_HEADER_LEN + 100_000);
+        assert!(v[RESOURCE_HEADER_LEN..].iter().all(|b| *b == 7));
+        assert_eq!(ResourceHeader::from_store(&v).resource_len(), u16::MAX);
+        assert_eq!(ResourceHeader::from_store(&v).id(), writer.id());
+    }
+
+    #[test]
+    fn resourcewriter_finish() {
+        let mut writer = ResourceWriter::new(ResourceId::Patient).unwrap();
+        writer.set_u16(514u16).unwrap();
+        let v = writer.finish().unwrap();
+        assert_eq!(v.len(), RESOURCE_HEADER_LEN + 2);
+        let header = ResourceHeader::from_store(&v);
+        assert_eq!(header.resource_len() as usize, v.len());
+        assert_eq!(header.id(), writer.id());
+        assert_eq!(header.typ(), ResourceId::Patient);
+        assert_eq!(header.format(), FORMAT_VERSION);
+    }
+
+    #[test]
+    fn resourcewriter_old_header() {
+        let mut v = ResourceWriter::new(ResourceId::Patient).unwrap().finish().unwrap();
+        // a header written before the format byte, with the ResourceId as u16
+        v[18] = 0;
+        let header = ResourceHeader::from_store(&v);
+        assert_eq!(header.format(), FORMAT_U16);
+        assert_eq!(header.typ(), ResourceId::Patient);
+    }
+
+    #[test]
+    fn resourcewriter_set_id() {
+        let mut writer = ResourceWriter::new(ResourceId::Patient).unwrap();
+        let id = Uuid::new_v4();
+        writer.set_id(id);
+        let v = writer.finish().unwrap();
+        assert_eq!(writer.id(), id);
+        assert_eq!(ResourceHeader::from_store(&v).id(), id);
+    }
+
+    #[test]
+    fn resourcewriter_stamp() {
+        let mut writer = ResourceWriter::new(ResourceId::Patient).unwrap();
+        let mut v = writer.finish().unwrap();
+        ResourceHeader::stamp(&mut v, 3, 1686391200000);
+        let header = ResourceHeader::from_store(&v);
+        assert_eq!(header.version_id(), 3);
+        assert_eq!(header.last_updated(), 1686391200000);
+        assert_eq!(header.id(), writer.id());
+        assert_eq!(header.resource_len() as usize, RESOURCE_HEADER_LEN);
+        assert!(header.previous().is_none());
+        ResourceHeader::link(&mut v, 7, 120);
+        assert_eq!(ResourceHeader::from_store(&v).previous(), Some((7, 120)));
+    }
+
+    #[test]
+    fn resourcewriter_tombstone() {
+        let id = Uuid::new_v4();
+        let stored = ResourceHeader::tombstone(ResourceId::Patient, id).unwrap();
+        assert_eq!(stored.len(), RESOURCE_HEADER_LEN);
+        let header = ResourceHeader::from_store(&stored);
+        assert!(header.is_deleted());
+        assert_eq!(header.id(), id);
+        assert_eq!(header.typ(), ResourceId::Patient);
     }
 
 
@@ -0,0 +1,89 @@
+use crate::error::Result;
+use super::btree::BTree;
+use super::index::Pages;
+
+
+/// Length of the keys of a secondary index, see [`crate::search::terms`].
+pub const KEY_LEN: usize = 48;
+
+pub type Key = [u8; KEY_LEN];
+
+/// Secondary indexes are B+trees like the primary [`super::index`], but they only hold
+/// keys, the key already contains the id of the resource it points to. All secondary
+/// indexes share one tree, the keys start with the resource type and search parameter.
+type Secondary = BTree<Key, ()>;
+
+
+/// Creates an empty secondary index and returns the page of its root.
+pub fn create<P: Pages>(pages: &mut P) -> Result<u16> {
+    Ok(Secondary::create(pages)?.root)
+}
+
+/// Inserts 'key', if not yet present. Returns the page of the root, which changes if the root was split.
+pub fn insert<P: Pages>(pages: &mut P, root: u16, key: &Key) -> Result<u16> {
+    let mut secondary = Secondary::at(root);
+    secondary.insert(pages, key, ())?;
+    Ok(secondary.root)
+}
+
+/// Removes 'key' from its leaf, nodes are not merged (see [`super::index::remove`]).
+/// Returns if the key was present.
+pub fn remove<P: Pages>(pages: &mut P, root: u16, key: &Key) -> Result<bool> {
+    Secondary::at(root).remove(pages, key)
+}
+
+/// Returns all keys from 'from' (inclusive) to 'to' (exclusive) in key order.
+pub fn range<P: Pages>(pages: &mut P, root: u16, from: &Key, to: &Key) -> Result<Vec<Key>> {
+    let found = Secondary::at(root).range(pages, Some(from), Some(to))?;
+    Ok(found.into_iter().map(|(key, _)| key).collect())
+}
+
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::store::btree::test::MemPages;
+
+    fn key(term: u32, id: u32) -> Key {
+        let mut key = [0u8; KEY_LEN];
+        key[..4].copy_from_slice(&term.to_be_bytes());
+        key[KEY_LEN-4..].copy_from_slice(&id.to_be_bytes());
+        key
+    }
+
+    #[test]
+    fn secondary_insert_range() {
+        let mut pages = MemPages::default();
+        let mut root = create(&mut pages).unwrap();
+        // 100 terms with 100 resources each, inserted out of order
+        for id in 0..100 {
+            for term in (0..100).rev() {
+                root = insert(&mut pages, root, &key(term, id)).unwrap();
+            }
+        }
+        root = insert(&mut pages, root, &key(5, 5)).unwrap();
+        assert!(Secondary::at(root).is_split(&mut pages).unwrap());
+
+        let found = range(&mut pages, root, &key(42, 0), &key(43, 0)).unwrap();
+        assert_eq!(found, (0..100).map(|id| key(42, id)).collect::<Vec<Key>>());
+        assert_eq!(range(&mut pages, root, &key(10, 0), &key(20, 0)).unwrap().len(), 1000);
+        assert!(range(&mut pages, root, &key(100, 0), &[0xFF; KEY_LEN]).unwrap().is_empty());
+        assert_eq!(range(&mut pages, root, &[0; KEY_LEN], &[0xFF; KEY_LEN]).unwrap().len(), 10_000);
+    }
+
+    #[test]
+    fn secondary_remove() {
+        let mut pages = MemPages::default();
+        let mut root = create(&mut pages).unwrap();
+        for id in 0..1000 {
+            root = insert(&mut pages, root, &key(1, id)).unwrap();
+        }
+        for id in (0..1000).filter(|id| id % 2 == 0) {
+            assert!(remove(&mut pages, root, &key(1, id)).unwrap());
+        }
+        assert!(!remove(&mut pages, root, &key(1, 0)).unwrap());
+        let found = range(&mut pages, root, &key(1, 0), &key(2, 0)).unwrap();
+        assert_eq!(found, (0..1000).filter(|id| id % 2 == 1).map(|id| key(1, id)).collect::<Vec<Key>>());
+    }
+}
@@ -0,0 +1,320 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Read;
+use std::sync::{Arc, Mutex, MutexGuard, RwLock, RwLockReadGuard, RwLockWriteGuard};
+use uuid::Uuid;
+use crate::error::{Result, Error};
+use crate::parser::serializer::{stored_to_json, resource_to_json};
+use super::header::Head;
+use super::resourcewriter::ResourceHeader;
+use super::index::Pages;
+use super::history::{self, Version};
+use crate::resourcetypes::ResourceId;
+use crate::search::query::{self, Query};
+use super::store::{Store, PAGE_SIZE, parse_id, read_resource, to_json};
+
+
+/// Thread-safe handle of a [`Store`], created by [`Store::into_shared`]. Clones share
+/// the same store.
+///
+/// There is a single writer: all writes go through the [`Store`] behind a mutex.
+/// Readers do not take that mutex. They read the snapshot published by the last
+/// commit, that is the pages committed since the last checkpoint (kept in memory)
+/// and the main file for all other pages. The writer only writes pages back to the
+/// main file, while no reader is reading from it, so readers never see a partially
+/// applied transaction.
+#[derive(Debug, Clone)]
+pub struct SharedStore {
+    inner: Arc<Inner>
+}
+
+#[derive(Debug)]
+struct Inner {
+    writer: Mutex<Store>,
+    snapshots: Arc<Snapshots>,
+    file: File
+}
+
+impl SharedStore {
+    pub(super) fn new(store: Store, snapshots: Arc<Snapshots>, file: File) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                writer: Mutex::new(store),
+                snapshots,
+                file
+            })
+        }
+    }
+
+    /// Returns the writer. Other writers block until it is dropped, readers don't.
+    /// If a previous writer panicked, its uncommitted changes are rolled back.
+    pub fn writer(&self) -> Result<MutexGuard<'_, Store>> {
+        match self.inner.writer.lock() {
+            Ok(store) => Ok(store),
+            Err(err) => {
+                let mut store = err.into_inner();
+                store.rollback()?;
+                self.inner.writer.clear_poison();
+                Ok(store)
+            }
+        }
+    }
+
+    /// Returns the resource with the given id as json, as of the last commit.
+    pub fn get_resource_by_id(&self, id: &str) -> Result<Option<String>> {
+        let id = parse_id(id)?;
+        match self.find_resource(&id)? {
+            Some(resource) => Ok(Some(stored_to_json(&resource)?)),
+            None => Ok(None)
+        }
+    }
+
+    /// See [`Store::get_resource`], as of the last commit.
+    pub fn get_resource(&self, id: &str) -> Result<Option<(ResourceHeader, String)>> {
+        let id = parse_id(id)?;
+        match self.find_resource(&id)? {
+            Some(resource) => Ok(Some((ResourceHeader::from_store(&resource), resource_to_json(&resource)?))),
+            None => Ok(None)
+        }
+    }
+
+    /// See [`Store::add_resource`].
+    pub fn add_resource<D: Read>(&self, data: D) -> Result<Uuid> {
+        self.writer()?.add_resource(data)
+    }
+
+    /// See [`Store::update_resource`].
+    pub fn update_resource<D: Read>(&self, id: &str, data: D) -> Result<()> {
+        self.writer()?.update_resource(id, data)
+    }
+
+    /// See [`Store::delete_resource`].
+    pub fn delete_resource(&self, id: &str) -> Result<bool> {
+        self.writer()?.delete_resource(id)
+    }
+
+    /// See [`Store::vread`], as of the last commit.
+    pub fn vread(&self, id: &str, version_id: u32) -> Result<Option<Version>> {
+        let id = parse_id(id)?;
+        self.read(|pages, root| history::version(pages, root, &id, version_id))
+    }
+
+    /// See [`Store::history`], as of the last commit.
+    pub fn history(&self, id: &str) -> Result<Vec<Version>> {
+        let id = parse_id(id)?;
+        self.read(|pages, root| history::versions(pages, root, &id))
+    }
+
+    /// See [`Store::type_history`], as of the last commit.
+    pub fn type_history(&self, typ: ResourceId) -> Result<Vec<Version>> {
+        self.read(|pages, root| history::all_versions(pages, root, Some(typ)))
+    }
+
+    /// See [`Store::system_history`], as of the last commit.
+    pub fn system_history(&self) -> Result<Vec<Version>> {
+        self.read(|pages, root| history::all_versions(pages, root, None))
+    }
+
+    /// See [`Store::search`], as of the last commit.
+    pub fn search(&self, typ: ResourceId, query: &Query) -> Result<(usize, Vec<(ResourceHeader, String)>)> {
+        let (total, found) = self.read_snapshot(|pages, snapshot| {
+            query::search(pages, snapshot.index_root, snapshot.search_root, &typ, query)
+        })?;
+        Ok((total, to_json(&found)?))
+    }
+
+    fn find_resource(&self, id: &Uuid) -> Result<Option<Vec<u8>>> {
+        self.read(|pages, root| read_resource(pages, root, id))
+    }
+
+    // Runs 'f' on the pages of the current snapshot and the root of its index.
+    fn read<T, F: FnOnce(&mut SnapshotPages, u16) -> Result<T>>(&self, f: F) -> Result<T> {
+        self.read_snapshot(|pages, snapshot| f(pages, snapshot.index_root))
+    }
+
+    // Runs 'f' on the pages of the current snapshot.
+    fn read_snapshot<T, F: FnOnce(&mut SnapshotPages, &Snapshot) -> Result<T>>(&self, f: F) -> Result<T> {
+        // the file must not change between taking the snapshot and reading its pages
+        let _file = self.inner.snapshots.read_file();
+        let snapshot = self.inner.snapshots.current();
+        let mut pages = SnapshotPages {
+            file: &self.inner.file,
+            snapshot: &snapshot
+        };
+        f(&mut pages, &snapshot)
+    }
+}
+
+
+/// The committed state as seen by the readers.
+#[derive(Debug, Clone, Default)]
+struct Snapshot {
+    index_root: u16,
+    search_root: u16,
+    // pages committed since the last checkpoint
+    pages: HashMap<u16, Arc<Vec<u8>>>
+}
+
+/// Shared between the writing [`Store`] and its readers. The writer publishes a new
+/// [`Snapshot`] with every commit and holds the file lock while writing pages back.
+#[derive(Debug, Default)]
+pub(super) struct Snapshots {
+    file: RwLock<()>,
+    current: RwLock<Arc<Snapshot>>
+}
+
+impl Snapshots {
+    pub(super) fn new(index_root: u16, search_root: u16) -> Self {
+        Self {
+            file: RwLock::new(()),
+            current: RwLock::new(Arc::new(Snapshot { index_root, search_root, pages: HashMap::new() }))
+        }
+    }
+
+    /// Makes the committed pages visible to the readers. Must happen before they
+    /// are written back to the main file.
+    pub(super) fn publish(&self, index_root: u16, search_root: u16, pages: Vec<(u16, Vec<u8>)>) {
+        let mut current = self.current.write().unwrap_or_else(|err| err.into_inner());
+        let mut snapshot = Snapshot::clone(&current);
+        snapshot.index_root = index_root;
+        snapshot.search_root = search_root;
+        for (num, page) in pages {
+            snapshot.pages.insert(num, Arc::new(page));
+        }
+        *current = Arc::new(snapshot);
+    }
+
+    /// Drops the committed pages, once the main file holds all of them.
+    pub(super) fn clear(&self, index_root: u16, search_root: u16) {
+        let mut current = self.current.write().unwrap_or_else(|err| err.into_inner());
+        *current = Arc::new(Snapshot { index_root, search_root, pages: HashMap::new() });
+    }
+
+    /// Locks the main file for writing, waits for the readers to finish.
+    pub(super) fn write_file(&self) -> RwLockWriteGuard<'_, ()> {
+        self.file.write().unwrap_or_else(|err| err.into_inner())
+    }
+
+    fn read_file(&self) -> RwLockReadGuard<'_, ()> {
+        self.file.read().unwrap_or_else(|err| err.into_inner())
+    }
+
+    fn current(&self) -> Arc<Snapshot> {
+        self.current.read().unwrap_or_else(|err| err.into_inner()).clone()
+    }
+}
+
+
+// Read only view of the pages of a snapshot.
+struct SnapshotPages<'a> {
+    file: &'a File,
+    snapshot: &'a Snapshot
+}
+
+impl Pages for SnapshotPages<'_> {
+    fn read_page(&mut self, num: u16) -> Result<Vec<u8>> {
+        if let Some(page) = self.snapshot.pages.get(&num) {
+            return Ok(page.to_vec())
+        }
+        let mut page = vec![0u8; PAGE_SIZE];
+        read_exact_at(self.file, &mut page, (num as usize * PAGE_SIZE) as u64)?;
+        Ok(page)
+    }
+
+    fn write_page(&mut self, _num: u16, _page: &[u8]) -> Result<()> {
+        Err(Error::Custom("a snapshot is read only".to_string()))
+    }
+
+    fn allocate_page(&mut self) -> Result<u16> {
+        Err(Error::Custom("a snapshot is read only".to_string()))
+    }
+}
+
+// Reads without moving the cursor of the file, which belongs to the writer.
+#[cfg(unix)]
+fn read_exact_at(file: &File, buf: &mut [u8], offset: u64) -> std::io::Result<()> {
+    use std::os::unix::fs::FileExt;
+    file.read_exact_at(buf, offset)
+}
+
+#[cfg(windows)]
+fn read_exact_at(file: &File, mut buf: &mut [u8], mut offset: u64) -> std::io::Result<()> {
+    use std::os::windows::fs::FileExt;
+    while !buf.is_empty() {
+        match file.seek_read(buf, offset)? {
+            0 => return Err(std::io::ErrorKind::UnexpectedEof.into()),
+            n => {
+                buf = &mut buf[n..];
+                offset += n as u64;
+            }
+        }
+    }
+    Ok(())
+}
+
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::thread;
+    use crate::store::test::TempStore;
+
+    #[test]
+    fn shared_concurrent_readers() {
+        let path = TempStore::new("shared_readers");
+        let store = Store::open_path(&path).unwrap().into_shared().unwrap();
+        let first = store.add_resource(&br#"{"resourceType": "patient", "active": true}"#[..]).unwrap().to_string();
+        let ids = Arc::new(RwLock::new(vec![first]));
+
+        let readers: Vec<_> = (0..4).map(|_| {
+            let store = store.clone();
+            let ids = ids.clone();
+            thread::spawn(move || {
+                for i in 0..500 {
+                    let known = ids.read().unwrap().clone();
+                    let id = &known[i % known.len()];
+                    assert_eq!(store.get_resource_by_id(id).unwrap().unwrap(), r#"{"resourceType":"patient","active":true}"#);
+                }
+            })
+        }).collect();
+        for _ in 0..200 {
+            let id = store.add_resource(&br#"{"resourceType": "patient", "active": true}"#[..]).unwrap();
+            ids.write().unwrap().push(id.to_string());
+        }
+        for reader in readers {
+            reader.join().unwrap();
+        }
+        for id in ids.read().unwrap().iter() {
+            assert!(store.get_resource_by_id(id).unwrap().is_some());
+        }
+        drop(store);
+    }
+
+    #[test]
+    fn shared_readers_see_commits_only() {
+        let path = TempStore::new("shared_isolation");
+        let store = Store::open_path(&path).unwrap().into_shared().unwrap();
+        let existing = store.add_resource(&br#"{"resourceType": "patient", "active": true}"#[..]).unwrap().to_string();
+
+        let mut writer = store.writer().unwrap();
+        let mut tx = writer.begin();
+        let created = tx.create(&br#"{"resourceType": "patient", "active": false}"#[..]).unwrap().to_string();
+        assert!(tx.delete(&existing).unwrap());
+        // readers are not blocked by the open transaction and don't see it
+        let reader = store.clone();
+        let (check_created, check_existing) = (created.clone(), existing.clone());
+        thread::spawn(move || {
+            assert!(reader.get_resource_by_id(&check_created).unwrap().is_none());
+            assert!(reader.get_resource_by_id(&check_existing).unwrap().is_some());
+        }).join().unwrap();
+        tx.commit().unwrap();
+        drop(writer);
+
+        assert!(store.get_resource_by_id(&created).unwrap().is_some());
+        assert!(store.get_resource_by_id(&existing).unwrap().is_none());
+        store.writer().unwrap().checkpoint().unwrap();
+        assert!(store.get_resource_by_id(&created).unwrap().is_some());
+        drop(store);
+    }
+}
@@ -1,139 +1,476 @@
-use std::io::{Read, Write, Seek};
-use std::fs::{OpenOptions, File};
+use std::io::{Read, Write};
+use std::fs::OpenOptions;
+use std::path::Path;
+use std::sync::Arc;
 use crate::error::{Result, Error};
+use crate::parser::serializer::{stored_to_json, resource_to_json};
+use super::header::{Head, PAGE_HEADER_LEN};
+use super::index::{self, Location, Pages};
+use super::secondary;
+use super::overflow;
+use super::page::PageHeader;
+use super::pager::Pager;
+use super::wal::Wal;
+use super::history::{self, Version, read_at};
+use crate::resourcetypes::ResourceId;
+use super::transaction::Transaction;
+use crate::search::query::{self, Query};
+use crate::search::terms;
+use super::shared::{SharedStore, Snapshots};
+use super::resourcewriter::{ResourceHeader, RESOURCE_HEADER_LEN};
+use uuid::Uuid;
 
 const INIT_PAGES: usize = 4;
 pub const PAGE_SIZE: usize = 4096;
-type PAGE = &'static [u8;PAGE_SIZE];
-
-fn print_const() {
-    println!("INFO: constants\n\t- PAGE_SIZE:  {PAGE_SIZE}\n\t- INIT_PAGES: {INIT_PAGES}");
-}
+/// Number of frames in the buffer pool of the [`Pager`].
+pub const POOL_SIZE: usize = 64;
+/// Number of pages in the [`Wal`] that trigger a checkpoint.
+pub const CHECKPOINT_FRAMES: usize = 1024;
 
 #[derive(Debug)]
 pub struct Store {
-    file: File,
+    pager: Pager,
     header: StoreHeader,
+    snapshots: Option<Arc<Snapshots>>,
 }
 
 impl Store {
 
-    pub fn open() -> std::result::Result<Self, std::io::Error> {
-    print_const();
-        match OpenOptions::new()
+    pub fn open() -> Result<Self> {
+        Store::open_path("store.db")
+    }
+
+    pub fn open_path<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let mut store = Store::open_file(path)?;
+        if store.header.index_root == 0 {
+            if let Err(err) = store.build_index() {
+                store.rollback()?;
+                return Err(err)
+            }
+            store.commit()?;
+        }
+        if store.header.search_root == 0 {
+            if let Err(err) = store.build_search_index() {
+                store.rollback()?;
+                return Err(err)
+            }
+            store.commit()?;
+        }
+        Ok(store)
+    }
+
+    fn open_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let mut f = OpenOptions::new()
             .read(true)
             .write(true)
             .create(true)
-            .open("store.db") {
-                Ok(mut f) => {
-                    if let Ok(md) = f.metadata() {
-                        if md.len() == 0 {
-                            println!("INFO: File 'store.db' was empty -> setting {INIT_PAGES} pages and writing header.");
-                            // we initialize with INIT_PAGES pages to start with
-                            let mut buf: [u8; PAGE_SIZE*INIT_PAGES] = [0; PAGE_SIZE*INIT_PAGES];
-                            let header = StoreHeader::new(INIT_PAGES as u16);
-                            header.flush_init(buf.as_mut_ptr());
-                            f.write(&buf)?;
-                            f.sync_all()?;
-                            Ok(Self {
-                               file: f,
-                               header,
-                            })
-                        } else {
-                            println!("INFO: Reading Store Header.");
-                            let mut buf: [u8; PAGE_SIZE] = [0; PAGE_SIZE];
-                            f.read(&mut buf)?;
-                            let header = StoreHeader::read_init(&buf.as_ref());
-                            Ok(Self {file: f, header})                              
-                        }
-                    } else {
-                        println!("INFO: FILE store.db was empty -> setting {INIT_PAGES} pages and writing header.");
-                        // we initialize with INIT_PAGES pages to start with
-                        let mut buf: [u8; PAGE_SIZE*INIT_PAGES] = [0; PAGE_SIZE*INIT_PAGES];
-                        let header = StoreHeader::new(INIT_PAGES as u16);
-                        header.flush_init(buf.as_mut_ptr());
-                        f.write(&buf)?;
-                        f.sync_all()?;
-                        Ok(Self {
-                            file: f,
-                            header,
-                        })
-                   }
-                },
-                Err(err) => Err(err)
+            .truncate(false)
+            .open(path)?;
+        if f.metadata()?.len() == 0 {
+            // we initialize with INIT_PAGES pages to start with
+            let mut buf: [u8; PAGE_SIZE*INIT_PAGES] = [0; PAGE_SIZE*INIT_PAGES];
+            let header = StoreHeader::new(INIT_PAGES as u16);
+            header.flush_init(buf.as_mut_ptr());
+            f.write_all(&buf)?;
+            f.sync_all()?;
+        }
+        let mut wal = Wal::open(path)?;
+        let pages = wal.recover()?;
+        let mut pager = Pager::new(f, wal, POOL_SIZE);
+        if !pages.is_empty() {
+            for (num, page) in pages.iter() {
+                pager.write_through(*num, page)?;
+            }
+        }
+        pager.checkpoint()?;
+        let header = StoreHeader::read_init(&pager.read_page(0)?);
+        Ok(Self {
+            pager,
+            header,
+            snapshots: None
+        })
+    }
+
+    /// Starts a [`Transaction`]. Changes made through it only become visible
+    /// to the store once the transaction is committed.
+    pub fn begin(&mut self) -> Transaction<'_> {
+        Transaction::new(self)
+    }
+
+    /// Turns the store into a [`SharedStore`], which can be used from several threads.
+    pub fn into_shared(mut self) -> Result<SharedStore> {
+        let file = self.pager.try_clone_file()?;
+        let snapshots = Arc::new(Snapshots::new(self.header.index_root, self.header.search_root));
+        self.snapshots = Some(snapshots.clone());
+        Ok(SharedStore::new(self, snapshots, file))
+    }
+
+    // Makes all modified pages durable. Their images are written to the [`Wal`] first,
+    // only then the pages are written to the main file.
+    pub(super) fn commit(&mut self) -> Result<()> {
+        let pages = match self.pager.commit() {
+            Ok(pages) => pages,
+            Err(err) => {
+                self.rollback()?;
+                return Err(err)
+            }
+        };
+        match self.snapshots.clone() {
+            Some(snapshots) => {
+                snapshots.publish(self.header.index_root, self.header.search_root, pages);
+                let _file = snapshots.write_file();
+                self.pager.write_back_all()?;
+            },
+            None => self.pager.write_back_all()?
+        }
+        if self.pager.wal().frames() >= CHECKPOINT_FRAMES {
+            self.checkpoint()?;
+        }
+        Ok(())
+    }
+
+    // Drops all modified pages and rereads the header.
+    pub(super) fn rollback(&mut self) -> Result<()> {
+        self.pager.discard()?;
+        self.header = StoreHeader::read_init(&self.pager.read_page(0)?);
+        Ok(())
+    }
+
+    /// Syncs the main file and empties the [`Wal`]. Happens automatically once the log
+    /// holds [`CHECKPOINT_FRAMES`] pages and when the store is dropped, use [`Store::close`]
+    /// to see whether the last checkpoint failed.
+    pub fn checkpoint(&mut self) -> Result<()> {
+        match self.snapshots.clone() {
+            Some(snapshots) => {
+                let _file = snapshots.write_file();
+                self.pager.checkpoint()?;
+                snapshots.clear(self.header.index_root, self.header.search_root);
+                Ok(())
+            },
+            None => self.pager.checkpoint()
+        }
+    }
+
+    /// Checkpoints and closes the store. Unlike dropping it, this reports a failed checkpoint,
+    /// the pages are still in the [`Wal`] then and get replayed on the next open.
+    pub fn close(mut self) -> Result<()> {
+        self.checkpoint()
+    }
+
+    // Writes the store header into page 0.
+    fn flush_header(&mut self) -> Result<()> {
+        let frame = self.pager.pin(0)?;
+        self.header.flush_init(self.pager.page_mut(frame).as_mut_ptr());
+        self.pager.unpin(frame);
+        Ok(())
+    }
+
+    // Creates the primary index. Resources already stored (by a version without index)
+    // are added to it, at this point every page up to the top page is a data page.
+    fn build_index(&mut self) -> Result<()> {
+        let top = self.header.top_page;
+        let mut root = index::create(self)?;
+        for page_num in 1..=top {
+            let page = self.pager.read_page(page_num)?;
+            let page_header = PageHeader::from_store(&page);
+            let mut offset = PAGE_HEADER_LEN;
+            for _ in 0..page_header.num_resources {
+                let header = ResourceHeader::from_store(&page[offset..offset+RESOURCE_HEADER_LEN]);
+                let len = header.resource_len() as usize;
+                if len < RESOURCE_HEADER_LEN || offset + len > PAGE_SIZE {
+                    return Err(Error::SegmentationFault)
+                }
+                let loc = Location { page: page_num, offset: offset as u16 };
+                root = index::insert(self, root, &header.id(), loc)?;
+                offset += len;
+            }
+        }
+        self.header.index_root = root;
+        self.flush_header()
+    }
+
+    // Creates the secondary indexes from the latest versions of all resources.
+    fn build_search_index(&mut self) -> Result<()> {
+        let mut root = secondary::create(self)?;
+        let index_root = self.header.index_root;
+        for (_, loc) in index::entries(self, index_root)? {
+            let stored = read_at(self, loc)?;
+            for key in terms::keys(&stored)? {
+                root = secondary::insert(self, root, &key)?;
             }
+        }
+        self.header.search_root = root;
+        self.flush_header()
+    }
+
+    // Replaces the keys of the version 'previous' in the secondary indexes by those
+    // of 'resource', both as stored.
+    fn index_search_values(&mut self, previous: Option<&[u8]>, resource: &[u8]) -> Result<()> {
+        if self.header.search_root == 0 {
+            return Ok(())
+        }
+        let old = match previous {
+            Some(previous) => terms::keys(previous)?,
+            None => Vec::new()
+        };
+        let new = terms::keys(resource)?;
+        let mut root = self.header.search_root;
+        for key in old.iter().filter(|key| new.binary_search(key).is_err()) {
+            secondary::remove(self, root, key)?;
+        }
+        for key in new.iter().filter(|key| old.binary_search(key).is_err()) {
+            root = secondary::insert(self, root, key)?;
+        }
+        self.header.search_root = root;
+        Ok(())
+    }
+
+    /// Parses the json resource, writes it to the top page (or a newly allocated one, 
+    /// if it does not fit) and returns the [`Uuid`] assigned to it.
+    pub fn add_resource<D: Read>(&mut self, data: D) -> Result<Uuid> {
+        let mut tx = self.begin();
+        let id = tx.create(data)?;
+        tx.commit()?;
+        Ok(id)
+    }
+
+    /// Replaces the resource with the given id, or creates it with that id.
+    pub fn update_resource<D: Read>(&mut self, id: &str, data: D) -> Result<()> {
+        let mut tx = self.begin();
+        tx.update(id, data)?;
+        tx.commit()
+    }
+
+    /// Deletes the resource with the given id. Returns if there was such a resource.
+    pub fn delete_resource(&mut self, id: &str) -> Result<bool> {
+        let mut tx = self.begin();
+        let deleted = tx.delete(id)?;
+        tx.commit()?;
+        Ok(deleted)
+    }
+
+    // Writes the resource to the data page and points the index to it. The resource
+    // gets the next version and 'last_updated' stamped into its header and is linked
+    // to the version it replaces. Resources too large for a data page are written to
+    // overflow pages, the data page then only holds a reference to them.
+    pub(super) fn write_resource(&mut self, id: &Uuid, mut resource: Vec<u8>, last_updated: i64) -> Result<()> {
+        let previous = match index::search(self, self.header.index_root, id)? {
+            Some(loc) => {
+                ResourceHeader::link(&mut resource, loc.page, loc.offset);
+                Some(read_at(self, loc)?)
+            },
+            None => None
+        };
+        let version_id = match &previous {
+            Some(previous) => ResourceHeader::from_store(previous).version_id() + 1,
+            None => 1
+        };
+        ResourceHeader::stamp(&mut resource, version_id, last_updated);
+        let reference = match resource.len() > PAGE_SIZE - PAGE_HEADER_LEN {
+            true => {
+                let first = overflow::write(self, &resource)?;
+                Some(overflow::reference(&resource, first)?)
+            },
+            false => None
+        };
+        let stored = reference.as_deref().unwrap_or(&resource);
+        let mut page_num = self.header.data_page;
+        let mut frame = self.pager.pin(page_num)?;
+        let mut page_header = PageHeader::from_store(self.pager.page(frame));
+        if page_header.free_space(PAGE_SIZE) < stored.len() {
+            self.pager.unpin(frame);
+            page_num = self.allocate_page()?;
+            self.header.data_page = page_num;
+            frame = self.pager.pin(page_num)?;
+            page_header = PageHeader::from_store(self.pager.page(frame));
+        }
+        let offset = page_header.free_offset as usize;
+        page_header.num_resources += 1;
+        page_header.free_offset += stored.len() as u16;
+        let stored_header = page_header.to_store()?;
+        let page = self.pager.page_mut(frame);
+        page[offset..offset+stored.len()].copy_from_slice(stored);
+        page[..PAGE_HEADER_LEN].copy_from_slice(&stored_header);
+        self.pager.unpin(frame);
+        let loc = Location { page: page_num, offset: offset as u16 };
+        self.header.index_root = index::insert(self, self.header.index_root, id, loc)?;
+        self.index_search_values(previous.as_deref(), &resource)?;
+        self.flush_header()
+    }
+
+    // Writes a new version marking the resource as deleted. Returns if there 
+    // was such a resource.
+    pub(super) fn remove_resource(&mut self, id: &Uuid, last_updated: i64) -> Result<bool> {
+        let header = match index::search(self, self.header.index_root, id)? {
+            Some(loc) => ResourceHeader::from_store(&read_at(self, loc)?),
+            None => return Ok(false)
+        };
+        if header.is_deleted() {
+            return Ok(false)
+        }
+        let tombstone = ResourceHeader::tombstone(header.typ(), *id)?;
+        self.write_resource(id, tombstone, last_updated)?;
+        Ok(true)
+    }
+
+    // Looks up the resource in the primary index and returns it as stored,
+    // i.e. including its [`ResourceHeader`].
+    pub(super) fn find_resource(&mut self, id: &Uuid) -> Result<Option<Vec<u8>>> {
+        let root = self.header.index_root;
+        read_resource(self, root, id)
+    }
+
+    /// Returns the resource with the given id as json, or [`None`] if there is no such resource.
+    pub fn get_resource_by_id(&mut self, id: &str) -> Result<Option<String>> {
+        let id = parse_id(id)?;
+        match self.find_resource(&id)? {
+            Some(resource) => Ok(Some(stored_to_json(&resource)?)),
+            None => Ok(None)
+        }
+    }
+
+    /// Returns the version 'version_id' of the resource with the given id.
+    pub fn vread(&mut self, id: &str, version_id: u32) -> Result<Option<Version>> {
+        let id = parse_id(id)?;
+        let root = self.header.index_root;
+        history::version(self, root, &id, version_id)
+    }
+
+    /// Returns all versions of the resource with the given id, the latest first.
+    pub fn history(&mut self, id: &str) -> Result<Vec<Version>> {
+        let id = parse_id(id)?;
+        let root = self.header.index_root;
+        history::versions(self, root, &id)
+    }
+
+    /// Returns all versions of all resources of type 'typ', the latest first.
+    pub fn type_history(&mut self, typ: ResourceId) -> Result<Vec<Version>> {
+        let root = self.header.index_root;
+        history::all_versions(self, root, Some(typ))
+    }
+
+    /// Returns all versions of all resources, the latest first.
+    pub fn system_history(&mut self) -> Result<Vec<Version>> {
+        let root = self.header.index_root;
+        history::all_versions(self, root, None)
+    }
+
+    /// Returns the number of current resources of type 'typ' matching 'query' and the
+    /// [`ResourceHeader`] and json (with 'id' and 'meta') of them, at most `query.count`.
+    pub fn search(&mut self, typ: ResourceId, query: &Query) -> Result<(usize, Vec<(ResourceHeader, String)>)> {
+        let (index_root, search_root) = (self.header.index_root, self.header.search_root);
+        let (total, found) = query::search(self, index_root, search_root, &typ, query)?;
+        Ok((total, to_json(&found)?))
+    }
+
+    /// Returns the [`ResourceHeader`] and the json of the resource with the given id. 
+    /// Unlike [`Store::get_resource_by_id`], the json includes 'id' and 'meta'.
+    pub fn get_resource(&mut self, id: &str) -> Result<Option<(ResourceHeader, String)>> {
+        let id = parse_id(id)?;
+        match self.find_resource(&id)? {
+            Some(resource) => Ok(Some((ResourceHeader::from_store(&resource), resource_to_json(&resource)?))),
+            None => Ok(None)
+        }
+    }
+}
+
+
+
+
+/// Looks up the resource in the index at 'root' and reads its latest version, including 
+/// the [`ResourceHeader`]. Returns [`None`] if there is no such resource or it was deleted.
+pub fn read_resource<P: Pages>(pages: &mut P, root: u16, id: &Uuid) -> Result<Option<Vec<u8>>> {
+    let loc = match index::search(pages, root, id)? {
+        Some(loc) => loc,
+        None => return Ok(None)
+    };
+    let stored = read_at(pages, loc)?;
+    let header = ResourceHeader::from_store(&stored);
+    if &header.id() != id {
+        return Err(Error::SegmentationFault)
     }
+    match header.is_deleted() {
+        true => Ok(None),
+        false => Ok(Some(stored))
+    }
+}
 
-    pub fn get_page_copy(&mut self, num: usize) -> Vec<u8> {
-        let start = num * PAGE_SIZE;
-        self.file.seek(std::io::SeekFrom::Start(start as u64)).unwrap();
-        let mut buf = [0u8; PAGE_SIZE];
-        self.file.read_exact(&mut buf).unwrap();
-        buf.to_vec()
-    }
-
-    pub fn add_resource() {}
-
-
-    //pub fn create_resource_from_json<D: Read>(&mut self, resource_id: &str, mut data: D) -> Result<()> {
-    //    let mut buf = Vec::new();
-    //    match data.read_to_end(&mut buf) {
-    //        Ok(_) => {
-    //            let page_num = self.header.inc_top();
-    //            let mut parser = JsonParser::new_from_slice(&buf, resource_id, page_num as usize)?;
-    //            parser.parse()?;
-    //            let ptr = parser.flush();
-    //            let len = parser.len();
-    //            println!("LENGTH {}", len);
-    //            let page_offset = (page_num - 1) * PAGE_SIZE as u16;
-    //            //TODO ERROR
-    //            println!("OFFSET: {page_offset}");
-    //            self.file.seek(std::io::SeekFrom::Start(page_offset as u64)).unwrap();
-    //            //TODO make sure mem size is not longer than page size
-    //            let buf: &[u8] = unsafe {
-    //                let buf = std::ptr::slice_from_raw_parts(ptr, len);
-    //                &*buf
-    //            };
-    //            println!("BUFFER: {:?}", buf);
-    //            
-    //            self.file.write_all(buf).unwrap();
-    //            self.file.sync_data().unwrap();
-    //            Ok(())
-    //        },
-    //        Err(err) => Err(Error::Custom(err.to_string()))
-    //    }
-    //}
-
-    pub fn get_resource_by_id(&mut self, id: &str) {
-        todo!()
+/// Returns the [`ResourceHeader`] and the json (with 'id' and 'meta') of stored resources.
+pub fn to_json(found: &[Vec<u8>]) -> Result<Vec<(ResourceHeader, String)>> {
+    found.iter()
+        .map(|stored| Ok((ResourceHeader::from_store(stored), resource_to_json(stored)?)))
+        .collect()
+}
+
+/// Parses the id of a resource.
+pub fn parse_id(id: &str) -> Result<Uuid> {
+    match Uuid::parse_str(id) {
+        Ok(id) => Ok(id),
+        Err(_) => Err(Error::Conversion(id.to_string(), "Uuid".to_string()))
     }
 }
 
+impl Drop for Store {
+    fn drop(&mut self) {
+        // a failed checkpoint leaves the pages in the wal, use close to see the error
+        let _ = self.checkpoint();
+    }
+}
 
+impl Pages for Store {
+    fn read_page(&mut self, num: u16) -> Result<Vec<u8>> {
+        self.pager.read_page(num)
+    }
+
+    fn write_page(&mut self, num: u16, page: &[u8]) -> Result<()> {
+        self.pager.write_page(num, page)
+    }
+
+    // Moves the top page up by one and grows the file, if the new top page
+    // is beyond the current number of pages.
+    fn allocate_page(&mut self) -> Result<u16> {
+        let top = self.header.inc_top()?;
+        if top >= self.header.num_pages {
+            self.header.num_pages = top + 1;
+        }
+        self.pager.write_page(top, &[0u8; PAGE_SIZE])?;
+        self.flush_header()?;
+        Ok(top)
+    }
+}
 
 
 #[derive(Debug)]
 struct StoreHeader {
     num_pages: u16,
     page_size: u16,
-    top_page: u16
+    top_page: u16,
+    index_root: u16,
+    data_page: u16,
+    search_root: u16
 } 
 
 
 /// Header of the db main file. For now, this only includes 
-/// number of pages, page size and top_page. However, the header is one page long.
+/// number of pages, page size, top_page (the last allocated page), the root of the 
+/// primary index, the page resources are currently written to and the root of the
+/// secondary indexes. However, the header is one page long.
 ///
 /// Layout:
 ///
-/// |TYPE|num pages|page size|top page|...           |
-/// |----|---------|---------|--------|--------------|
-/// |LEN |2        |2        |2       |page size - 4 |
+/// |TYPE|num pages|page size|top page|index root|data page|search root|...            |
+/// |----|---------|---------|--------|----------|---------|-----------|---------------|
+/// |LEN |2        |2        |2       |2         |2        |2          |page size - 12 |
 impl StoreHeader {
     fn new(num_pages: u16) -> Self {
         Self {
             num_pages,
             page_size: PAGE_SIZE as u16,
-            top_page: 1
+            top_page: 1,
+            index_root: 0,
+            data_page: 1,
+            search_root: 0
         }
     }
     
@@ -148,10 +485,20 @@ impl StoreHeader {
         size[1] = head[3]; 
         top[0] = head[4]; 
         top[1] = head[5]; 
+        let top_page = u16::from_be_bytes(top);
+        let index_root = u16::from_be_bytes([head[6], head[7]]);
+        // files written before the index existed only know the top page
+        let data_page = match u16::from_be_bytes([head[8], head[9]]) {
+            0 => top_page,
+            page => page
+        };
         Self {
             num_pages: u16::from_be_bytes(num),
             page_size: u16::from_be_bytes(size),
-            top_page: u16::from_be_bytes(top)
+            top_page,
+            index_root,
+            data_page,
+            search_root: u16::from_be_bytes([head[10], head[11]])
         }
     }
 
@@ -160,6 +507,9 @@ impl StoreHeader {
         let num = self.num_pages.to_be_bytes();
         let size = self.page_size.to_be_bytes();
         let top = self.top_page.to_be_bytes();
+        let root = self.index_root.to_be_bytes();
+        let data = self.data_page.to_be_bytes();
+        let search = self.search_root.to_be_bytes();
         unsafe {
             buf.write(num[0]);   
             buf.add(1).write(num[1]);   
@@ -167,12 +517,24 @@ impl StoreHeader {
             buf.add(3).write(size[1]);   
             buf.add(4).write(top[0]);   
             buf.add(5).write(top[1]);   
+            buf.add(6).write(root[0]);   
+            buf.add(7).write(root[1]);   
+            buf.add(8).write(data[0]);   
+            buf.add(9).write(data[1]);   
+            buf.add(10).write(search[0]);   
+            buf.add(11).write(search[1]);   
         }
     }
 
-    fn inc_top(&mut self) -> u16 {
-        self.top_page+=1;
-        self.top_page
+    // The number of pages is one more than the top page, so both have to fit into a u16.
+    fn inc_top(&mut self) -> Result<u16> {
+        match self.top_page.checked_add(1) {
+            Some(top) if top < u16::MAX => {
+                self.top_page = top;
+                Ok(top)
+            },
+            _ => Err(Error::StoreFull)
+        }
     }
 }
 
@@ -181,14 +543,276 @@ impl StoreHeader {
 mod test {
 
     use super::*;
+    use crate::parser::json::resource_from_json;
+    use crate::store::test::TempStore;
 
     #[test]
     fn store_open_store() {
-        let store = Store::open().unwrap();
+        let path = TempStore::new("open");
+        let store = Store::open_path(&path).unwrap();
         assert_eq!(store.header.num_pages, INIT_PAGES as u16);
         assert_eq!(store.header.page_size, PAGE_SIZE as u16);
     }
-}
 
+    #[test]
+    fn store_add_and_get_resource() {
+        let path = TempStore::new("add_get");
+        let mut store = Store::open_path(&path).unwrap();
+        let data = br#"{"resourceType": "patient", "active": true, "name": [{"family": "Chalmers", "given": ["Peter", "James"]}]}"#;
+        let id = store.add_resource(&data[..]).unwrap();
+        let other = store.add_resource(&br#"{"resourceType": "patient", "active": false}"#[..]).unwrap();
+        assert_eq!(
+            store.get_resource_by_id(&id.to_string()).unwrap().unwrap(), 
+            r#"{"resourceType":"patient","active":true,"name":[{"family":"Chalmers","given":["Peter","James"]}]}"#
+        );
+        assert_eq!(
+            store.get_resource_by_id(&other.to_string()).unwrap().unwrap(), 
+            r#"{"resourceType":"patient","active":false}"#
+        );
+        assert!(store.get_resource_by_id(&Uuid::new_v4().to_string()).unwrap().is_none());
+        assert!(store.get_resource_by_id("not-a-uuid").is_err());
+        drop(store);
+
+        let mut store = Store::open_path(&path).unwrap();
+        assert!(store.get_resource_by_id(&id.to_string()).unwrap().is_some());
+    }
+
+    #[test]
+    fn store_allocates_pages() {
+        let path = TempStore::new("allocate");
+        let mut store = Store::open_path(&path).unwrap();
+        let data = br#"{"resourceType": "patient", "text": {"status": "generated", "div": "<div xmlns=\"http://www.w3.org/1999/xhtml\">Some longer narrative to fill up the pages a little faster</div>"}}"#;
+        let ids: Vec<Uuid> = (0..100).map(|_| store.add_resource(&data[..]).unwrap()).collect();
+        assert!(store.header.top_page > INIT_PAGES as u16);
+        assert!(store.header.data_page > INIT_PAGES as u16);
+        assert_eq!(store.header.num_pages, store.header.top_page + 1);
+        drop(store);
+
+        let mut store = Store::open_path(&path).unwrap();
+        for id in ids.iter() {
+            assert!(store.get_resource_by_id(&id.to_string()).unwrap().is_some());
+        }
+    }
+
+    #[test]
+    fn store_refuses_pages_past_max() {
+        let path = TempStore::new("full");
+        let mut store = Store::open_path(&path).unwrap();
+        store.header.top_page = u16::MAX - 1;
+        let num_pages = store.header.num_pages;
+        assert!(matches!(store.allocate_page(), Err(Error::StoreFull)));
+        assert_eq!(store.header.top_page, u16::MAX - 1);
+        assert_eq!(store.header.num_pages, num_pages);
+    }
+
+    #[test]
+    fn store_builds_missing_index() {
+        // a file as it was written before the index existed: header and one data page
+        let path = TempStore::new("rebuild");
+        let mut file = [0u8; PAGE_SIZE * INIT_PAGES];
+        let mut header = StoreHeader::new(INIT_PAGES as u16);
+        header.data_page = 0;
+        header.flush_init(file.as_mut_ptr());
+        let mut page_header = PageHeader::new();
+        let mut ids = Vec::new();
+        for _ in 0..50 {
+            let (id, resource) = resource_from_json(br#"{"resourceType": "patient", "active": true}"#).unwrap();
+            let offset = PAGE_SIZE + page_header.free_offset as usize;
+            file[offset..offset+resource.len()].copy_from_slice(&resource);
+            page_header.num_resources += 1;
+            page_header.free_offset += resource.len() as u16;
+            ids.push(id);
+        }
+        file[PAGE_SIZE..PAGE_SIZE+PAGE_HEADER_LEN].copy_from_slice(&page_header.to_store().unwrap());
+        std::fs::write(&path, file).unwrap();
+
+        let mut store = Store::open_path(&path).unwrap();
+        assert_ne!(store.header.index_root, 0);
+        assert_eq!(store.header.data_page, 1);
+        for id in ids.iter() {
+            assert!(store.get_resource_by_id(&id.to_string()).unwrap().is_some());
+        }
+        let id = store.add_resource(&br#"{"resourceType": "patient"}"#[..]).unwrap();
+        assert!(store.get_resource_by_id(&id.to_string()).unwrap().is_some());
+    }
+
+    #[test]
+    fn store_recovers_from_wal() {
+        let path = TempStore::new("recover");
+        let mut store = Store::open_path(&path).unwrap();
+        let ids: Vec<Uuid> = (0..20).map(|_| store.add_resource(&br#"{"resourceType": "patient", "active": true}"#[..]).unwrap()).collect();
+        // crash: no checkpoint happens and none of the pages made it into the main file
+        std::mem::forget(store);
+        let len = std::fs::metadata(&path).unwrap().len() as usize;
+        std::fs::write(&path, vec![0u8; len]).unwrap();
+
+        let mut store = Store::open_path(&path).unwrap();
+        for id in ids.iter() {
+            assert!(store.get_resource_by_id(&id.to_string()).unwrap().is_some());
+        }
+        store.close().unwrap();
+        assert_eq!(std::fs::metadata(path.wal()).unwrap().len(), 0);
+    }
+
+    #[test]
+    fn store_rolls_back_pages() {
+        let path = TempStore::new("rollback");
+        let mut store = Store::open_path(&path).unwrap();
+        let top = store.header.top_page;
+        let page = store.allocate_page().unwrap();
+        store.write_page(page, &[1u8; PAGE_SIZE]).unwrap();
+        assert!(!store.pager.dirty_pages().is_empty());
+        store.rollback().unwrap();
+        assert_eq!(store.header.top_page, top);
+        assert!(store.pager.dirty_pages().is_empty());
+    }
 
+    #[test]
+    fn store_keeps_versions() {
+        let path = TempStore::new("versions");
+        let mut store = Store::open_path(&path).unwrap();
+        let id = store.add_resource(&br#"{"resourceType": "patient", "active": true}"#[..]).unwrap().to_string();
+        store.update_resource(&id, &br#"{"resourceType": "patient", "active": false}"#[..]).unwrap();
+        assert!(store.delete_resource(&id).unwrap());
+        assert!(!store.delete_resource(&id).unwrap());
+        assert!(store.get_resource_by_id(&id).unwrap().is_none());
 
+        let versions = store.history(&id).unwrap();
+        assert_eq!(versions.iter().map(|v| v.header.version_id()).collect::<Vec<u32>>(), vec![3, 2, 1]);
+        assert!(versions[0].json.is_none());
+        let first = store.vread(&id, 1).unwrap().unwrap();
+        assert!(first.json.unwrap().ends_with(r#""active":true}"#));
+        assert!(store.vread(&id, 4).unwrap().is_none());
+        assert_eq!(store.system_history().unwrap().len(), 3);
+        assert_eq!(store.type_history(ResourceId::Patient).unwrap().len(), 3);
+        assert!(store.type_history(ResourceId::Empty).unwrap().is_empty());
+        drop(store);
+    }
+
+    #[test]
+    fn store_maintains_search_index() {
+        let path = TempStore::new("search_index");
+        let mut store = Store::open_path(&path).unwrap();
+        let id = store.add_resource(&br#"{"resourceType": "patient", "identifier": [{"system": "urn:a", "value": "1"}]}"#[..]).unwrap();
+        store.add_resource(&br#"{"resourceType": "patient", "identifier": [{"system": "urn:a", "value": "2"}]}"#[..]).unwrap();
+
+        // number of keys the secondary index holds for the query
+        fn indexed(store: &mut Store, query: &str) -> usize {
+            let query = Query::parse(&ResourceId::Patient, query).unwrap();
+            let root = store.header.search_root;
+            let mut found = 0;
+            for (from, to) in terms::ranges(&ResourceId::Patient, &query.criteria[0]).unwrap().unwrap() {
+                found += secondary::range(store, root, &from, &to).unwrap().len();
+            }
+            found
+        }
+        let search = |store: &mut Store, query: &str| {
+            store.search(ResourceId::Patient, &Query::parse(&ResourceId::Patient, query).unwrap()).unwrap().0
+        };
+        assert_eq!(indexed(&mut store, "identifier=1"), 1);
+        assert_eq!(indexed(&mut store, "identifier=urn:a|"), 2);
+        assert_eq!(search(&mut store, "identifier=urn:a|1"), 1);
+        let query = Query::parse(&ResourceId::Patient, "identifier=urn:a|&_count=1").unwrap();
+        let (total, found) = store.search(ResourceId::Patient, &query).unwrap();
+        assert_eq!((total, found.len()), (2, 1));
+
+        store.update_resource(&id.to_string(), &br#"{"resourceType": "patient", "identifier": [{"system": "urn:a", "value": "3"}]}"#[..]).unwrap();
+        assert_eq!(indexed(&mut store, "identifier=1"), 0);
+        assert_eq!(indexed(&mut store, "identifier=3"), 1);
+        assert_eq!(search(&mut store, "identifier=1"), 0);
+        assert_eq!(search(&mut store, "identifier=3"), 1);
+
+        assert!(store.delete_resource(&id.to_string()).unwrap());
+        assert_eq!(indexed(&mut store, "identifier=3"), 0);
+        assert_eq!(indexed(&mut store, "identifier=urn:a|"), 1);
+        drop(store);
+
+        // the index is kept in the file, a missing one is rebuilt
+        let mut store = Store::open_path(&path).unwrap();
+        assert_eq!(indexed(&mut store, "identifier=2"), 1);
+        store.header.search_root = 0;
+        store.flush_header().unwrap();
+        store.commit().unwrap();
+        drop(store);
+        let mut store = Store::open_path(&path).unwrap();
+        assert_ne!(store.header.search_root, 0);
+        assert_eq!(indexed(&mut store, "identifier=urn:a|"), 1);
+        assert_eq!(search(&mut store, "identifier=2"), 1);
+        drop(store);
+    }
+
+    #[test]
+    fn store_large_resources() {
+        let path = TempStore::new("large");
+        let mut store = Store::open_path(&path).unwrap();
+        // far more pages than frames in the pool, they are spilled to the wal
+        let mut ids = Vec::new();
+        for size in [300_000, 600_000] {
+            let names: Vec<String> = (0..size / 2000).map(|i| format!(r#"{{"family": "Family{i}{}"}}"#, "x".repeat(1980))).collect();
+            let large = format!(r#"{{"resourceType": "patient", "name": [{}]}}"#, names.join(","));
+            ids.push((store.add_resource(large.as_bytes()).unwrap().to_string(), names.len()));
+        }
+        drop(store);
+
+        let mut store = Store::open_path(&path).unwrap();
+        for (id, num) in ids.iter() {
+            let json = store.get_resource_by_id(id).unwrap().unwrap();
+            assert!(json.len() > num * 1980);
+            assert!(json.ends_with(&format!(r#"{{"family":"Family{}{}"}}]}}"#, num - 1, "x".repeat(1980))));
+        }
+        drop(store);
+    }
+
+    #[test]
+    fn store_large_transaction() {
+        let path = TempStore::new("large_tx");
+        let mut store = Store::open_path(&path).unwrap();
+        let mut tx = store.begin();
+        let ids: Vec<String> = (0..400)
+            .map(|i| {
+                let patient = format!(r#"{{"resourceType": "patient", "name": [{{"family": "Family{i}{}"}}]}}"#, "x".repeat(2000));
+                tx.create(patient.as_bytes()).unwrap().to_string()
+            })
+            .collect();
+        tx.commit().unwrap();
+        for id in ids.iter() {
+            assert!(store.get_resource_by_id(id).unwrap().is_some());
+        }
+        let query = Query::parse(&ResourceId::Patient, "family=family399").unwrap();
+        assert_eq!(store.search(ResourceId::Patient, &query).unwrap().0, 1);
+
+        drop(store);
+
+        let mut store = Store::open_path(&path).unwrap();
+        assert!(ids.iter().all(|id| store.get_resource_by_id(id).unwrap().is_some()));
+        drop(store);
+    }
+
+    #[test]
+    fn store_overflow_pages() {
+        let path = TempStore::new("overflow");
+        let mut store = Store::open_path(&path).unwrap();
+        let names: Vec<String> = (0..8).map(|i| format!(r#"{{"family": "Family{i}{}", "given": ["Given{i}"]}}"#, "x".repeat(1500))).collect();
+        let large = format!(r#"{{"resourceType": "patient", "active": true, "name": [{}]}}"#, names.join(","));
+        let id = store.add_resource(large.as_bytes()).unwrap().to_string();
+        let small = store.add_resource(&br#"{"resourceType": "patient", "active": false}"#[..]).unwrap().to_string();
+        let json = store.get_resource_by_id(&id).unwrap().unwrap();
+        assert!(json.len() > 3 * PAGE_SIZE);
+        assert!(json.ends_with(r#"x","given":["Given7"]}]}"#));
+        let query = Query::parse(&ResourceId::Patient, "family=family3").unwrap();
+        assert_eq!(store.search(ResourceId::Patient, &query).unwrap().0, 1);
+
+        // versions on overflow pages are kept like all others
+        store.update_resource(&id, &br#"{"resourceType": "patient", "active": false}"#[..]).unwrap();
+        assert!(store.vread(&id, 1).unwrap().unwrap().json.unwrap().contains("Family7"));
+        assert_eq!(store.history(&id).unwrap().len(), 2);
+        assert_eq!(store.search(ResourceId::Patient, &query).unwrap().0, 0);
+        drop(store);
+
+        let mut store = Store::open_path(&path).unwrap();
+        assert!(store.vread(&id, 1).unwrap().unwrap().json.unwrap().contains("Family7"));
+        assert_eq!(store.get_resource_by_id(&id).unwrap().unwrap(), r#"{"resourceType":"patient","active":false}"#);
+        assert!(store.get_resource_by_id(&small).unwrap().is_some());
+        drop(store);
+    }
+}
@@ -0,0 +1,222 @@
+use std::io::Read;
+use indexmap::IndexMap;
+use chrono::Utc;
+use uuid::Uuid;
+use crate::error::{Result, Error};
+use crate::parser::json::{resource_from_json, resource_from_json_with_id};
+use crate::parser::serializer::stored_to_json;
+use crate::resourcetypes::ResourceId;
+use super::header::Head;
+use super::resourcewriter::ResourceHeader;
+use super::store::{Store, parse_id};
+
+
+/// Handle returned by [`Store::begin`]. Creates, updates and deletes are only staged
+/// in memory, nothing touches the pages of the store until [`Transaction::commit`].
+/// There all staged changes are applied and made durable by a single commit to the
+/// [`super::wal::Wal`], so either all of them or none survive a crash.
+///
+/// While the transaction is open it holds the store exclusively, reads through the
+/// transaction see its own staged changes. Dropping the transaction without commit
+/// discards the staged changes.
+#[derive(Debug)]
+pub struct Transaction<'s> {
+    store: &'s mut Store,
+    // 'None' marks a deleted resource
+    staged: IndexMap<Uuid, Option<Vec<u8>>>
+}
+
+impl<'s> Transaction<'s> {
+    pub(super) fn new(store: &'s mut Store) -> Self {
+        Self {
+            store,
+            staged: IndexMap::new()
+        }
+    }
+
+    /// Stages a new resource and returns the [`Uuid`] assigned to it.
+    pub fn create<D: Read>(&mut self, data: D) -> Result<Uuid> {
+        let (id, resource) = resource_from_json(&read_all(data)?)?;
+        self.staged.insert(id, Some(resource));
+        Ok(id)
+    }
+
+    /// Stages a new version of the resource with the given id. If there is no such
+    /// resource, it is created with that id.
+    pub fn update<D: Read>(&mut self, id: &str, data: D) -> Result<()> {
+        let id = parse_id(id)?;
+        let resource = resource_from_json_with_id(&read_all(data)?, &id)?;
+        self.staged.insert(id, Some(resource));
+        Ok(())
+    }
+
+    /// Stages the deletion of the resource with the given id. Returns if there was
+    /// such a resource.
+    pub fn delete(&mut self, id: &str) -> Result<bool> {
+        let id = parse_id(id)?;
+        let exists = self.find_resource(&id)?.is_some();
+        if exists {
+            self.staged.insert(id, None);
+        }
+        Ok(exists)
+    }
+
+    /// Returns the resource with the given id as json, including the changes staged so far.
+    pub fn get_resource_by_id(&mut self, id: &str) -> Result<Option<String>> {
+        let id = parse_id(id)?;
+        match self.find_resource(&id)? {
+            Some(resource) => Ok(Some(stored_to_json(&resource)?)),
+            None => Ok(None)
+        }
+    }
+
+    /// Returns the type of the resource with the given id, including the changes staged so far.
+    pub fn get_resource_type(&mut self, id: &str) -> Result<Option<ResourceId>> {
+        let id = parse_id(id)?;
+        Ok(self.find_resource(&id)?.map(|resource| ResourceHeader::from_store(&resource).typ()))
+    }
+
+    /// Returns the number of staged changes.
+    pub fn len(&self) -> usize {
+        self.staged.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.staged.is_empty()
+    }
+
+    /// Applies all staged changes. If any of them fails, the store is left as it was
+    /// before the transaction.
+    pub fn commit(self) -> Result<()> {
+        let store = self.store;
+        let now = Utc::now().timestamp_millis();
+        for (id, resource) in self.staged.into_iter() {
+            let applied = match resource {
+                Some(resource) => store.write_resource(&id, resource, now),
+                None => store.remove_resource(&id, now).map(|_| ())
+            };
+            if let Err(err) = applied {
+                store.rollback()?;
+                return Err(err)
+            }
+        }
+        store.commit()
+    }
+
+    /// Discards all staged changes, the same as dropping the transaction.
+    pub fn rollback(self) {}
+
+    fn find_resource(&mut self, id: &Uuid) -> Result<Option<Vec<u8>>> {
+        match self.staged.get(id) {
+            Some(Some(resource)) => Ok(Some(resource.clone())),
+            Some(None) => Ok(None),
+            None => self.store.find_resource(id)
+        }
+    }
+}
+
+
+fn read_all<D: Read>(mut data: D) -> Result<Vec<u8>> {
+    let mut buf = Vec::new();
+    if let Err(err) = data.read_to_end(&mut buf) {
+        return Err(Error::Custom(err.to_string()))
+    }
+    Ok(buf)
+}
+
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::resourcetypes::ResourceId;
+    use crate::store::resourcewriter::ResourceHeader;
+    use crate::store::test::TempStore;
+
+    #[test]
+    fn transaction_commit() {
+        let path = TempStore::new("tx_commit");
+        let mut store = Store::open_path(&path).unwrap();
+        let existing = store.add_resource(&br#"{"resourceType": "patient", "active": true}"#[..]).unwrap().to_string();
+        let removed = store.add_resource(&br#"{"resourceType": "patient", "active": true}"#[..]).unwrap().to_string();
+
+        let mut tx = store.begin();
+        let created = tx.create(&br#"{"resourceType": "patient", "gender": "female"}"#[..]).unwrap().to_string();
+        tx.update(&existing, &br#"{"resourceType": "patient", "active": false}"#[..]).unwrap();
+        assert!(tx.delete(&removed).unwrap());
+        assert!(!tx.delete(&Uuid::new_v4().to_string()).unwrap());
+        assert_eq!(tx.len(), 3);
+        // the transaction sees its own changes
+        assert_eq!(tx.get_resource_by_id(&existing).unwrap().unwrap(), r#"{"resourceType":"patient","active":false}"#);
+        assert!(tx.get_resource_by_id(&removed).unwrap().is_none());
+        tx.commit().unwrap();
+
+        assert_eq!(store.get_resource_by_id(&created).unwrap().unwrap(), r#"{"resourceType":"patient","gender":"female"}"#);
+        assert_eq!(store.get_resource_by_id(&existing).unwrap().unwrap(), r#"{"resourceType":"patient","active":false}"#);
+        assert!(store.get_resource_by_id(&removed).unwrap().is_none());
+        drop(store);
+
+        let mut store = Store::open_path(&path).unwrap();
+        assert_eq!(store.get_resource_by_id(&existing).unwrap().unwrap(), r#"{"resourceType":"patient","active":false}"#);
+        assert!(store.get_resource_by_id(&removed).unwrap().is_none());
+        drop(store);
+    }
+
+    #[test]
+    fn transaction_rollback() {
+        let path = TempStore::new("tx_rollback");
+        let mut store = Store::open_path(&path).unwrap();
+        let existing = store.add_resource(&br#"{"resourceType": "patient", "active": true}"#[..]).unwrap().to_string();
+
+        let mut tx = store.begin();
+        let created = tx.create(&br#"{"resourceType": "patient", "active": false}"#[..]).unwrap().to_string();
+        tx.delete(&existing).unwrap();
+        tx.rollback();
+        assert!(store.get_resource_by_id(&created).unwrap().is_none());
+        assert!(store.get_resource_by_id(&existing).unwrap().is_some());
+
+        // a failing change discards the ones applied before it
+        let mut tx = store.begin();
+        let created = tx.create(&br#"{"resourceType": "patient", "active": false}"#[..]).unwrap().to_string();
+        let mut broken = ResourceHeader::tombstone(ResourceId::Patient, Uuid::new_v4()).unwrap();
+        broken.extend([5, 1]);
+        let len = broken.len() as u16;
+        broken[..2].copy_from_slice(&len.to_be_bytes());
+        tx.staged.insert(Uuid::new_v4(), Some(broken));
+        tx.delete(&existing).unwrap();
+        assert!(tx.commit().is_err());
+        assert!(store.get_resource_by_id(&created).unwrap().is_none());
+        assert!(store.get_resource_by_id(&existing).unwrap().is_some());
+        drop(store);
+    }
+
+    #[test]
+    fn transaction_rollback_large() {
+        let path = TempStore::new("tx_rollback_large");
+        let mut store = Store::open_path(&path).unwrap();
+        let existing = store.add_resource(&br#"{"resourceType": "patient", "active": true}"#[..]).unwrap().to_string();
+
+        // more pages than the pool holds, a failure still discards all of them
+        let mut tx = store.begin();
+        let created: Vec<String> = (0..400)
+            .map(|i| {
+                let patient = format!(r#"{{"resourceType": "patient", "name": [{{"family": "Family{i}{}"}}]}}"#, "x".repeat(2000));
+                tx.create(patient.as_bytes()).unwrap().to_string()
+            })
+            .collect();
+        let mut broken = ResourceHeader::tombstone(ResourceId::Patient, Uuid::new_v4()).unwrap();
+        broken.extend([5, 1]);
+        let len = broken.len() as u16;
+        broken[..2].copy_from_slice(&len.to_be_bytes());
+        tx.staged.insert(Uuid::new_v4(), Some(broken));
+        assert!(tx.commit().is_err());
+        assert!(created.iter().all(|id| store.get_resource_by_id(id).unwrap().is_none()));
+        assert!(store.get_resource_by_id(&existing).unwrap().is_some());
+        drop(store);
+
+        let mut store = Store::open_path(&path).unwrap();
+        assert!(created.iter().all(|id| store.get_resource_by_id(id).unwrap().is_none()));
+        assert!(store.get_resource_by_id(&existing).unwrap().is_some());
+        drop(store);
+    }
+}
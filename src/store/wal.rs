@@ -0,0 +1,279 @@
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Write, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use crate::error::{Result, Error};
+use super::store::PAGE_SIZE;
+
+
+const MAGIC: &[u8; 8] = b"FHIRWAL\0";
+const WAL_HEADER_LEN: usize = 16;
+const FRAME_HEADER_LEN: usize = 8;
+const PAGE_FRAME: u8 = 1;
+const COMMIT_FRAME: u8 = 2;
+
+
+/// Write-ahead log of the store. Before pages reach the main file, their images are
+/// appended here and made durable by a commit frame. Committed pages are replayed into
+/// the main file after an unclean shutdown. Pages the [`super::pager::Pager`] has to
+/// evict during a transaction are appended early by [`Wal::append`], they only count
+/// once the commit frame of their transaction follows.
+///
+/// Layout Header:
+/// |Num Bytes |8     |2         |6        |
+/// |----------|------|----------|---------|
+/// |          |MAGIC |page size |reserved |
+///
+/// Layout Frame:
+/// |Num Bytes |1     |1        |2                |4         |PAGE_SIZE (page frames only) |
+/// |----------|------|---------|-----------------|----------|-----------------------------|
+/// |          |kind  |reserved |page / num pages |checksum  |page image                   |
+///
+/// The checksum of a commit frame combines the checksums of all page frames of its transaction.
+#[derive(Debug)]
+pub struct Wal {
+    file: File,
+    path: PathBuf,
+    len: u64,
+    frames: usize,
+    // length of the log up to the last commit frame
+    committed: u64,
+    // page frames appended since the last commit frame and their combined checksum
+    pending: usize,
+    combined: u32
+}
+
+impl Wal {
+    /// Opens (or creates) the log that belongs to the store file at 'store_path'.
+    pub fn open<P: AsRef<Path>>(store_path: P) -> Result<Self> {
+        let mut path = store_path.as_ref().as_os_str().to_owned();
+        path.push("-wal");
+        let path = PathBuf::from(path);
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&path)?;
+        let len = file.metadata()?.len();
+        Ok(Self {
+            file,
+            path,
+            len,
+            frames: 0,
+            committed: len,
+            pending: 0,
+            combined: 0
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the number of page frames written since the last checkpoint.
+    pub fn frames(&self) -> usize {
+        self.frames
+    }
+
+    /// Appends the image of page 'page' to the transaction in progress, without syncing.
+    /// Returns the position of the image, see [`Wal::read_page`].
+    pub fn append(&mut self, page: u16, data: &[u8]) -> Result<u64> {
+        let mut buf = Vec::with_capacity(WAL_HEADER_LEN + FRAME_HEADER_LEN + PAGE_SIZE);
+        self.push_page(&mut buf, page, data)?;
+        self.file.seek(SeekFrom::Start(self.len))?;
+        if let Err(err) = self.file.write_all(&buf) {
+            let _ = self.file.set_len(self.len);
+            return Err(err.into())
+        }
+        self.len += buf.len() as u64;
+        self.pending += 1;
+        Ok(self.len - PAGE_SIZE as u64)
+    }
+
+    /// Reads a page image written by [`Wal::append`].
+    pub fn read_page(&mut self, pos: u64) -> Result<Vec<u8>> {
+        let mut data = vec![0u8; PAGE_SIZE];
+        self.file.seek(SeekFrom::Start(pos))?;
+        self.file.read_exact(&mut data)?;
+        Ok(data)
+    }
+
+    /// Appends the page images followed by a commit frame and syncs the log. The commit
+    /// includes the pages appended before by [`Wal::append`]. Only after this returns the
+    /// pages may be written to the main file.
+    pub fn commit(&mut self, pages: &[(u16, Vec<u8>)]) -> Result<()> {
+        if pages.is_empty() && self.pending == 0 {
+            return Ok(())
+        }
+        let num = u16::try_from(self.pending + pages.len()).map_err(|_| Error::BufferOverflow)?;
+        let mut buf = Vec::with_capacity(pages.len() * (FRAME_HEADER_LEN + PAGE_SIZE) + FRAME_HEADER_LEN + WAL_HEADER_LEN);
+        for (page, data) in pages.iter() {
+            self.push_page(&mut buf, *page, data)?;
+        }
+        buf.extend([COMMIT_FRAME, 0]);
+        buf.extend(num.to_be_bytes());
+        buf.extend(self.combined.to_be_bytes());
+
+        self.file.seek(SeekFrom::Start(self.len))?;
+        if let Err(err) = self.file.write_all(&buf).and_then(|_| self.file.sync_data()) {
+            // get rid of the partially written transaction
+            self.rollback()?;
+            return Err(err.into())
+        }
+        self.len += buf.len() as u64;
+        self.committed = self.len;
+        self.frames += num as usize;
+        self.pending = 0;
+        self.combined = 0;
+        Ok(())
+    }
+
+    /// Drops the pages appended since the last commit.
+    pub fn rollback(&mut self) -> Result<()> {
+        self.file.set_len(self.committed)?;
+        self.len = self.committed;
+        self.pending = 0;
+        self.combined = 0;
+        Ok(())
+    }
+
+    // Adds a page frame to 'buf', preceded by the header if the log is still empty.
+    fn push_page(&mut self, buf: &mut Vec<u8>, page: u16, data: &[u8]) -> Result<()> {
+        if data.len() != PAGE_SIZE {
+            return Err(Error::BufferOverflow)
+        }
+        if self.len == 0 && buf.is_empty() {
+            buf.extend(MAGIC);
+            buf.extend((PAGE_SIZE as u16).to_be_bytes());
+            buf.extend([0u8; 6]);
+        }
+        let sum = checksum(page, data);
+        self.combined = combine(self.combined, sum);
+        buf.extend([PAGE_FRAME, 0]);
+        buf.extend(page.to_be_bytes());
+        buf.extend(sum.to_be_bytes());
+        buf.extend(data);
+        Ok(())
+    }
+
+    /// Reads all completely committed transactions and returns their pages in log order.
+    /// Everything after the last valid commit frame (a torn write or a transaction that
+    /// was never committed) is ignored.
+    pub fn recover(&mut self) -> Result<Vec<(u16, Vec<u8>)>> {
+        let mut data = Vec::new();
+        self.file.seek(SeekFrom::Start(0))?;
+        self.file.read_to_end(&mut data)?;
+        if data.len() < WAL_HEADER_LEN {
+            return Ok(Vec::new())
+        }
+        if &data[..8] != MAGIC || u16::from_be_bytes([data[8], data[9]]) as usize != PAGE_SIZE {
+            return Err(Error::Expected("write-ahead log".to_string(), self.path.display().to_string()))
+        }
+        let mut result = Vec::new();
+        let mut pending = Vec::new();
+        let mut combined = 0u32;
+        let mut offset = WAL_HEADER_LEN;
+        while offset + FRAME_HEADER_LEN <= data.len() {
+            let kind = data[offset];
+            let page = u16::from_be_bytes([data[offset+2], data[offset+3]]);
+            let sum = u32::from_be_bytes([data[offset+4], data[offset+5], data[offset+6], data[offset+7]]);
+            offset += FRAME_HEADER_LEN;
+            match kind {
+                PAGE_FRAME => {
+                    if offset + PAGE_SIZE > data.len() {
+                        break;
+                    }
+                    let image = &data[offset..offset+PAGE_SIZE];
+                    if checksum(page, image) != sum {
+                        break;
+                    }
+                    combined = combine(combined, sum);
+                    pending.push((page, image.to_vec()));
+                    offset += PAGE_SIZE;
+                },
+                COMMIT_FRAME => {
+                    if page as usize != pending.len() || sum != combined {
+                        break;
+                    }
+                    result.append(&mut pending);
+                    combined = 0;
+                },
+                _ => break
+            }
+        }
+        Ok(result)
+    }
+
+    /// Empties the log, must only be called once the main file was synced.
+    pub fn truncate(&mut self) -> Result<()> {
+        self.file.set_len(0)?;
+        self.file.sync_all()?;
+        self.len = 0;
+        self.frames = 0;
+        self.committed = 0;
+        self.pending = 0;
+        self.combined = 0;
+        Ok(())
+    }
+}
+
+
+// FNV-1a over the page number and the page image.
+fn checksum(page: u16, data: &[u8]) -> u32 {
+    let mut hash = 0x811c9dc5u32;
+    for byte in page.to_be_bytes().iter().chain(data.iter()) {
+        hash ^= *byte as u32;
+        hash = hash.wrapping_mul(0x01000193);
+    }
+    hash
+}
+
+fn combine(combined: u32, sum: u32) -> u32 {
+    combined.rotate_left(5) ^ sum
+}
+
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::store::test::TempStore;
+
+    #[test]
+    fn wal_commit_recover() {
+        let path = TempStore::new("wal_recover");
+        let mut wal = Wal::open(&path).unwrap();
+        wal.commit(&[(1, vec![1u8; PAGE_SIZE]), (2, vec![2u8; PAGE_SIZE])]).unwrap();
+        wal.commit(&[(1, vec![3u8; PAGE_SIZE])]).unwrap();
+        assert_eq!(wal.frames(), 3);
+
+        let mut reopened = Wal::open(&path).unwrap();
+        let pages = reopened.recover().unwrap();
+        assert_eq!(pages.len(), 3);
+        assert_eq!(pages[0], (1, vec![1u8; PAGE_SIZE]));
+        assert_eq!(pages[2], (1, vec![3u8; PAGE_SIZE]));
+
+        wal.truncate().unwrap();
+        assert!(wal.recover().unwrap().is_empty());
+    }
+
+    #[test]
+    fn wal_ignores_incomplete() {
+        let path = TempStore::new("wal_torn");
+        let mut wal = Wal::open(&path).unwrap();
+        wal.commit(&[(1, vec![1u8; PAGE_SIZE])]).unwrap();
+        wal.commit(&[(2, vec![2u8; PAGE_SIZE]), (3, vec![3u8; PAGE_SIZE])]).unwrap();
+        // torn write: the second transaction lost its commit frame
+        let len = wal.len - FRAME_HEADER_LEN as u64;
+        wal.file.set_len(len).unwrap();
+        assert_eq!(wal.recover().unwrap(), vec![(1, vec![1u8; PAGE_SIZE])]);
+
+        // corrupted page image
+        wal.file.set_len(0).unwrap();
+        wal.len = 0;
+        wal.commit(&[(1, vec![1u8; PAGE_SIZE])]).unwrap();
+        wal.file.seek(SeekFrom::Start((WAL_HEADER_LEN + FRAME_HEADER_LEN + 10) as u64)).unwrap();
+        wal.file.write_all(&[9]).unwrap();
+        assert!(wal.recover().unwrap().is_empty());
+    }
+}